@@ -0,0 +1,38 @@
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{command, Runtime, UriSchemeContext};
+
+use crate::state::aero_pages::{AeroPage, AeroRequest, AERO_PAGES};
+
+fn not_found() -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(b"Not found".to_vec())
+        .unwrap()
+}
+
+/// Serve a request for the aero:// scheme. Every page gets the SvelteKit app shell,
+/// which renders the route of the same name; the app's built files are served as they
+/// are. Assets come from the bundle (or `frontendDist` in dev builds).
+pub fn aero_protocol<R: Runtime>(ctx: UriSchemeContext<'_, R>, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = match AeroRequest::from_path(request.uri().path()) {
+        AeroRequest::Page(_) => "/index.html".to_string(),
+        AeroRequest::Asset(path) => path,
+        AeroRequest::NotFound => return not_found(),
+    };
+    let Some(asset) = ctx.app_handle().asset_resolver().get(path) else {
+        return not_found();
+    };
+
+    let mut response = Response::builder().header(header::CONTENT_TYPE, asset.mime_type);
+    if let Some(csp) = asset.csp_header {
+        response = response.header("Content-Security-Policy", csp);
+    }
+    response.body(asset.bytes).unwrap()
+}
+
+/// Internal pages worth opening directly, for the about page
+#[command]
+pub fn aero_pages_get_all() -> Vec<AeroPage> {
+    AERO_PAGES.iter().filter(|page| page.listed).copied().collect()
+}
@@ -1,6 +1,7 @@
-use tauri::{command, AppHandle, Emitter, Manager};
+use tauri::{command, AppHandle, Manager};
 
-use crate::state::chrome_height::ChromeHeight;
+use super::layout::relayout_all;
+use crate::state::layout::LayoutInsets;
 use crate::storage::bookmarks::Bookmark;
 use crate::storage::database::Database;
 
@@ -93,27 +94,19 @@ pub fn bookmark_get(app: AppHandle, id: String) -> Result<Option<Bookmark>, Stri
 	db.bookmark_get(&id)
 }
 
-/// Toggle bookmarks bar visibility — updates chrome height and resizes webviews
+/// Toggle bookmarks bar visibility — updates the top inset and lays every window out again
 #[command]
 pub fn bookmark_toggle_bar(app: AppHandle, visible: bool) -> Result<(), String> {
-	let chrome_height = app.state::<ChromeHeight>();
-	chrome_height.set_bookmarks_bar(visible);
+	let layout = app.state::<LayoutInsets>();
+	let changed = layout.set_bookmarks_bar(visible);
 
 	// Save the setting
 	let db = app.state::<Database>();
 	db.settings_set("show_bookmarks_bar", if visible { "true" } else { "false" })?;
 
-	// Emit event so UI can update, then resize all tab webviews
-	let _ = app.emit("chrome_height_changed", chrome_height.get());
-	let _ = super::tabs::tab_resize_all(app.clone());
-
-	// Resize the UI webview to the new chrome height
-	if let Some(ui) = app.get_webview("browser-ui") {
-		let window = app.get_window("main").ok_or("Main window not found")?;
-		let size = window.inner_size().map_err(|e| e.to_string())?;
-		let scale = window.scale_factor().map_err(|e| e.to_string())?;
-		let width = size.width as f64 / scale;
-		let _ = ui.set_size(tauri::LogicalSize::new(width, chrome_height.get()));
+	// Emits layout_changed so the UI can update
+	if changed {
+		relayout_all(&app);
 	}
 
 	Ok(())
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use tauri::{command, AppHandle, Manager};
+
+use crate::events::{emit, AppEvent};
+use crate::state::tab_groups::GROUP_COLORS;
+use crate::state::tab_state::TabManager;
+use crate::storage::containers::Container;
+use crate::storage::database::Database;
+
+fn validate_color(color: &str) -> Result<(), String> {
+    if GROUP_COLORS.contains(&color) {
+        Ok(())
+    } else {
+        Err(format!("Invalid container colour: {}", color))
+    }
+}
+
+/// Where a container's cookies, storage and cache live:
+/// {app_data_dir}/default/containers/{id}, next to the profile database
+pub(super) fn container_data_dir(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data.join("default").join("containers").join(id))
+}
+
+/// Keep a remembered container only if it still exists — tabs from a deleted
+/// container come back in the default one
+pub(super) fn existing_container(app: &AppHandle, id: Option<String>) -> Option<String> {
+    let db = app.state::<Database>();
+    id.filter(|id| matches!(db.container_get(id), Ok(Some(_))))
+}
+
+/// Send the whole container list to every window
+fn emit_containers(app: &AppHandle) -> Result<Vec<Container>, String> {
+    let containers = app.state::<Database>().container_get_all()?;
+    emit(app, AppEvent::ContainersUpdated(containers.clone()));
+    Ok(containers)
+}
+
+/// All containers in list order
+#[command]
+pub fn container_get_all(app: AppHandle) -> Result<Vec<Container>, String> {
+    app.state::<Database>().container_get_all()
+}
+
+/// Create a container. Without a colour, the next one from the group palette is used.
+#[command]
+pub fn container_create(
+    app: AppHandle,
+    name: String,
+    color: Option<String>,
+) -> Result<Container, String> {
+    let db = app.state::<Database>();
+    let color = match color {
+        Some(color) => {
+            validate_color(&color)?;
+            color
+        }
+        None => GROUP_COLORS[db.container_get_all()?.len() % GROUP_COLORS.len()].to_string(),
+    };
+
+    let container = db.container_create(&name, &color)?;
+    emit_containers(&app)?;
+    Ok(container)
+}
+
+/// Rename and/or recolour a container
+#[command]
+pub fn container_update(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<Container, String> {
+    if let Some(color) = &color {
+        validate_color(color)?;
+    }
+
+    let container = app
+        .state::<Database>()
+        .container_update(&id, name.as_deref(), color.as_deref())?;
+    emit_containers(&app)?;
+    Ok(container)
+}
+
+/// Delete a container and its browsing data. Refused while tabs are open in it.
+#[command]
+pub fn container_delete(app: AppHandle, id: String) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    if tab_manager
+        .get_all_tabs()
+        .iter()
+        .any(|t| t.container_id.as_deref() == Some(id.as_str()))
+    {
+        return Err("Close the container's tabs before deleting it".to_string());
+    }
+
+    app.state::<Database>().container_delete(&id)?;
+    // Best effort — a locked file only leaves some disk space behind
+    let _ = std::fs::remove_dir_all(container_data_dir(&app, &id)?);
+
+    emit_containers(&app)?;
+    Ok(())
+}
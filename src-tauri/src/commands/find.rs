@@ -1,5 +1,6 @@
-use tauri::{command, AppHandle, Emitter, Manager};
+use tauri::{command, AppHandle, Manager, Webview, Window};
 
+use crate::events::{emit, AppEvent};
 use crate::state::tab_state::TabManager;
 
 /// Find text in the active tab's page.
@@ -8,12 +9,15 @@ use crate::state::tab_state::TabManager;
 #[command]
 pub async fn find_in_page(
     app: AppHandle,
+    window: Window,
     query: String,
     forward: bool,
     new_search: bool,
 ) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
 
     let webview = app
         .get_webview(&label)
@@ -76,21 +80,30 @@ pub async fn find_in_page(
     Ok(())
 }
 
-/// Internal command: receive find match count from content webview JS
+/// Internal command: receive find match count from content webview JS.
+/// Tagged with the tab's label so only the window showing that tab updates its find bar.
 #[command]
-pub fn __find_result(app: AppHandle, total: i32, current: i32) -> Result<(), String> {
-    let _ = app.emit("find_result", serde_json::json!({
-        "total": total,
-        "current": current,
-    }));
+pub fn __find_result(
+    app: AppHandle,
+    webview: Webview,
+    total: i32,
+    current: i32,
+) -> Result<(), String> {
+    emit(&app, AppEvent::FindResult {
+        label: webview.label().to_string(),
+        total,
+        current,
+    });
     Ok(())
 }
 
 /// Clear find highlighting in the active tab
 #[command]
-pub async fn find_clear(app: AppHandle) -> Result<(), String> {
+pub async fn find_clear(app: AppHandle, window: Window) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
 
     let webview = app
         .get_webview(&label)
@@ -100,10 +113,11 @@ pub async fn find_clear(app: AppHandle) -> Result<(), String> {
         .eval("window.getSelection()?.removeAllRanges()")
         .map_err(|e| e.to_string())?;
 
-    let _ = app.emit("find_result", serde_json::json!({
-        "total": 0,
-        "current": 0,
-    }));
+    emit(&app, AppEvent::FindResult {
+        label,
+        total: 0,
+        current: 0,
+    });
 
     Ok(())
 }
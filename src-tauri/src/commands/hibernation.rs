@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use tauri::{command, AppHandle, Manager};
+
+use super::webviews::tab_controller;
+use crate::state::tab_state::TabManager;
+use crate::storage::database::Database;
+
+/// How often background tabs are checked for idleness
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Read a numeric setting where 0 (or a missing/invalid value) means "no limit"
+fn limit_setting(app: &AppHandle, key: &str) -> Option<u64> {
+    app.try_state::<Database>()
+        .and_then(|db| db.settings_get(key).ok().flatten())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|&v| v > 0)
+}
+
+/// Discard background tabs that have been idle past `tab_discard_after_minutes`,
+/// and the least recently used ones while more than `max_live_tabs` are alive.
+pub fn discard_background_tabs(app: &AppHandle) {
+    let Some(tab_manager) = app.try_state::<TabManager>() else {
+        return;
+    };
+
+    let idle_after = limit_setting(app, "tab_discard_after_minutes")
+        .map(|minutes| Duration::from_secs(minutes * 60));
+    let max_live = limit_setting(app, "max_live_tabs").map(|max| max as usize);
+
+    for label in tab_manager.discard_candidates(Instant::now(), idle_after, max_live) {
+        let _ = tab_controller(app).discard(&label);
+    }
+}
+
+/// Check for idle background tabs once a minute for the life of the app
+pub fn spawn_idle_watcher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        discard_background_tabs(&app);
+    });
+}
+
+/// Discard a background tab right away (e.g. from the tab context menu)
+#[command]
+pub fn tab_discard(app: AppHandle, label: String) -> Result<(), String> {
+    tab_controller(&app).discard(&label)
+}
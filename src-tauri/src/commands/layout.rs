@@ -0,0 +1,79 @@
+use tauri::{command, AppHandle, LogicalPosition, LogicalSize, Manager, Window};
+
+use super::webviews::tab_controller;
+use super::windows::{browser_windows, get_browser_window, ui_label};
+use crate::events::{emit, AppEvent};
+use crate::state::layout::{Edge, Insets, LayoutInsets, Rect};
+
+/// Current layout insets (the defaults until the state is managed)
+pub fn get_insets(app: &AppHandle) -> Insets {
+    app.try_state::<LayoutInsets>()
+        .map(|layout| layout.get())
+        .unwrap_or_else(|| LayoutInsets::new().get())
+}
+
+/// Helper: a window's inner size in logical pixels
+fn window_size(window: &Window) -> Result<(f64, f64), String> {
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    Ok((size.width as f64 / scale, size.height as f64 / scale))
+}
+
+/// Helper: a window's content area — inside the insets, where tab webviews go
+pub fn get_content_rect(app: &AppHandle, window_id: &str) -> Result<Rect, String> {
+    let window = get_browser_window(app, window_id)?;
+    let (width, height) = window_size(&window)?;
+    Ok(get_insets(app).content_rect(width, height))
+}
+
+/// Size a window's browser UI webview to cover the insets
+pub fn layout_ui(app: &AppHandle, window_id: &str) -> Result<(), String> {
+    let window = get_browser_window(app, window_id)?;
+    let (width, height) = window_size(&window)?;
+    let rect = get_insets(app).ui_rect(width, height);
+
+    if let Some(ui) = app.get_webview(&ui_label(window_id)) {
+        let _ = ui.set_position(LogicalPosition::new(rect.x, rect.y));
+        let _ = ui.set_size(LogicalSize::new(rect.width, rect.height));
+    }
+    Ok(())
+}
+
+/// Show, hide and position a window's content webviews. The active tab fills the
+/// content area — or, when it is part of a split view, every pane of the split is
+/// shown side by side. Called whenever the active tab, a split, the insets or the
+/// window size change.
+pub fn layout_window(app: &AppHandle, window_id: &str) -> Result<(), String> {
+    tab_controller(app).layout(window_id)
+}
+
+/// Tell every UI the insets changed and lay every browser window out again
+pub fn relayout_all(app: &AppHandle) {
+    emit(app, AppEvent::LayoutChanged(get_insets(app)));
+    for window in browser_windows(app) {
+        let _ = layout_ui(app, window.label());
+        let _ = layout_window(app, window.label());
+    }
+}
+
+/// Reserve space on one edge of every window for a UI component (side panel,
+/// vertical tabs, infobar…). A size of 0 gives the space back.
+#[command]
+pub fn layout_set_inset(
+    app: AppHandle,
+    component: String,
+    edge: Edge,
+    size: f64,
+) -> Result<Insets, String> {
+    let layout = app.state::<LayoutInsets>();
+    if layout.set(&component, edge, size) {
+        relayout_all(&app);
+    }
+    Ok(layout.get())
+}
+
+/// Get the current layout insets
+#[command]
+pub fn layout_get_insets(app: AppHandle) -> Result<Insets, String> {
+    Ok(get_insets(&app))
+}
@@ -0,0 +1,125 @@
+use tauri::{AppHandle, Manager, Webview};
+
+use super::webviews::tab_controller;
+use crate::state::aero_pages::WEBVIEW_ORIGIN;
+use crate::state::load_error::{LoadError, LoadErrorKind};
+use crate::state::tab_state::TabManager;
+use crate::storage::database::Database;
+
+/// A tab's page failed to load: mark the visit as failed in history and show the error
+/// page in its place. Runs off the webview's event handler, which mustn't start a new
+/// navigation itself. Only web pages count — internal pages and cancelled loads don't.
+fn report_load_error(app: &AppHandle, label: &str, url: &str, kind: LoadErrorKind) {
+    let is_web_page = url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+        && !url.starts_with(WEBVIEW_ORIGIN);
+    if !is_web_page {
+        return;
+    }
+
+    let app = app.clone();
+    let label = label.to_string();
+    let error = LoadError::new(url, kind);
+    tauri::async_runtime::spawn(async move {
+        if app.state::<TabManager>().get_tab(&label).is_none() {
+            return;
+        }
+        if let Some(db) = app.try_state::<Database>() {
+            let _ = db.history_set_error(&error.url, error.kind.as_str());
+        }
+        let _ = tab_controller(&app).load_failed(&label, error);
+    });
+}
+
+/// Watch a tab's webview for navigations that fail (DNS, refused connection, TLS,
+/// timeout) and replace the engine's own error page with `aero://error`
+pub(super) fn watch_load_errors(app: &AppHandle, label: &str, webview: &Webview) {
+    let app = app.clone();
+    let label = label.to_string();
+    let _ = webview.with_webview(move |platform| {
+        watch_platform_webview(platform, app, label);
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn watch_platform_webview(platform: tauri::webview::PlatformWebview, app: AppHandle, label: String) {
+    use webkit2gtk::WebViewExt;
+
+    let view = platform.inner();
+    let (tls_app, tls_label) = (app.clone(), label.clone());
+    view.connect_load_failed(move |_, _, uri, error| match classify_gerror(error) {
+        Some(kind) => {
+            report_load_error(&app, &label, uri, kind);
+            true
+        }
+        None => false,
+    });
+    view.connect_load_failed_with_tls_errors(move |_, uri, _, _| {
+        report_load_error(&tls_app, &tls_label, uri, LoadErrorKind::Tls);
+        true
+    });
+}
+
+/// Classify a WebKitGTK load failure. Cancelled loads (stopped, replaced by another
+/// navigation, turned into a download) and policy decisions aren't failures.
+#[cfg(target_os = "linux")]
+fn classify_gerror(error: &webkit2gtk::glib::Error) -> Option<LoadErrorKind> {
+    use webkit2gtk::gio::{IOErrorEnum, ResolverError, TlsError};
+    use webkit2gtk::NetworkError;
+
+    if error.kind::<ResolverError>().is_some() {
+        return Some(LoadErrorKind::Dns);
+    }
+    if error.kind::<TlsError>().is_some() {
+        return Some(LoadErrorKind::Tls);
+    }
+    if let Some(io) = error.kind::<IOErrorEnum>() {
+        return match io {
+            IOErrorEnum::Cancelled => None,
+            IOErrorEnum::HostNotFound => Some(LoadErrorKind::Dns),
+            IOErrorEnum::ConnectionRefused => Some(LoadErrorKind::Refused),
+            IOErrorEnum::TimedOut => Some(LoadErrorKind::Timeout),
+            _ => Some(LoadErrorKind::Connection),
+        };
+    }
+    match error.kind::<NetworkError>() {
+        Some(NetworkError::Failed | NetworkError::Transport) => Some(LoadErrorKind::Connection),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn watch_platform_webview(platform: tauri::webview::PlatformWebview, app: AppHandle, label: String) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_WEB_ERROR_STATUS;
+    use webview2_com::{take_pwstr, NavigationCompletedEventHandler};
+    use windows::core::{BOOL, PWSTR};
+
+    unsafe {
+        let Ok(core) = platform.controller().CoreWebView2() else {
+            return;
+        };
+        let handler = NavigationCompletedEventHandler::create(Box::new(move |sender, args| {
+            let (Some(sender), Some(args)) = (sender, args) else {
+                return Ok(());
+            };
+            let mut success = BOOL::default();
+            args.IsSuccess(&mut success)?;
+            if success.as_bool() {
+                return Ok(());
+            }
+            let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+            args.WebErrorStatus(&mut status)?;
+            if let Some(kind) = LoadErrorKind::from_webview2_status(status.0) {
+                let mut source = PWSTR::null();
+                sender.Source(&mut source)?;
+                report_load_error(&app, &label, &take_pwstr(source), kind);
+            }
+            Ok(())
+        }));
+        let mut token = 0;
+        let _ = core.add_NavigationCompleted(&handler, &mut token);
+    }
+}
+
+/// Other platforms keep the engine's own error pages
+#[cfg(not(any(target_os = "linux", windows)))]
+fn watch_platform_webview(_platform: tauri::webview::PlatformWebview, _app: AppHandle, _label: String) {}
@@ -1,6 +1,20 @@
 pub mod tabs;
+pub mod layout;
+pub mod tab_groups;
+pub mod split_view;
+pub mod hibernation;
 pub mod navigation;
 pub mod find;
+pub mod zoom;
 pub mod settings;
 pub mod history;
 pub mod bookmarks;
+pub mod session;
+pub mod windows;
+pub mod webviews;
+pub mod watchdog;
+pub mod containers;
+pub mod omnibox;
+pub mod search_engines;
+pub mod load_errors;
+pub mod aero_pages;
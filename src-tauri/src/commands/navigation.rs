@@ -1,173 +1,116 @@
-use tauri::{command, AppHandle, Emitter, Manager};
-
-use crate::state::tab_state::TabManager;
-
-/// Convert an aero:// URL to the corresponding SvelteKit app route path.
-/// Returns None if the URL is not an aero:// URL.
-fn resolve_aero_url(url: &str) -> Option<String> {
-    if let Some(page) = url.strip_prefix("aero://") {
-        let page = page.trim_end_matches('/');
-        if page.is_empty() {
-            return Some("/".to_string());
-        }
-        return Some(format!("/{}", page));
-    }
-    None
-}
-
-/// Helper: update can_go_back/forward from nav_stack/nav_pos, then emit event.
-fn emit_nav_state(app: &AppHandle, label: &str) {
-    let tab_manager = app.state::<TabManager>();
-
-    let (can_go_back, can_go_forward) = {
-        let tabs = tab_manager.tabs.lock().unwrap();
-        if let Some(tab) = tabs.iter().find(|t| t.label == label) {
-            let back = tab.nav_pos > 0;
-            let fwd = tab.nav_pos < (tab.nav_stack.len() as i32 - 1);
-            (back, fwd)
-        } else {
-            (false, false)
-        }
-    };
-
-    tab_manager.update_tab(label, |tab| {
-        tab.can_go_back = can_go_back;
-        tab.can_go_forward = can_go_forward;
-    });
+use tauri::{command, AppHandle, Manager, Window};
 
-    let _ = app.emit("tab_updated", serde_json::json!({
-        "label": label,
-        "can_go_back": can_go_back,
-        "can_go_forward": can_go_forward,
-    }));
-}
+use super::omnibox::resolve;
+use super::session::save_session;
+use super::webviews::tab_controller;
+use crate::state::omnibox::InputKind;
+use crate::state::tab_state::{NavHistory, TabManager};
 
-/// Navigate the active tab (or a specific tab) to a URL
+/// Navigate the calling window's active tab (or a specific tab) to what was typed
+/// in the address bar — a URL, a bare host, or a search
 #[command]
 pub async fn navigate_to(
     app: AppHandle,
+    window: Window,
     url: String,
     label: Option<String>,
 ) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
     let target_label = label
-        .or_else(|| tab_manager.get_active_tab())
+        .or_else(|| tab_manager.get_active_tab(window.label()))
         .ok_or("No active tab")?;
 
-    let webview = app
-        .get_webview(&target_label)
-        .ok_or("Tab webview not found")?;
-
-    // Handle aero:// internal URLs — navigate to SvelteKit app route
-    if let Some(route) = resolve_aero_url(&url) {
-        // Construct the Tauri app URL for the given route
-        // On Linux: https://tauri.localhost/route, on Windows: tauri://localhost/route
-        let tauri_url = if cfg!(target_os = "windows") {
-            format!("tauri://localhost{}", route)
-        } else {
-            format!("https://tauri.localhost{}", route)
-        };
-        let parsed: url::Url = tauri_url
-            .parse()
-            .map_err(|e| format!("Invalid app URL: {}", e))?;
-        webview.navigate(parsed).map_err(|e| e.to_string())?;
-    } else {
-        let parsed_url: url::Url = url
-            .parse()
-            .map_err(|e| format!("Invalid URL: {}", e))?;
-        webview.navigate(parsed_url).map_err(|e| e.to_string())?;
+    let resolved = resolve(&app, &url)?;
+    tab_controller(&app).navigate(&target_label, &resolved.url)?;
+    // Searches aren't pages people come back to by typing them
+    if resolved.kind != InputKind::Search {
+        tab_manager.update_tab(&target_label, |tab| tab.nav_typed = true);
     }
+    save_session(&app);
 
-    // Push onto nav stack (truncate any forward history)
-    tab_manager.update_tab(&target_label, |tab| {
-        tab.url = url.clone();
-        tab.is_loading = true;
-        let new_pos = tab.nav_pos + 1;
-        tab.nav_stack.truncate(new_pos as usize);
-        tab.nav_stack.push(url.clone());
-        tab.nav_pos = new_pos;
-    });
-
-    emit_nav_state(&app, &target_label);
+    Ok(())
+}
 
+/// Move `delta` entries through a tab's history, saving the session if it moved
+fn traverse(app: &AppHandle, label: &str, delta: i32) -> Result<(), String> {
+    if tab_controller(app).go(label, delta)? {
+        save_session(app);
+    }
     Ok(())
 }
 
 /// Navigate back in the active tab
 #[command]
-pub async fn navigate_back(app: AppHandle) -> Result<(), String> {
+pub async fn navigate_back(app: AppHandle, window: Window) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
-
-    let webview = app
-        .get_webview(&label)
-        .ok_or("Tab webview not found")?;
-
-    // Only go back if we can
-    let can_back = {
-        let tabs = tab_manager.tabs.lock().unwrap();
-        tabs.iter().find(|t| t.label == label).map(|t| t.nav_pos > 0).unwrap_or(false)
-    };
-
-    if !can_back {
-        return Ok(());
-    }
-
-    tab_manager.update_tab(&label, |tab| {
-        tab.nav_traversing = true;
-        tab.nav_pos -= 1;
-    });
-
-    webview
-        .eval("window.history.back()")
-        .map_err(|e| e.to_string())?;
-
-    emit_nav_state(&app, &label);
-
-    Ok(())
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
+    traverse(&app, &label, -1)
 }
 
 /// Navigate forward in the active tab
 #[command]
-pub async fn navigate_forward(app: AppHandle) -> Result<(), String> {
+pub async fn navigate_forward(app: AppHandle, window: Window) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
-
-    let webview = app
-        .get_webview(&label)
-        .ok_or("Tab webview not found")?;
-
-    // Only go forward if we can
-    let can_fwd = {
-        let tabs = tab_manager.tabs.lock().unwrap();
-        tabs.iter().find(|t| t.label == label)
-            .map(|t| t.nav_pos < (t.nav_stack.len() as i32 - 1))
-            .unwrap_or(false)
-    };
-
-    if !can_fwd {
-        return Ok(());
-    }
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
+    traverse(&app, &label, 1)
+}
 
-    tab_manager.update_tab(&label, |tab| {
-        tab.nav_traversing = true;
-        tab.nav_pos += 1;
-    });
+/// Get the back/forward history of the calling window's active tab (or a specific tab)
+#[command]
+pub fn navigate_get_history(
+    app: AppHandle,
+    window: Window,
+    label: Option<String>,
+) -> Result<NavHistory, String> {
+    let tab_manager = app.state::<TabManager>();
+    let target_label = label
+        .or_else(|| tab_manager.get_active_tab(window.label()))
+        .ok_or("No active tab")?;
 
-    webview
-        .eval("window.history.forward()")
-        .map_err(|e| e.to_string())?;
+    let tab = tab_manager.get_tab(&target_label).ok_or("Tab not found")?;
+    Ok(tab.nav_history())
+}
 
-    emit_nav_state(&app, &label);
+/// Jump several steps through a tab's history to entry `index` (as listed by
+/// `navigate_get_history`) — the active tab's unless `label` is given
+#[command]
+pub async fn navigate_go_to_index(
+    app: AppHandle,
+    window: Window,
+    index: usize,
+    label: Option<String>,
+) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let target_label = label
+        .or_else(|| tab_manager.get_active_tab(window.label()))
+        .ok_or("No active tab")?;
 
+    if tab_controller(&app).go_to_index(&target_label, index)? {
+        save_session(&app);
+    }
     Ok(())
 }
 
 /// Refresh the active tab
 #[command]
-pub async fn navigate_refresh(app: AppHandle) -> Result<(), String> {
+pub async fn navigate_refresh(app: AppHandle, window: Window) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
+
+    // A crashed page can't reload itself — its webview is recreated instead
+    if tab_manager.get_tab(&label).is_some_and(|t| t.is_crashed) {
+        return tab_controller(&app).recover(&label);
+    }
+    // The error page stands in for the page that failed — try that one again
+    if tab_controller(&app).retry(&label)? {
+        return Ok(());
+    }
 
     let webview = app
         .get_webview(&label)
@@ -184,11 +127,20 @@ pub async fn navigate_refresh(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Load a tab's failed page again — the Retry button of the error page
+#[command]
+pub async fn navigate_retry(app: AppHandle, label: String) -> Result<(), String> {
+    tab_controller(&app).retry(&label)?;
+    Ok(())
+}
+
 /// Stop loading the active tab
 #[command]
-pub async fn navigate_stop(app: AppHandle) -> Result<(), String> {
+pub async fn navigate_stop(app: AppHandle, window: Window) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
 
     let webview = app
         .get_webview(&label)
@@ -207,9 +159,11 @@ pub async fn navigate_stop(app: AppHandle) -> Result<(), String> {
 
 /// Get the current URL of the active tab by querying the webview
 #[command]
-pub async fn navigate_get_url(app: AppHandle) -> Result<String, String> {
+pub async fn navigate_get_url(app: AppHandle, window: Window) -> Result<String, String> {
     let tab_manager = app.state::<TabManager>();
-    let label = tab_manager.get_active_tab().ok_or("No active tab")?;
+    let label = tab_manager
+        .get_active_tab(window.label())
+        .ok_or("No active tab")?;
 
     let tab = tab_manager
         .get_tab(&label)
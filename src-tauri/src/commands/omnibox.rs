@@ -0,0 +1,80 @@
+use std::time::Instant;
+
+use tauri::{command, AppHandle, Manager, Window};
+
+use crate::state::omnibox::{resolve_input, ResolvedInput};
+use crate::state::suggest::{suggest, OmniboxSuggestions, SuggestSources};
+use crate::state::tab_search::TabSearchResult;
+use crate::state::tab_state::TabManager;
+use crate::storage::database::Database;
+use crate::storage::search_engines::SearchEngine;
+
+/// Where new tabs open when the `new_tab_page` setting is missing or unusable
+const DEFAULT_NEW_TAB_URL: &str = "https://www.google.com/";
+
+fn setting(app: &AppHandle, key: &str) -> Option<String> {
+    let db = app.try_state::<Database>()?;
+    db.settings_get(key).ok().flatten()
+}
+
+fn search_engines(app: &AppHandle) -> Vec<SearchEngine> {
+    app.try_state::<Database>()
+        .and_then(|db| db.search_engine_get_all().ok())
+        .unwrap_or_default()
+}
+
+/// Work out what address bar input means, searching with the keyword's or the
+/// default search engine when it isn't a URL or host
+pub(super) fn resolve(app: &AppHandle, input: &str) -> Result<ResolvedInput, String> {
+    resolve_input(input, &search_engines(app)).ok_or_else(|| "Nothing to navigate to".to_string())
+}
+
+/// The URL to load for address bar input (see `resolve`)
+pub(super) fn resolve_url(app: &AppHandle, input: &str) -> Result<String, String> {
+    resolve(app, input).map(|resolved| resolved.url)
+}
+
+/// The page new tabs open on, from the `new_tab_page` setting
+pub(super) fn new_tab_url(app: &AppHandle) -> String {
+    setting(app, "new_tab_page")
+        .and_then(|page| resolve_url(app, &page).ok())
+        .unwrap_or_else(|| DEFAULT_NEW_TAB_URL.to_string())
+}
+
+/// How many pages each source offers before ranking
+const SUGGEST_CANDIDATES: usize = 30;
+
+/// Rank address bar suggestions for `query`: the typed URL or the inline-completed
+/// page, a search, then history, bookmarks and open tabs by frecency and match
+#[command]
+pub fn omnibox_suggest(
+    app: AppHandle,
+    window: Window,
+    query: String,
+    limit: Option<usize>,
+) -> Result<OmniboxSuggestions, String> {
+    // Sources are narrowed by the longest word; ranking checks every word
+    let Some(lookup) = query.split_whitespace().max_by_key(|w| w.len()) else {
+        return Ok(OmniboxSuggestions::default());
+    };
+    let db = app.state::<Database>();
+    let tab_manager = app.state::<TabManager>();
+
+    let history = db.history_suggest(lookup, SUGGEST_CANDIDATES as i64)?;
+    let bookmarks = db.bookmark_search(lookup, SUGGEST_CANDIDATES as i64)?;
+    let active = tab_manager.get_active_tab(window.label());
+    let tabs: Vec<TabSearchResult> = tab_manager
+        .search_tabs(lookup, Instant::now(), SUGGEST_CANDIDATES)
+        .into_iter()
+        .filter(|t| Some(&t.label) != active.as_ref())
+        .collect();
+    let engines = db.search_engine_get_all()?;
+
+    let sources = SuggestSources {
+        history: &history,
+        bookmarks: &bookmarks,
+        tabs: &tabs,
+        engines: &engines,
+    };
+    Ok(suggest(&query, &sources, limit.unwrap_or(8)))
+}
@@ -0,0 +1,84 @@
+use tauri::{command, AppHandle, Manager};
+
+use crate::events::{emit, AppEvent};
+use crate::state::opensearch::parse_opensearch;
+use crate::state::tab_state::TabManager;
+use crate::storage::database::Database;
+use crate::storage::search_engines::{SearchEngine, SearchEngineFields};
+
+/// Send the whole search engine list to every window
+fn emit_search_engines(app: &AppHandle) -> Result<Vec<SearchEngine>, String> {
+    let engines = app.state::<Database>().search_engine_get_all()?;
+    emit(app, AppEvent::SearchEnginesUpdated(engines.clone()));
+    Ok(engines)
+}
+
+/// All search engines in list order
+#[command]
+pub fn search_engine_get_all(app: AppHandle) -> Result<Vec<SearchEngine>, String> {
+    app.state::<Database>().search_engine_get_all()
+}
+
+/// Add a search engine. Its URL needs `%s` (or `{searchTerms}`) where the query goes.
+#[command]
+pub fn search_engine_add(app: AppHandle, engine: SearchEngineFields) -> Result<SearchEngine, String> {
+    let engine = app.state::<Database>().search_engine_add(&engine)?;
+    emit_search_engines(&app)?;
+    Ok(engine)
+}
+
+/// Replace a search engine's name, keyword and URLs
+#[command]
+pub fn search_engine_update(
+    app: AppHandle,
+    id: String,
+    engine: SearchEngineFields,
+) -> Result<SearchEngine, String> {
+    let engine = app.state::<Database>().search_engine_update(&id, &engine)?;
+    emit_search_engines(&app)?;
+    Ok(engine)
+}
+
+/// Use a search engine for address bar searches without a keyword
+#[command]
+pub fn search_engine_set_default(app: AppHandle, id: String) -> Result<(), String> {
+    app.state::<Database>().search_engine_set_default(&id)?;
+    emit_search_engines(&app)?;
+    Ok(())
+}
+
+/// Delete a search engine. The last one can't be deleted.
+#[command]
+pub fn search_engine_delete(app: AppHandle, id: String) -> Result<(), String> {
+    app.state::<Database>().search_engine_delete(&id)?;
+    emit_search_engines(&app)?;
+    Ok(())
+}
+
+/// Internal command: the injected helper found an OpenSearch description on the page
+/// (`<link rel="search">`) and sends its XML, fetched from `href`. Engines that aren't
+/// added yet are offered to the tab's window.
+#[command]
+pub fn __tab_opensearch_found(app: AppHandle, label: String, href: String, xml: String) -> Result<(), String> {
+    if app.state::<TabManager>().get_tab(&label).is_none() {
+        return Err("Tab not found".to_string());
+    }
+
+    let mut engine = parse_opensearch(&xml)?;
+    // Icons may be given relative to the description
+    engine.favicon = engine.favicon.and_then(|src| {
+        let base = url::Url::parse(&href).ok()?;
+        let icon = base.join(&src).ok()?;
+        matches!(icon.scheme(), "http" | "https" | "data").then(|| icon.to_string())
+    });
+
+    let known = app
+        .state::<Database>()
+        .search_engine_get_all()?
+        .iter()
+        .any(|e| e.url == engine.url);
+    if !known {
+        emit(&app, AppEvent::SearchEngineFound { label, engine });
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Manager};
+
+use super::containers::existing_container;
+use super::hibernation::discard_background_tabs;
+use super::windows::{next_window_id, MAIN_WINDOW_ID};
+use crate::state::tab_state::{TabInfo, TabManager};
+use crate::storage::database::Database;
+use crate::storage::session::SessionTab;
+
+/// Value of the `restore_on_startup` setting that reopens the previous session
+const RESTORE_CONTINUE: &str = "continue";
+
+fn to_session_tab(tab: &TabInfo, is_active: bool) -> SessionTab {
+    SessionTab {
+        window_id: tab.window_id.clone(),
+        url: tab.url.clone(),
+        title: tab.title.clone(),
+        favicon: tab.favicon.clone(),
+        nav_stack: tab.nav_stack.clone(),
+        nav_pos: tab.nav_pos,
+        is_active,
+        is_pinned: tab.is_pinned,
+        container_id: tab.container_id.clone(),
+    }
+}
+
+/// Snapshot every open tab into the database.
+/// Called after any tab change so a crash loses at most the last action.
+pub fn save_session(app: &AppHandle) {
+    let (Some(tab_manager), Some(db)) = (app.try_state::<TabManager>(), app.try_state::<Database>())
+    else {
+        return;
+    };
+
+    let tabs: Vec<SessionTab> = tab_manager
+        .get_all_tabs()
+        .iter()
+        .map(|t| to_session_tab(t, tab_manager.is_active(&t.label)))
+        .collect();
+
+    let _ = db.session_save(&tabs);
+}
+
+/// Recreate the previous session's windows and tabs if `restore_on_startup` is "continue".
+/// The first saved window's tabs go into the main window; every other saved
+/// window gets a new window. Leaves the tab list empty otherwise, so the UI
+/// opens its usual first tab.
+pub async fn restore_session(app: AppHandle) -> Result<(), String> {
+    let snapshot = {
+        let db = app.state::<Database>();
+        if db.settings_get("restore_on_startup")?.as_deref() != Some(RESTORE_CONTINUE) {
+            return Ok(());
+        }
+        db.session_load()?
+    };
+
+    // Saved window ID -> window it is restored into
+    let mut windows: HashMap<String, String> = HashMap::new();
+    let mut active_labels = Vec::new();
+    for saved in snapshot {
+        let window_id = match windows.get(&saved.window_id) {
+            Some(id) => id.clone(),
+            None => {
+                let id = if windows.is_empty() {
+                    MAIN_WINDOW_ID.to_string()
+                } else {
+                    let id = next_window_id();
+                    crate::create_browser_window(&app, &id).map_err(|e| e.to_string())?;
+                    id
+                };
+                windows.insert(saved.window_id.clone(), id.clone());
+                id
+            }
+        };
+
+        // Skip tabs whose URL no longer loads rather than dropping the whole session.
+        // The session is saved once every tab is back with its history.
+        let created = super::tabs::open_tab(
+            &app,
+            &window_id,
+            &saved.url,
+            saved.is_pinned,
+            None,
+            false,
+            existing_container(&app, saved.container_id.clone()),
+        );
+        let Ok(info) = created else {
+            continue;
+        };
+
+        // The new webview only knows the current page, so mark the stack as restored
+        let tab_manager = app.state::<TabManager>();
+        tab_manager.update_tab(&info.label, |tab| {
+            tab.title = saved.title.clone();
+            tab.favicon = saved.favicon.clone();
+            tab.restore_nav(saved.nav_stack.clone(), saved.nav_pos);
+        });
+
+        if saved.is_active {
+            active_labels.push(info.label);
+        }
+    }
+
+    for label in active_labels {
+        super::tabs::tab_set_active(app.clone(), label).await?;
+    }
+
+    discard_background_tabs(&app);
+    save_session(&app);
+    Ok(())
+}
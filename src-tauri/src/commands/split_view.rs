@@ -0,0 +1,84 @@
+use tauri::{command, AppHandle, Manager, Window};
+
+use super::layout::layout_window;
+use super::tabs::tab_set_active;
+use super::webviews::tab_controller;
+use crate::events::{emit, AppEvent};
+use crate::state::split_view::SplitView;
+use crate::state::tab_state::TabManager;
+
+fn emit_removed(app: &AppHandle, split_id: &str) {
+    emit(app, AppEvent::TabSplitRemoved { split_id: split_id.to_string() });
+}
+
+/// Drop split members that were closed or moved away and tell the UI
+pub fn prune_splits(app: &AppHandle) {
+    tab_controller(app).prune_splits();
+}
+
+/// Show two or more tabs of one window side by side, left to right in the given order.
+/// `ratios` are the panes' shares of the content width (equal when omitted).
+/// The split stays put across tab switches: it is shown whenever one of its tabs is active.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_split(
+    app: AppHandle,
+    labels: Vec<String>,
+    ratios: Option<Vec<f64>>,
+) -> Result<SplitView, String> {
+    let tab_manager = app.state::<TabManager>();
+    let (split, dissolved) = tab_manager.add_split(&labels, ratios.as_deref())?;
+
+    for split_id in &dissolved {
+        emit_removed(&app, split_id);
+    }
+    emit(&app, AppEvent::TabSplitUpdated(split.clone()));
+
+    // Keep focus on the active tab if it joined the split, otherwise focus the first pane
+    let focus = tab_manager
+        .get_active_tab(&split.window_id)
+        .filter(|label| split.labels.contains(label))
+        .unwrap_or_else(|| split.labels[0].clone());
+    tab_set_active(app.clone(), focus).await?;
+
+    Ok(split)
+}
+
+/// Dissolve the split a tab is part of. The active tab goes back to filling the window.
+#[command]
+pub fn tab_unsplit(app: AppHandle, label: String) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let split = tab_manager.split_of(&label).ok_or("Tab is not in a split")?;
+    tab_manager.remove_split(&split.id);
+
+    emit_removed(&app, &split.id);
+    layout_window(&app, &split.window_id)
+}
+
+/// Resize a split's panes, e.g. while its divider is dragged
+#[command]
+pub fn tab_split_set_ratios(
+    app: AppHandle,
+    split_id: String,
+    ratios: Vec<f64>,
+) -> Result<SplitView, String> {
+    let tab_manager = app.state::<TabManager>();
+    let split = tab_manager
+        .set_split_ratios(&split_id, &ratios)
+        .ok_or("Split not found")?;
+
+    layout_window(&app, &split.window_id)?;
+    emit(&app, AppEvent::TabSplitUpdated(split.clone()));
+    Ok(split)
+}
+
+/// Get the calling window's splits
+#[command]
+pub fn tab_split_get_all(app: AppHandle, window: Window) -> Result<Vec<SplitView>, String> {
+    let tab_manager = app.state::<TabManager>();
+    Ok(tab_manager
+        .get_splits()
+        .into_iter()
+        .filter(|s| s.window_id == window.label())
+        .collect())
+}
@@ -0,0 +1,208 @@
+use tauri::{command, AppHandle, Manager};
+
+use super::session::save_session;
+use super::webviews::tab_controller;
+use super::windows::get_browser_window;
+use crate::events::{emit, emit_tab_update, AppEvent, TabUpdate};
+use crate::state::tab_groups::{next_group_id, TabGroup, GROUP_COLORS};
+use crate::state::tab_state::TabManager;
+
+fn validate_color(color: &str) -> Result<(), String> {
+    if GROUP_COLORS.contains(&color) {
+        Ok(())
+    } else {
+        Err(format!("Invalid group colour: {}", color))
+    }
+}
+
+/// Tell the UI a tab joined or left a group (and moved, if it did)
+fn emit_membership(app: &AppHandle, label: &str, old_index: usize, new_index: usize) {
+    let tab_manager = app.state::<TabManager>();
+    let group_id = tab_manager.get_tab(label).and_then(|t| t.group_id);
+
+    emit_tab_update(app, TabUpdate {
+        group_id: Some(group_id),
+        ..TabUpdate::new(label)
+    });
+    if old_index != new_index {
+        emit(app, AppEvent::TabReordered {
+            label: label.to_string(),
+            old_index,
+            new_index,
+        });
+    }
+}
+
+/// Drop groups left without members and tell the UI
+pub fn prune_groups(app: &AppHandle) {
+    tab_controller(app).prune_groups();
+}
+
+/// Create a group from one or more tabs. Members are gathered next to the first tab.
+#[command]
+pub fn tab_group_create(
+    app: AppHandle,
+    labels: Vec<String>,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<TabGroup, String> {
+    let tab_manager = app.state::<TabManager>();
+
+    if labels.is_empty() {
+        return Err("A group needs at least one tab".to_string());
+    }
+    for label in &labels {
+        let tab = tab_manager.get_tab(label).ok_or("Tab not found")?;
+        if tab.is_pinned {
+            return Err("Pinned tabs can't be grouped".to_string());
+        }
+    }
+
+    // Cycle through the palette so neighbouring groups differ by default
+    let color = match color {
+        Some(color) => {
+            validate_color(&color)?;
+            color
+        }
+        None => GROUP_COLORS[tab_manager.get_groups().len() % GROUP_COLORS.len()].to_string(),
+    };
+
+    let group = TabGroup {
+        id: next_group_id(),
+        name: name.unwrap_or_default(),
+        color,
+        collapsed: false,
+    };
+    tab_manager.add_group(group.clone());
+    emit(&app, AppEvent::TabGroupUpdated(group.clone()));
+
+    for label in &labels {
+        let (old_index, new_index) = tab_manager.group_add_tab(label, &group.id)?;
+        emit_membership(&app, label, old_index, new_index);
+    }
+
+    // Tabs may have been taken from other groups
+    prune_groups(&app);
+    save_session(&app);
+
+    Ok(group)
+}
+
+/// Add a tab to an existing group
+#[command]
+pub fn tab_group_add_tab(app: AppHandle, group_id: String, label: String) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let (old_index, new_index) = tab_manager.group_add_tab(&label, &group_id)?;
+
+    emit_membership(&app, &label, old_index, new_index);
+    prune_groups(&app);
+    save_session(&app);
+
+    Ok(())
+}
+
+/// Remove a tab from its group. The group is dropped once it has no tabs left.
+#[command]
+pub fn tab_group_remove_tab(app: AppHandle, label: String) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let (old_index, new_index) = tab_manager.group_remove_tab(&label)?;
+
+    emit_membership(&app, &label, old_index, new_index);
+    prune_groups(&app);
+    save_session(&app);
+
+    Ok(())
+}
+
+/// Rename and/or recolour a group
+#[command]
+pub fn tab_group_update(
+    app: AppHandle,
+    group_id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<TabGroup, String> {
+    if let Some(color) = &color {
+        validate_color(color)?;
+    }
+
+    let tab_manager = app.state::<TabManager>();
+    let group = tab_manager
+        .update_group(&group_id, |group| {
+            if let Some(name) = name {
+                group.name = name;
+            }
+            if let Some(color) = color {
+                group.color = color;
+            }
+        })
+        .ok_or("Group not found")?;
+
+    emit(&app, AppEvent::TabGroupUpdated(group.clone()));
+    Ok(group)
+}
+
+/// Collapse or expand a group. Collapsing hides its tabs' webviews and moves
+/// focus to the nearest visible tab (opening a new one if there is none).
+#[command]
+pub async fn tab_group_set_collapsed(
+    app: AppHandle,
+    group_id: String,
+    collapsed: bool,
+) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let group = tab_manager
+        .update_group(&group_id, |group| group.collapsed = collapsed)
+        .ok_or("Group not found")?;
+
+    if collapsed {
+        let members = tab_manager.group_members(&group_id);
+        let active_in_group = members.iter().any(|label| tab_manager.is_active(label));
+
+        if active_in_group {
+            match tab_manager.get_adjacent_outside_group(&group_id) {
+                Some(next) => super::tabs::tab_set_active(app.clone(), next).await?,
+                None => {
+                    let window_id = tab_manager
+                        .get_tab(&members[0])
+                        .map(|t| t.window_id)
+                        .ok_or("Tab not found")?;
+                    let window = get_browser_window(&app, &window_id)?;
+                    super::tabs::tab_create(app.clone(), window, None, None, None, None, None).await?;
+                }
+            }
+        }
+
+        for label in &members {
+            if let Some(wv) = app.get_webview(label) {
+                let _ = wv.hide();
+            }
+        }
+    }
+
+    emit(&app, AppEvent::TabGroupUpdated(group));
+    Ok(())
+}
+
+/// Close every tab in a group, and with it the group
+#[command]
+pub async fn tab_group_close(app: AppHandle, group_id: String) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    if tab_manager.get_group(&group_id).is_none() {
+        return Err("Group not found".to_string());
+    }
+
+    for label in tab_manager.group_members(&group_id) {
+        super::tabs::tab_close(app.clone(), label, None).await?;
+    }
+
+    prune_groups(&app);
+    Ok(())
+}
+
+/// Get all tab groups
+#[command]
+pub fn tab_group_get_all(app: AppHandle) -> Result<Vec<TabGroup>, String> {
+    let tab_manager = app.state::<TabManager>();
+    Ok(tab_manager.get_groups())
+}
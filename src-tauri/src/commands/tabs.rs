@@ -1,100 +1,190 @@
-use tauri::{command, AppHandle, Emitter, Manager, WebviewUrl};
+use tauri::{command, AppHandle, Manager, WebviewUrl, Window};
 use tauri::webview::NewWindowResponse;
 use tauri::{LogicalPosition, LogicalSize};
 
-use crate::state::chrome_height::ChromeHeight;
-use crate::state::tab_state::{next_tab_label, TabInfo, TabManager};
+use super::containers::{container_data_dir, existing_container};
+use super::hibernation::discard_background_tabs;
+use super::layout::{get_content_rect, layout_window};
+use super::load_errors::watch_load_errors;
+use super::omnibox::{new_tab_url, resolve_url};
+use super::session::save_session;
+use super::tab_groups::prune_groups;
+use super::watchdog::{watch_crashes, WATCHDOG_SCRIPT};
+use super::webviews::{tab_controller, to_webview_url};
+use super::windows::{get_browser_window, ui_label};
+use super::zoom::restore_zoom;
+use crate::events::{emit, emit_tab_update, AppEvent, TabUpdate};
+use crate::state::aero_pages::{AeroUrl, WEBVIEW_ORIGIN};
+use crate::state::tab_close::CloseSelector;
+use crate::state::tab_search::TabSearchResult;
+use crate::state::tab_state::{SpaNavigation, TabInfo, TabManager};
+use crate::storage::closed_tabs::ClosedTab;
 use crate::storage::database::Database;
 
-/// Default chrome height in logical pixels (tab bar + toolbar)
-/// The actual height is dynamic via ChromeHeight state (changes when bookmarks bar toggles)
-const CHROME_HEIGHT: f64 = 76.0;
+/// Media observer injected into every document of a content webview before page scripts run.
+/// Reports whether the page is playing sound via `__tab_audio_update`, which answers with
+/// the tab's mute state, and keeps media elements muted — including ones created or started
+/// later — while the tab is muted. `__AERO_LABEL__` is replaced with the tab label.
+const MEDIA_SCRIPT: &str = r#"
+(function() {
+    if (window.__aeroMedia) return;
+    window.__aeroMedia = true;
+    var label = '__AERO_LABEL__';
+    var muted = false;
+    var audible = false;
+    // Media elements never attached to the document (new Audio()) are tracked here
+    var detached = new Set();
+
+    function mediaElements() {
+        var all = Array.prototype.slice.call(document.querySelectorAll('video, audio'));
+        detached.forEach(function(el) { if (all.indexOf(el) < 0) all.push(el); });
+        return all;
+    }
 
-/// Convert a Tauri app URL (tauri://localhost/settings) back to aero:// format.
-/// Returns the original URL if it's not a Tauri app URL.
-fn to_aero_url(url: &str) -> String {
-    // Tauri app URLs look like: tauri://localhost/path or https://tauri.localhost/path
-    let path = if let Some(path) = url.strip_prefix("tauri://localhost") {
-        Some(path)
-    } else if let Some(path) = url.strip_prefix("https://tauri.localhost") {
-        Some(path)
-    } else {
-        None
+    // Remember what the page wanted so unmuting the tab restores it
+    function applyMute(el) {
+        if (muted) {
+            if (el.__aeroPageMuted === undefined) el.__aeroPageMuted = el.muted;
+            el.muted = true;
+        } else if (el.__aeroPageMuted !== undefined) {
+            el.muted = el.__aeroPageMuted;
+            el.__aeroPageMuted = undefined;
+        }
+    }
+
+    function isAudible(el) {
+        var pageMuted = el.__aeroPageMuted !== undefined ? el.__aeroPageMuted : el.muted;
+        return !el.paused && !el.ended && el.volume > 0 && !pageMuted;
+    }
+
+    function send() {
+        window.__TAURI_INTERNALS__?.invoke('__tab_audio_update', {
+            label: label,
+            audible: audible
+        }).then(function(m) { window.__aeroSetMuted(m); }).catch(function(){});
+    }
+
+    function report() {
+        var now = mediaElements().some(isAudible);
+        if (now === audible) return;
+        audible = now;
+        send();
+    }
+
+    window.__aeroSetMuted = function(m) {
+        if (muted === !!m) return;
+        muted = !!m;
+        mediaElements().forEach(applyMute);
     };
 
-    if let Some(path) = path {
-        let page = path.trim_start_matches('/').trim_end_matches('/');
-        // Only convert known internal pages
-        if page == "settings" || page == "history" || page == "bookmarks" {
-            return format!("aero://{}", page);
+    function onMediaEvent(e) {
+        var el = e.target;
+        if (!(el instanceof HTMLMediaElement)) return;
+        if (e.type === 'play' || e.type === 'playing') applyMute(el);
+        // The page unmuting an element mustn't get past a muted tab
+        if (e.type === 'volumechange' && muted && !el.muted) {
+            el.__aeroPageMuted = false;
+            el.muted = true;
         }
+        report();
     }
 
-    url.to_string()
+    var events = ['play', 'playing', 'pause', 'ended', 'emptied', 'volumechange'];
+    events.forEach(function(type) {
+        document.addEventListener(type, onMediaEvent, true);
+    });
+
+    var play = HTMLMediaElement.prototype.play;
+    HTMLMediaElement.prototype.play = function() {
+        if (!this.isConnected && !detached.has(this)) {
+            detached.add(this);
+            var el = this;
+            events.forEach(function(type) { el.addEventListener(type, onMediaEvent); });
+        }
+        applyMute(this);
+        return play.apply(this, arguments);
+    };
+
+    // Fetch the mute state for this document straight away
+    send();
+})();
+"#;
+
+/// Escape text for use inside HTML markup
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
 }
 
-/// Helper: get the content area size (below the chrome)
-fn get_content_size(app: &AppHandle) -> Result<(f64, f64), String> {
-    let window = app.get_window("main").ok_or("Main window not found")?;
-    let size = window.inner_size().map_err(|e| e.to_string())?;
-    let scale = window.scale_factor().map_err(|e| e.to_string())?;
-    let width = size.width as f64 / scale;
-    let height = size.height as f64 / scale;
-    // Use dynamic chrome height if available, fall back to constant
-    let chrome_h = app
-        .try_state::<ChromeHeight>()
-        .map(|ch| ch.get())
-        .unwrap_or(CHROME_HEIGHT);
-    Ok((width, (height - chrome_h).max(0.0)))
+/// Convert the URL a webview reports back to what the tab shows: internal pages
+/// as aero:// URLs (see the page registry), anything else unchanged
+fn to_aero_url(url: &str) -> String {
+    AeroUrl::from_webview_url(url).map_or_else(|| url.to_string(), |page| page.display())
 }
 
-/// Get the current chrome height (for positioning content webviews)
-fn get_chrome_height(app: &AppHandle) -> f64 {
-    app.try_state::<ChromeHeight>()
-        .map(|ch| ch.get())
-        .unwrap_or(CHROME_HEIGHT)
+/// Record a page visit in history (skipping internal and blank pages). `typed`
+/// visits were asked for in the address bar and rank higher in its suggestions.
+fn record_visit(app: &AppHandle, url: &str, title: Option<&str>, typed: bool) {
+    if url.starts_with("aero://") || url.starts_with(WEBVIEW_ORIGIN) || url.starts_with("about:") {
+        return;
+    }
+    if let Some(db) = app.try_state::<Database>() {
+        let _ = db.history_add_visit(url, title);
+        if typed {
+            let _ = db.history_mark_typed(url);
+        }
+    }
 }
 
-/// Create a new tab webview and register it in state.
-/// MUST be async to avoid WebView2 deadlock on Windows.
-#[command]
-pub async fn tab_create(
-    app: AppHandle,
-    url: Option<String>,
-) -> Result<TabInfo, String> {
-    let label = next_tab_label();
-    let url = url.unwrap_or_else(|| "https://www.google.com".to_string());
-
-    let webview_url = if url.starts_with("aero://") {
-        // Internal page — map to SvelteKit route
-        let page = url.strip_prefix("aero://").unwrap().trim_end_matches('/');
-        let route = if page.is_empty() { "/".to_string() } else { format!("/{}", page) };
-        WebviewUrl::App(route.into())
-    } else if url.starts_with("http://") || url.starts_with("https://") {
-        WebviewUrl::External(url.parse().map_err(|e| format!("Invalid URL: {}", e))?)
+/// Build a content webview for `url` under `label` and attach it below a window's chrome.
+/// Used for new tabs and to bring a discarded tab back to life. Tabs in a container
+/// keep their cookies and storage in the container's own data directory.
+pub(super) fn open_tab_webview(
+    app: &AppHandle,
+    window_id: &str,
+    label: &str,
+    url: &str,
+    container_id: Option<&str>,
+) -> Result<(), String> {
+    // Internal pages load from the aero:// protocol handler
+    let webview_url = if AeroUrl::parse(url).is_some() {
+        WebviewUrl::CustomProtocol(to_webview_url(url)?)
     } else {
-        let full_url = format!("https://{}", url);
-        WebviewUrl::External(
-            full_url
-                .parse()
-                .map_err(|e| format!("Invalid URL: {}", e))?,
-        )
+        WebviewUrl::External(to_webview_url(url)?)
     };
 
-    let window = app
-        .get_window("main")
-        .ok_or("Main window not found")?;
+    let window = get_browser_window(app, window_id)?;
 
-    let (width, content_height) = get_content_size(&app)?;
+    let area = get_content_rect(app, window_id)?;
 
     // Clone for event handlers
-    let label_for_load = label.clone();
+    let label_for_load = label.to_string();
     let app_for_load = app.clone();
 
+    let label_for_new_window = label.to_string();
     let app_for_new_window = app.clone();
-    let webview = tauri::webview::WebviewBuilder::new(&label, webview_url)
+    let mut builder = tauri::webview::WebviewBuilder::new(label, webview_url);
+    if let Some(id) = container_id {
+        builder = builder.data_directory(container_data_dir(app, id)?);
+    }
+    let webview = builder
+        .initialization_script(MEDIA_SCRIPT.replace("__AERO_LABEL__", label))
+        .initialization_script(WATCHDOG_SCRIPT.replace("__AERO_LABEL__", label))
         .on_new_window(move |url, _features| {
-            let _ = app_for_new_window.emit("open_in_new_tab", url.to_string());
+            // Open in whichever window owns the tab now
+            let window_id = app_for_new_window
+                .state::<TabManager>()
+                .get_tab(&label_for_new_window)
+                .map(|t| t.window_id);
+            emit(&app_for_new_window, AppEvent::OpenInNewTab {
+                url: url.to_string(),
+                window_id,
+                opener: label_for_new_window.clone(),
+                background: false,
+            });
             NewWindowResponse::Deny
         })
         .on_page_load(move |webview, payload| {
@@ -104,77 +194,40 @@ pub async fn tab_create(
             };
 
             let tab_manager = app_for_load.state::<TabManager>();
-            let url_str = to_aero_url(&payload.url().to_string());
+            let url_str = to_aero_url(payload.url().as_ref());
             let label_clone = label_for_load.clone();
 
-            tab_manager.update_tab(&label_clone, |tab| {
-                tab.is_loading = loading;
-                tab.url = url_str.clone();
-
-                // On page finish: track navigation in nav_stack
-                if !loading {
-                    if tab.nav_traversing {
-                        // This was a back/forward — nav_pos already updated, just clear flag
-                        tab.nav_traversing = false;
-                    } else {
-                        // This is a new navigation (link click, redirect after initial load, etc.)
-                        // Only push if URL differs from current stack position
-                        let current = if tab.nav_pos >= 0 && (tab.nav_pos as usize) < tab.nav_stack.len() {
-                            Some(tab.nav_stack[tab.nav_pos as usize].as_str())
-                        } else {
-                            None
-                        };
-                        if current != Some(&url_str) {
-                            let new_pos = tab.nav_pos + 1;
-                            tab.nav_stack.truncate(new_pos as usize);
-                            tab.nav_stack.push(url_str.clone());
-                            tab.nav_pos = new_pos;
-                        }
-                    }
-                    tab.can_go_back = tab.nav_pos > 0;
-                    tab.can_go_forward = (tab.nav_pos as usize) < tab.nav_stack.len() - 1;
-                }
-            });
+            // On page finish the load goes into the tab's nav_stack
+            tab_controller(&app_for_load).page_load(&label_clone, &url_str, loading);
+
+            // Each page comes up at its site's zoom level
+            restore_zoom(&app_for_load, &label_clone, &url_str);
 
-            // Read nav state for the event
-            let (can_go_back, can_go_forward) = tab_manager
-                .get_tab(&label_clone)
-                .map(|t| (t.can_go_back, t.can_go_forward))
-                .unwrap_or((false, false));
-
-            let _ = app_for_load.emit("tab_updated", serde_json::json!({
-                "label": label_clone,
-                "loading": loading,
-                "url": url_str,
-                "can_go_back": can_go_back,
-                "can_go_forward": can_go_forward,
-            }));
-
-            // Record page visit in history (skip internal and blank pages)
-            if !loading
-                && !url_str.starts_with("aero://")
-                && !url_str.starts_with("about:")
-                && !url_str.starts_with("tauri://")
-                && !url_str.starts_with("https://tauri.localhost")
-            {
-                if let Some(db) = app_for_load.try_state::<Database>() {
-                    let title = tab_manager
-                        .get_tab(&label_clone)
-                        .and_then(|t| {
-                            if t.title.is_empty() || t.title == "New Tab" {
-                                None
-                            } else {
-                                Some(t.title)
-                            }
-                        });
-                    let _ = db.history_add_visit(&url_str, title.as_deref());
+            if !loading {
+                save_session(&app_for_load);
+
+                let title = tab_manager
+                    .get_tab(&label_clone)
+                    .map(|t| t.title)
+                    .filter(|title| !title.is_empty() && title != "New Tab");
+                let mut typed = false;
+                tab_manager.update_tab(&label_clone, |tab| typed = std::mem::take(&mut tab.nav_typed));
+                record_visit(&app_for_load, &url_str, title.as_deref(), typed);
+
+                // A failed load can still finish — the visit goes in history as failed
+                let failed = tab_manager
+                    .get_tab(&label_clone)
+                    .and_then(|t| t.load_error)
+                    .filter(|e| e.url == url_str);
+                if let (Some(error), Some(db)) = (failed, app_for_load.try_state::<Database>()) {
+                    let _ = db.history_set_error(&error.url, error.kind.as_str());
                 }
             }
 
             // When page finishes loading, inject Aero helpers (title + hover)
             if !loading {
                 let label_inject = label_clone.clone();
-                let _ = webview.eval(&format!(
+                let _ = webview.eval(format!(
                     r#"
                     (function() {{
                         if (window.__aeroInjected) return;
@@ -238,6 +291,21 @@ pub async fn tab_create(
                             setTimeout(hideLoading, 2000);
                         }}
 
+                        // --- Ctrl/middle-click opens links in a background tab ---
+                        function openInTab(e) {{
+                            var modified = e.ctrlKey || e.metaKey || e.button === 1;
+                            var a = modified && e.target.closest('a[href]');
+                            if (!a || !/^https?:/.test(a.href) || !window.__TAURI_INTERNALS__) return;
+                            e.preventDefault();
+                            window.__TAURI_INTERNALS__.invoke('__tab_open_link', {{
+                                label: label,
+                                url: a.href,
+                                background: !e.shiftKey
+                            }}).catch(function(){{}});
+                        }}
+                        document.addEventListener('click', openInTab, true);
+                        document.addEventListener('auxclick', openInTab, true);
+
                         var lastHref = '';
                         document.addEventListener('mouseover', function(e) {{
                             var a = e.target.closest('a[href]');
@@ -252,6 +320,60 @@ pub async fn tab_create(
                                 }}
                             }}
                         }}, true);
+
+                        // --- Single-page app navigations ---
+                        function sendNavigation(kind) {{
+                            window.__TAURI_INTERNALS__?.invoke('__tab_spa_navigate', {{
+                                label: label,
+                                url: window.location.href,
+                                kind: kind
+                            }}).catch(function(){{}});
+                        }}
+                        var pushState = history.pushState;
+                        history.pushState = function() {{
+                            var result = pushState.apply(this, arguments);
+                            sendNavigation('push');
+                            return result;
+                        }};
+                        var replaceState = history.replaceState;
+                        // Pages replace state constantly — only a new URL matters
+                        history.replaceState = function() {{
+                            var before = window.location.href;
+                            var result = replaceState.apply(this, arguments);
+                            if (window.location.href !== before) sendNavigation('replace');
+                            return result;
+                        }};
+                        window.addEventListener('popstate', function() {{ sendNavigation('pop'); }});
+                        window.addEventListener('hashchange', function() {{ sendNavigation('pop'); }});
+
+                        // --- Scroll position, kept with the history entry ---
+                        var scrollTimer = null;
+                        window.addEventListener('scroll', function() {{
+                            clearTimeout(scrollTimer);
+                            scrollTimer = setTimeout(function() {{
+                                window.__TAURI_INTERNALS__?.invoke('__tab_scroll_update', {{
+                                    label: label,
+                                    url: window.location.href,
+                                    scrollY: window.scrollY
+                                }}).catch(function(){{}});
+                            }}, 250);
+                        }}, {{ passive: true }});
+
+                        // --- OpenSearch discovery: offer the site's search engine ---
+                        var searchLink = document.querySelector('link[rel="search"][type="application/opensearchdescription+xml"][href]');
+                        if (searchLink && window.__TAURI_INTERNALS__) {{
+                            var searchHref = searchLink.href;
+                            fetch(searchHref).then(function(r) {{
+                                return r.ok ? r.text() : '';
+                            }}).then(function(xml) {{
+                                if (!xml) return;
+                                return window.__TAURI_INTERNALS__.invoke('__tab_opensearch_found', {{
+                                    label: label,
+                                    href: searchHref,
+                                    xml: xml
+                                }});
+                            }}).catch(function(){{}});
+                        }}
                     }})();
                     "#,
                     label_inject
@@ -260,155 +382,189 @@ pub async fn tab_create(
         })
         .on_navigation(|_url| true);
 
-    window
+    let webview = window
         .add_child(
             webview,
-            LogicalPosition::new(0.0, get_chrome_height(&app)),
-            LogicalSize::new(width, content_height),
+            LogicalPosition::new(area.x, area.y),
+            LogicalSize::new(area.width, area.height),
         )
         .map_err(|e| format!("Failed to create tab webview: {}", e))?;
+    watch_load_errors(app, label, &webview);
+    watch_crashes(app, label, &webview);
 
-    let tab_info = TabInfo {
-        label: label.clone(),
-        url: url.clone(),
-        title: "New Tab".to_string(),
-        is_loading: true,
-        favicon: None,
-        can_go_back: false,
-        can_go_forward: false,
-        nav_stack: Vec::new(),
-        nav_pos: -1,
-        nav_traversing: false,
-    };
+    Ok(())
+}
 
-    let tab_manager = app.state::<TabManager>();
-    tab_manager.add_tab(tab_info.clone());
-
-    // Hide all other tabs and show this one
-    let all_labels = tab_manager.get_tab_labels();
-    for l in &all_labels {
-        if let Some(wv) = app.get_webview(l) {
-            if *l == label {
-                let _ = wv.show();
-            } else {
-                let _ = wv.hide();
-            }
-        }
+/// Create a tab webview at `url` and register it in state, like `tab_create` but
+/// without saving the session or discarding background tabs — callers that open
+/// several tabs do that once they're done. `url` is loaded exactly as given, for
+/// stored URLs (session, closed tabs, duplicates) that mustn't be resolved again.
+pub(super) fn open_tab(
+    app: &AppHandle,
+    window_id: &str,
+    url: &str,
+    pinned: bool,
+    opener: Option<String>,
+    background: bool,
+    container_id: Option<String>,
+) -> Result<TabInfo, String> {
+    if let Some(id) = &container_id {
+        let db = app.state::<Database>();
+        db.container_get(id)?
+            .ok_or_else(|| format!("Container {} not found", id))?;
     }
-    tab_manager.set_active_tab(Some(label.clone()));
-
-    let _ = app.emit("tab_created", &tab_info);
 
-    Ok(tab_info)
+    tab_controller(app).create_tab(window_id, url, pinned, opener, background, container_id)
 }
 
-/// Close a tab and its webview. Opens a new tab if this was the last one.
+/// Create a new tab webview in the given window and register it in state. `url` is
+/// resolved like address bar input; without one the tab opens the `new_tab_page`.
+/// Pinned tabs are placed at the end of the pinned block, tabs opened from another
+/// tab (`opener`) right after it and its earlier children, others at the end of the strip.
+/// `background` tabs open without being activated (e.g. Ctrl+click).
+/// The tab opens in `container_id` if given, else in its opener's container.
+/// MUST be async to avoid WebView2 deadlock on Windows.
 #[command]
-pub async fn tab_close(app: AppHandle, label: String) -> Result<(), String> {
-    let tab_manager = app.state::<TabManager>();
-
-    let adjacent = tab_manager.get_adjacent_tab(&label);
-    let was_active = tab_manager.get_active_tab() == Some(label.clone());
+pub async fn tab_create(
+    app: AppHandle,
+    window: Window,
+    url: Option<String>,
+    pinned: Option<bool>,
+    opener: Option<String>,
+    background: Option<bool>,
+    container_id: Option<String>,
+) -> Result<TabInfo, String> {
+    let url = match url {
+        Some(input) => resolve_url(&app, &input)?,
+        None => new_tab_url(&app),
+    };
+    let tab_info = open_tab(
+        &app,
+        window.label(),
+        &url,
+        pinned.unwrap_or(false),
+        opener,
+        background.unwrap_or(false),
+        container_id,
+    )?;
+
+    discard_background_tabs(&app);
+    save_session(&app);
 
-    tab_manager.remove_tab(&label);
+    Ok(tab_info)
+}
 
-    if let Some(webview) = app.get_webview(&label) {
-        webview.close().map_err(|e| e.to_string())?;
-    }
+/// Remember a closed tab so Ctrl+Shift+T can bring it back where it was
+fn remember_closed(app: &AppHandle, index: usize, tab: TabInfo) {
+    let db = app.state::<Database>();
+    let _ = db.closed_tab_push(&ClosedTab {
+        id: 0,
+        window_id: tab.window_id,
+        url: tab.url,
+        title: tab.title,
+        favicon: tab.favicon,
+        container_id: tab.container_id,
+        is_pinned: tab.is_pinned,
+        nav_stack: tab.nav_stack,
+        nav_pos: tab.nav_pos,
+        index,
+        closed_at: String::new(),
+    });
+}
 
-    let _ = app.emit("tab_closed", serde_json::json!({ "label": label }));
-
-    if was_active {
-        if let Some(next_label) = adjacent {
-            let all_labels = tab_manager.get_tab_labels();
-            for l in &all_labels {
-                if let Some(wv) = app.get_webview(l) {
-                    if *l == next_label {
-                        let _ = wv.show();
-                    } else {
-                        let _ = wv.hide();
-                    }
-                }
-            }
-            tab_manager.set_active_tab(Some(next_label.clone()));
-            if let Some(tab) = tab_manager.get_tab(&next_label) {
-                let _ = app.emit("tab_activated", &tab);
-            }
-        } else {
-            tab_create(app.clone(), None).await?;
-        }
-    }
+/// Close a tab and its webview. Focus returns to the tab's opener if it has one,
+/// else moves to a neighbour. Opens a new tab if this was the last one.
+/// Pinned tabs are only closed when `force` is set.
+#[command]
+pub async fn tab_close(app: AppHandle, label: String, force: Option<bool>) -> Result<(), String> {
+    let closed = tab_controller(&app).close(&label, force.unwrap_or(false), &new_tab_url(&app))?;
+    remember_closed(&app, closed.index, closed.tab);
 
+    discard_background_tabs(&app);
+    save_session(&app);
     Ok(())
 }
 
-/// Switch the active/visible tab
+/// Close several tabs of a window at once — the others, those to the right of a tab,
+/// those on a domain, or duplicates. Pinned tabs are kept. `selector` is an object
+/// tagged by `kind`, e.g. `{ kind: "to_right", label }` or `{ kind: "duplicates" }`.
+/// The UI gets a single `tabs_closed` event. Returns the closed labels.
 #[command]
-pub async fn tab_set_active(app: AppHandle, label: String) -> Result<(), String> {
-    let tab_manager = app.state::<TabManager>();
-
-    if tab_manager.get_tab(&label).is_none() {
-        return Err(format!("Tab {} not found", label));
+pub async fn tab_close_many(
+    app: AppHandle,
+    window: Window,
+    selector: CloseSelector,
+) -> Result<Vec<String>, String> {
+    let batch = tab_controller(&app).close_many(window.label(), &selector, &new_tab_url(&app))?;
+    if batch.closed.is_empty() {
+        return Ok(Vec::new());
     }
 
-    let all_labels = tab_manager.get_tab_labels();
-    for l in &all_labels {
-        if let Some(wv) = app.get_webview(l) {
-            if *l == label {
-                wv.show().map_err(|e| e.to_string())?;
-            } else {
-                wv.hide().map_err(|e| e.to_string())?;
-            }
-        }
+    let mut labels = Vec::with_capacity(batch.closed.len());
+    for (index, tab) in batch.closed {
+        labels.push(tab.label.clone());
+        remember_closed(&app, index, tab);
     }
+    labels.reverse();
 
-    tab_manager.set_active_tab(Some(label.clone()));
+    discard_background_tabs(&app);
+    save_session(&app);
+    Ok(labels)
+}
 
-    if let Some(tab) = tab_manager.get_tab(&label) {
-        let _ = app.emit("tab_activated", &tab);
-    }
+/// Switch the active/visible tab of the window that owns it
+#[command]
+pub async fn tab_set_active(app: AppHandle, label: String) -> Result<(), String> {
+    // Expands a collapsed group and wakes the tab (and its split panes) if discarded
+    tab_controller(&app).activate(&label)?;
 
+    discard_background_tabs(&app);
+    save_session(&app);
     Ok(())
 }
 
-/// Get all open tabs
+/// Get the calling window's open tabs
 #[command]
-pub fn tab_get_all(app: AppHandle) -> Result<Vec<TabInfo>, String> {
+pub fn tab_get_all(app: AppHandle, window: Window) -> Result<Vec<TabInfo>, String> {
     let tab_manager = app.state::<TabManager>();
-    Ok(tab_manager.get_all_tabs())
+    Ok(tab_manager.get_window_tabs(window.label()))
 }
 
-/// Get the currently active tab label
+/// Fuzzy-search every window's tabs by title and URL, best and most recently used first.
+/// Results carry their window ID so the UI can jump to them with `tab_set_active`.
 #[command]
-pub fn tab_get_active(app: AppHandle) -> Result<Option<String>, String> {
+pub fn tab_search(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<TabSearchResult>, String> {
     let tab_manager = app.state::<TabManager>();
-    Ok(tab_manager.get_active_tab())
+    Ok(tab_manager.search_tabs(&query, std::time::Instant::now(), limit.unwrap_or(20)))
 }
 
-/// Resize all content webviews to fit current window size.
-/// Called when the window is resized.
+/// Get the calling window's active tab label
 #[command]
-pub fn tab_resize_all(app: AppHandle) -> Result<(), String> {
+pub fn tab_get_active(app: AppHandle, window: Window) -> Result<Option<String>, String> {
     let tab_manager = app.state::<TabManager>();
-    let (width, content_height) = get_content_size(&app)?;
-
-    let chrome_h = get_chrome_height(&app);
-    for label in tab_manager.get_tab_labels() {
-        if let Some(wv) = app.get_webview(&label) {
-            let _ = wv.set_position(LogicalPosition::new(0.0, chrome_h));
-            let _ = wv.set_size(LogicalSize::new(width, content_height));
-        }
-    }
+    Ok(tab_manager.get_active_tab(window.label()))
+}
 
-    Ok(())
+/// Lay the calling window's content webviews out again to fit its current size
+#[command]
+pub fn tab_resize_all(app: AppHandle, window: Window) -> Result<(), String> {
+    layout_window(&app, window.label())
 }
 
-/// Duplicate a tab — creates a new tab with the same URL.
+/// Duplicate a tab — creates a new tab with the same URL in the same window and container.
 #[command]
 pub async fn tab_duplicate(app: AppHandle, label: String) -> Result<TabInfo, String> {
     let tab_manager = app.state::<TabManager>();
     let tab = tab_manager.get_tab(&label).ok_or("Tab not found")?;
-    tab_create(app, Some(tab.url)).await
+    let info = open_tab(&app, &tab.window_id, &tab.url, tab.is_pinned, None, false, tab.container_id)?;
+
+    discard_background_tabs(&app);
+    save_session(&app);
+    Ok(info)
 }
 
 /// Internal command: receive title updates from content webviews via JS injection.
@@ -416,14 +572,20 @@ pub async fn tab_duplicate(app: AppHandle, label: String) -> Result<TabInfo, Str
 #[command]
 pub fn __tab_title_update(app: AppHandle, label: String, title: String) -> Result<(), String> {
     let tab_manager = app.state::<TabManager>();
-    tab_manager.update_tab(&label, |tab| {
-        tab.title = title.clone();
-    });
+    tab_manager.update_tab(&label, |tab| tab.set_title(&title));
+
+    // Visits are often recorded before the page settles on its title
+    if let (Some(tab), Some(db)) = (tab_manager.get_tab(&label), app.try_state::<Database>()) {
+        if !title.is_empty() {
+            let _ = db.history_set_title(&tab.url, &title);
+        }
+    }
 
-    let _ = app.emit("tab_updated", serde_json::json!({
-        "label": label,
-        "title": title,
-    }));
+    emit_tab_update(&app, TabUpdate {
+        title: Some(title),
+        ..TabUpdate::new(&label)
+    });
+    save_session(&app);
 
     Ok(())
 }
@@ -439,58 +601,219 @@ pub fn __tab_favicon_update(app: AppHandle, label: String, favicon: String) -> R
         Some(favicon.clone())
     };
 
-    tab_manager.update_tab(&label, |tab| {
-        tab.favicon = favicon_opt.clone();
+    tab_manager.update_tab(&label, |tab| tab.set_favicon(favicon_opt.clone()));
+
+    emit_tab_update(&app, TabUpdate {
+        favicon: Some(favicon_opt),
+        ..TabUpdate::new(&label)
+    });
+    save_session(&app);
+
+    Ok(())
+}
+
+/// Internal command: receive scroll positions (debounced) from content webviews via JS
+/// injection. Kept on the history entry so going back to it can restore the position.
+#[command]
+pub fn __tab_scroll_update(app: AppHandle, label: String, url: String, scroll_y: f64) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let url = to_aero_url(&url);
+    tab_manager.update_tab(&label, |tab| tab.record_scroll(&url, scroll_y));
+    Ok(())
+}
+
+/// Internal command: a single-page app changed its URL without loading a document
+/// (pushState, replaceState, popstate, hashchange), reported by the injected helper
+#[command]
+pub fn __tab_spa_navigate(app: AppHandle, label: String, url: String, kind: SpaNavigation) -> Result<(), String> {
+    let url = to_aero_url(&url);
+    if tab_controller(&app).spa_navigate(&label, &url, kind)? {
+        // The title still belongs to the previous route — the page reports the new one
+        record_visit(&app, &url, None, false);
+    }
+    save_session(&app);
+    Ok(())
+}
+
+/// Internal command: a content webview asks for a link to open in a new tab
+/// (Ctrl/middle-click). The UI of the tab's window creates it next to the tab.
+#[command]
+pub fn __tab_open_link(app: AppHandle, label: String, url: String, background: bool) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let tab = tab_manager.get_tab(&label).ok_or("Tab not found")?;
+
+    emit(&app, AppEvent::OpenInNewTab {
+        url,
+        window_id: Some(tab.window_id),
+        opener: label,
+        background,
     });
+    Ok(())
+}
+
+/// Internal command: the injected media observer reports whether the page is playing sound.
+/// Returns the tab's mute state so a new document starts out muted when the tab is.
+#[command]
+pub fn __tab_audio_update(app: AppHandle, label: String, audible: bool) -> Result<bool, String> {
+    let tab_manager = app.state::<TabManager>();
+    let tab = tab_manager.get_tab(&label).ok_or("Tab not found")?;
+
+    if tab.is_audible != audible {
+        tab_manager.update_tab(&label, |tab| tab.is_audible = audible);
+        emit_tab_update(&app, TabUpdate {
+            is_audible: Some(audible),
+            ..TabUpdate::new(&label)
+        });
+    }
+
+    Ok(tab.is_muted)
+}
+
+/// Mute or unmute every media element in a tab's page. The tab stays muted across
+/// new media elements and navigations until unmuted.
+#[command]
+pub fn tab_set_muted(app: AppHandle, label: String, muted: bool) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    if tab_manager.get_tab(&label).is_none() {
+        return Err(format!("Tab {} not found", label));
+    }
+    tab_manager.update_tab(&label, |tab| tab.is_muted = muted);
+
+    // Discarded tabs have no page — the observer picks the state up when they wake
+    if let Some(webview) = app.get_webview(&label) {
+        let _ = webview.eval(format!(
+            "window.__aeroSetMuted && window.__aeroSetMuted({})",
+            muted
+        ));
+    }
 
-    let _ = app.emit("tab_updated", serde_json::json!({
-        "label": label,
-        "favicon": favicon_opt,
-    }));
+    emit_tab_update(&app, TabUpdate {
+        is_muted: Some(muted),
+        ..TabUpdate::new(&label)
+    });
 
     Ok(())
 }
 
-/// Reorder a tab to a new position in the tab list
+/// Reorder a tab to a new position in the tab list.
+/// With `whole_group`, a grouped tab drags its entire group along.
 #[command]
 pub fn tab_reorder(
     app: AppHandle,
     label: String,
     new_index: usize,
+    whole_group: Option<bool>,
 ) -> Result<(), String> {
-    let tab_manager = app.state::<TabManager>();
+    tab_controller(&app).reorder(&label, new_index, whole_group.unwrap_or(false))?;
+    save_session(&app);
+
+    Ok(())
+}
 
-    let mut tabs = tab_manager.tabs.lock().unwrap();
+/// Pin a tab — it moves to the end of the pinned block at the start of the strip
+#[command]
+pub fn tab_pin(app: AppHandle, label: String) -> Result<(), String> {
+    set_pinned(&app, &label, true)
+}
+
+/// Unpin a tab — it moves to the first position after the pinned block
+#[command]
+pub fn tab_unpin(app: AppHandle, label: String) -> Result<(), String> {
+    set_pinned(&app, &label, false)
+}
 
-    let old_index = tabs.iter()
-        .position(|t| t.label == label)
-        .ok_or("Tab not found")?;
+fn set_pinned(app: &AppHandle, label: &str, pinned: bool) -> Result<(), String> {
+    let tab_manager = app.state::<TabManager>();
+    let (old_index, new_index) = tab_manager.set_pinned(label, pinned)?;
 
-    if new_index >= tabs.len() {
-        return Err("Invalid index".to_string());
+    let group_id = tab_manager.get_tab(label).and_then(|t| t.group_id);
+    emit_tab_update(app, TabUpdate {
+        is_pinned: Some(pinned),
+        group_id: Some(group_id),
+        ..TabUpdate::new(label)
+    });
+    if old_index != new_index {
+        emit(app, AppEvent::TabReordered {
+            label: label.to_string(),
+            old_index,
+            new_index,
+        });
     }
+    prune_groups(app);
+    save_session(app);
 
-    let tab = tabs.remove(old_index);
-    tabs.insert(new_index, tab);
+    Ok(())
+}
 
-    drop(tabs);
+/// Reopen a tab recently closed in the calling window (the most recent, or a
+/// specific one by ID) at its old position, pinned if it was, with its back/forward
+/// history intact. Returns None when there is nothing to reopen.
+#[command]
+pub async fn tab_reopen_closed(
+    app: AppHandle,
+    window: Window,
+    id: Option<i64>,
+) -> Result<Option<TabInfo>, String> {
+    let window_id = window.label().to_string();
+    let closed = {
+        let db = app.state::<Database>();
+        db.closed_tab_pop(&window_id, id)?
+    };
+    let Some(closed) = closed else {
+        return Ok(None);
+    };
 
-    let _ = app.emit("tab_reordered", serde_json::json!({
-        "label": label,
-        "old_index": old_index,
-        "new_index": new_index,
-    }));
+    let container_id = existing_container(&app, closed.container_id.clone());
+    let info = open_tab(&app, &window_id, &closed.url, closed.is_pinned, None, false, container_id)?;
+    let tab_manager = app.state::<TabManager>();
 
-    Ok(())
+    tab_manager.update_tab(&info.label, |tab| {
+        tab.title = closed.title.clone();
+        tab.favicon = closed.favicon.clone();
+        tab.restore_nav(closed.nav_stack.clone(), closed.nav_pos);
+    });
+
+    // open_tab puts the tab at the end of the strip (or of the pinned block) —
+    // move it back to where it was closed from
+    let last = tab_manager.tab_count(&window_id).saturating_sub(1);
+    let new_index = closed.index.min(last);
+    if tab_manager.tab_index(&info.label) != Some(new_index) {
+        tab_controller(&app).reorder(&info.label, new_index, false)?;
+    }
+
+    let tab = tab_manager.get_tab(&info.label).ok_or("Tab not found")?;
+    emit_tab_update(&app, TabUpdate {
+        title: Some(tab.title.clone()),
+        favicon: Some(tab.favicon.clone()),
+        can_go_back: Some(tab.can_go_back),
+        can_go_forward: Some(tab.can_go_forward),
+        ..TabUpdate::new(&tab.label)
+    });
+    discard_background_tabs(&app);
+    save_session(&app);
+
+    Ok(Some(tab))
+}
+
+/// List tabs recently closed in the calling window, most recent first
+#[command]
+pub fn tab_get_recently_closed(
+    app: AppHandle,
+    window: Window,
+    limit: Option<i64>,
+) -> Result<Vec<ClosedTab>, String> {
+    let db = app.state::<Database>();
+    db.closed_tab_list(window.label(), limit.unwrap_or(10))
 }
 
 /// Show a native popup context menu as a separate borderless window on top of everything.
 /// Uses anchor-based navigation for menu item clicks (no __TAURI_INTERNALS__ needed).
-/// Auto-closes on focus loss, main window move, or Escape.
+/// Auto-closes on focus loss, browser window move, or Escape.
 /// MUST be async to avoid WebView2 deadlock on Windows.
 #[command]
 pub async fn show_context_menu(
     app: AppHandle,
+    window: Window,
     x: f64,
     y: f64,
     tab_label: String,
@@ -499,14 +822,13 @@ pub async fn show_context_menu(
     // Close any existing context menu popup
     close_context_menu(app.clone())?;
 
-    // Get the main window's screen position so we can place the popup relative to it
-    let main_window = app.get_window("main").ok_or("Main window not found")?;
-    let main_pos = main_window.outer_position().map_err(|e| e.to_string())?;
-    let scale = main_window.scale_factor().map_err(|e| e.to_string())?;
+    // Get the calling window's screen position so we can place the popup relative to it
+    let window_pos = window.outer_position().map_err(|e| e.to_string())?;
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
 
     // outer_position returns physical pixels, convert to logical and add click offset
-    let screen_x = (main_pos.x as f64 / scale) + x;
-    let screen_y = (main_pos.y as f64 / scale) + y;
+    let screen_x = (window_pos.x as f64 / scale) + x;
+    let screen_y = (window_pos.y as f64 / scale) + y;
 
     // Build the menu HTML — menu items use onclick + window.location to trigger
     // on_navigation interception, avoiding WebView2's built-in link hover tooltip
@@ -529,7 +851,8 @@ pub async fn show_context_menu(
         if item.get("separator").and_then(|v| v.as_bool()).unwrap_or(false) {
             menu_html.push_str(r#"<div class="sep"></div>"#);
         } else {
-            let label = item.get("label").and_then(|v| v.as_str()).unwrap_or("");
+            // Labels can be page titles (back/forward history menu) — never markup
+            let label = escape_html(item.get("label").and_then(|v| v.as_str()).unwrap_or(""));
             let action = item.get("action").and_then(|v| v.as_str()).unwrap_or("");
             menu_html.push_str(&format!(
                 r#"<div class="item" onclick="window.location='aero://action/{}';">{}</div>"#,
@@ -593,10 +916,10 @@ pub async fn show_context_menu(
                     let _ = w.close();
                 }
                 // Emit the action event to the UI webview
-                let _ = app_for_nav.emit("context_menu_action", serde_json::json!({
-                    "tab_label": tab_label_clone,
-                    "action": action,
-                }));
+                emit(&app_for_nav, AppEvent::ContextMenuAction {
+                    tab_label: tab_label_clone.clone(),
+                    action,
+                });
                 return false; // Block the navigation
             }
             // Allow about:blank
@@ -613,8 +936,11 @@ pub async fn show_context_menu(
 
     // Inject the menu HTML into the webview
     if let Some(wv) = app.get_webview("ctx-menu-wv") {
-        let escaped = menu_html.replace('\\', "\\\\").replace('`', "\\`");
-        let _ = wv.eval(&format!("document.open();document.write(`{}`);document.close();", escaped));
+        let escaped = menu_html
+            .replace('\\', "\\\\")
+            .replace('`', "\\`")
+            .replace("${", "\\${");
+        let _ = wv.eval(format!("document.open();document.write(`{}`);document.close();", escaped));
     }
 
     Ok(())
@@ -629,11 +955,11 @@ pub fn close_context_menu(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Focus the browser-ui webview (used when global shortcuts need to interact with UI)
+/// Focus the calling window's browser UI webview (used when global shortcuts need to interact with UI)
 #[command]
-pub fn ui_focus(app: AppHandle) -> Result<(), String> {
+pub fn ui_focus(app: AppHandle, window: Window) -> Result<(), String> {
     let webview = app
-        .get_webview("browser-ui")
+        .get_webview(&ui_label(window.label()))
         .ok_or("browser-ui webview not found")?;
     webview.set_focus().map_err(|e| e.to_string())?;
     Ok(())
@@ -641,12 +967,11 @@ pub fn ui_focus(app: AppHandle) -> Result<(), String> {
 
 /// Resize the browser-ui webview height (e.g. to show context menus below the 76px chrome)
 #[command]
-pub fn ui_set_height(app: AppHandle, height: f64) -> Result<(), String> {
+pub fn ui_set_height(app: AppHandle, window: Window, height: f64) -> Result<(), String> {
     let webview = app
-        .get_webview("browser-ui")
+        .get_webview(&ui_label(window.label()))
         .ok_or("browser-ui webview not found")?;
 
-    let window = app.get_window("main").ok_or("Main window not found")?;
     let size = window.inner_size().map_err(|e| e.to_string())?;
     let scale = window.scale_factor().map_err(|e| e.to_string())?;
     let width = size.width as f64 / scale;
@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use tauri::{command, AppHandle, Manager, Webview};
+
+use super::webviews::tab_controller;
+use crate::events::{emit, emit_tab_update, AppEvent, TabUpdate};
+use crate::state::tab_state::TabManager;
+
+/// How often live tabs are pinged
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a ping may go unanswered before the tab counts as unresponsive
+const PING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Ping responder injected into every document of a content webview before page scripts
+/// run. It checks in once straight away, arming the watchdog for the new document, and
+/// answers each ping through `__tab_pong`. `__AERO_LABEL__` is replaced with the tab label.
+pub(super) const WATCHDOG_SCRIPT: &str = r#"
+(function() {
+    if (window.__aeroPing) return;
+    var label = '__AERO_LABEL__';
+    window.__aeroPing = function() {
+        window.__TAURI_INTERNALS__?.invoke('__tab_pong', { label: label }).catch(function(){});
+    };
+    window.__aeroPing();
+})();
+"#;
+
+/// Ping every armed tab, and flag the ones that let a ping time out as unresponsive
+fn check_tabs(app: &AppHandle) {
+    let Some(tab_manager) = app.try_state::<TabManager>() else {
+        return;
+    };
+
+    let tick = tab_manager.watchdog_tick(Instant::now(), PING_TIMEOUT);
+    for label in tick.ping {
+        if let Some(webview) = app.get_webview(&label) {
+            let _ = webview.eval("window.__aeroPing && window.__aeroPing()");
+        }
+    }
+    for label in tick.unresponsive {
+        emit_tab_update(app, TabUpdate {
+            is_unresponsive: Some(true),
+            ..TabUpdate::new(&label)
+        });
+    }
+}
+
+/// A tab's content process died: mark the tab crashed until `tab_recover`
+fn report_crash(app: &AppHandle, label: &str) {
+    let Some(tab_manager) = app.try_state::<TabManager>() else {
+        return;
+    };
+    if tab_manager.mark_crashed(label) {
+        emit(app, AppEvent::TabCrashed { label: label.to_string() });
+    }
+}
+
+/// Watch a tab's webview for its content process dying. Only a dead process makes
+/// a tab crashed — a page that stops answering pings is merely unresponsive.
+pub(super) fn watch_crashes(app: &AppHandle, label: &str, webview: &Webview) {
+    let app = app.clone();
+    let label = label.to_string();
+    let _ = webview.with_webview(move |platform| {
+        watch_platform_process(platform, app, label);
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn watch_platform_process(platform: tauri::webview::PlatformWebview, app: AppHandle, label: String) {
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+
+    platform.inner().connect_web_process_terminated(move |_, reason| {
+        // TerminatedByApi is the app's own doing, not a crash
+        if matches!(
+            reason,
+            WebProcessTerminationReason::Crashed | WebProcessTerminationReason::ExceededMemoryLimit
+        ) {
+            report_crash(&app, &label);
+        }
+    });
+}
+
+#[cfg(windows)]
+fn watch_platform_process(platform: tauri::webview::PlatformWebview, app: AppHandle, label: String) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PROCESS_FAILED_KIND, COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED,
+    };
+    use webview2_com::ProcessFailedEventHandler;
+
+    unsafe {
+        let Ok(core) = platform.controller().CoreWebView2() else {
+            return;
+        };
+        let handler = ProcessFailedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+                return Ok(());
+            };
+            let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+            args.ProcessFailedKind(&mut kind)?;
+            // A hung renderer (RENDER_PROCESS_UNRESPONSIVE) is left to the watchdog
+            if kind == COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED
+                || kind == COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED
+            {
+                report_crash(&app, &label);
+            }
+            Ok(())
+        }));
+        let mut token = 0;
+        let _ = core.add_ProcessFailed(&handler, &mut token);
+    }
+}
+
+/// Other platforms only get the watchdog's unresponsive state
+#[cfg(not(any(target_os = "linux", windows)))]
+fn watch_platform_process(_platform: tauri::webview::PlatformWebview, _app: AppHandle, _label: String) {}
+
+/// Check on content webviews every few seconds for the life of the app
+pub fn spawn_watchdog(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(PING_INTERVAL);
+        check_tabs(&app);
+    });
+}
+
+/// Internal command: a content webview answered a ping (or checked in)
+#[command]
+pub fn __tab_pong(app: AppHandle, label: String) -> Result<(), String> {
+    if app.state::<TabManager>().watchdog_pong(&label) {
+        emit_tab_update(&app, TabUpdate {
+            is_unresponsive: Some(false),
+            ..TabUpdate::new(&label)
+        });
+    }
+    Ok(())
+}
+
+/// Bring a crashed tab back: its webview is recreated at the last known URL,
+/// keeping the tab's position and back/forward history.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_recover(app: AppHandle, label: String) -> Result<(), String> {
+    tab_controller(&app).recover(&label)
+}
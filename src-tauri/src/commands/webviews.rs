@@ -0,0 +1,87 @@
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, Webview};
+
+use super::layout::get_content_rect;
+use super::tabs::open_tab_webview;
+use crate::events::{emit, AppEvent};
+use crate::state::aero_pages::{AeroUrl, AERO_SCHEME};
+use crate::state::layout::Rect;
+use crate::state::tab_controller::{TabController, WebviewHost};
+use crate::state::tab_state::TabManager;
+
+/// Parse a display URL into the URL the webview should load. Internal pages
+/// go through the page registry; unknown aero:// pages are refused.
+pub(super) fn to_webview_url(url: &str) -> Result<url::Url, String> {
+    if url::Url::parse(url).is_ok_and(|u| u.scheme() == AERO_SCHEME) {
+        let page = AeroUrl::parse(url).ok_or_else(|| format!("Unknown internal page: {}", url))?;
+        return page
+            .webview_url()
+            .parse()
+            .map_err(|e| format!("Invalid internal page URL: {}", e));
+    }
+    url.parse().map_err(|e| format!("Invalid URL: {}", e))
+}
+
+/// Content webviews as Tauri child webviews of the browser windows
+pub struct TauriWebviews<'a> {
+    app: &'a AppHandle,
+}
+
+impl TauriWebviews<'_> {
+    fn webview(&self, label: &str) -> Result<Webview, String> {
+        self.app
+            .get_webview(label)
+            .ok_or_else(|| "Tab webview not found".to_string())
+    }
+}
+
+impl WebviewHost for TauriWebviews<'_> {
+    fn create(&self, window_id: &str, label: &str, url: &str, container_id: Option<&str>) -> Result<(), String> {
+        open_tab_webview(self.app, window_id, label, url, container_id)
+    }
+
+    fn navigate(&self, label: &str, url: &str) -> Result<(), String> {
+        // aero:// internal URLs load from the page registry's webview origin
+        self.webview(label)?
+            .navigate(to_webview_url(url)?)
+            .map_err(|e| e.to_string())
+    }
+
+    fn eval(&self, label: &str, script: &str) -> Result<(), String> {
+        self.webview(label)?.eval(script).map_err(|e| e.to_string())
+    }
+
+    fn show(&self, label: &str, rect: Rect) -> Result<(), String> {
+        let webview = self.webview(label)?;
+        let _ = webview.set_position(LogicalPosition::new(rect.x, rect.y));
+        let _ = webview.set_size(LogicalSize::new(rect.width, rect.height));
+        webview.show().map_err(|e| e.to_string())
+    }
+
+    fn hide(&self, label: &str) -> Result<(), String> {
+        self.webview(label)?.hide().map_err(|e| e.to_string())
+    }
+
+    /// The label is free again as soon as this returns — Tauri forgets the webview
+    /// before the event loop destroys it — so a crashed tab can get a new webview
+    /// under its own label straight away
+    fn close(&self, label: &str) -> Result<(), String> {
+        self.webview(label)?.close().map_err(|e| e.to_string())
+    }
+
+    fn has_webview(&self, label: &str) -> bool {
+        self.app.get_webview(label).is_some()
+    }
+
+    fn content_rect(&self, window_id: &str) -> Result<Rect, String> {
+        get_content_rect(self.app, window_id)
+    }
+
+    fn emit(&self, event: AppEvent) {
+        emit(self.app, event);
+    }
+}
+
+/// The tab controller over the app's tab state and real webviews
+pub fn tab_controller(app: &AppHandle) -> TabController<'_, TauriWebviews<'_>> {
+    TabController::new(app.state::<TabManager>().inner(), TauriWebviews { app })
+}
@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tauri::{command, AppHandle, Manager, Window};
+
+use super::layout::layout_window;
+use super::session::save_session;
+use super::split_view::prune_splits;
+use super::tab_groups::prune_groups;
+use super::tabs::tab_set_active;
+use crate::events::{emit, AppEvent};
+use crate::state::tab_state::TabManager;
+
+/// ID (and Tauri label) of the window opened at startup
+pub const MAIN_WINDOW_ID: &str = "main";
+
+/// Global window ID counter — ensures unique IDs across the app lifetime
+static WINDOW_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Generate the next unique window ID (e.g. "window-1", "window-2")
+pub fn next_window_id() -> String {
+    let id = WINDOW_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("window-{}", id)
+}
+
+/// Label of a window's browser UI webview (SvelteKit app)
+pub fn ui_label(window_id: &str) -> String {
+    format!("browser-ui-{}", window_id)
+}
+
+/// Whether a Tauri window is a browser window (as opposed to a popup like the context menu)
+pub fn is_browser_window(label: &str) -> bool {
+    label == MAIN_WINDOW_ID || label.starts_with("window-")
+}
+
+/// Look up a browser window by ID
+pub fn get_browser_window(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window {} not found", window_id))
+}
+
+/// All open browser windows
+pub fn browser_windows(app: &AppHandle) -> Vec<Window> {
+    app.windows()
+        .into_values()
+        .filter(|w| is_browser_window(w.label()))
+        .collect()
+}
+
+/// Forget a window's tabs once it has been destroyed.
+/// The last window keeps its tabs so the session saved on exit still has them.
+pub fn on_window_destroyed(app: &AppHandle, window_id: &str) {
+    let others_open = browser_windows(app).iter().any(|w| w.label() != window_id);
+    if !others_open {
+        return;
+    }
+
+    let tab_manager = app.state::<TabManager>();
+    tab_manager.remove_window(window_id);
+    prune_groups(app);
+    prune_splits(app);
+    save_session(app);
+}
+
+/// Open a new browser window. Its UI opens the first tab once it has loaded.
+/// Returns the new window's ID.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn window_create(app: AppHandle) -> Result<String, String> {
+    let window_id = next_window_id();
+    crate::create_browser_window(&app, &window_id).map_err(|e| e.to_string())?;
+    Ok(window_id)
+}
+
+/// Close a browser window (the calling one if no ID is given) along with its tabs.
+/// The app exits when the last window closes.
+#[command]
+pub fn window_close(app: AppHandle, window: Window, window_id: Option<String>) -> Result<(), String> {
+    let window_id = window_id.unwrap_or_else(|| window.label().to_string());
+    let target = get_browser_window(&app, &window_id)?;
+    target.close().map_err(|e| e.to_string())
+}
+
+/// Move a tab into another window (or a new one when `window_id` is None) without
+/// reloading it: its content webview is reparented, not recreated. The tab becomes
+/// active in the target window; the source window activates a neighbour, or closes
+/// if the tab was its last. Returns the target window's ID.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_move_to_window(
+    app: AppHandle,
+    label: String,
+    window_id: Option<String>,
+    index: Option<usize>,
+) -> Result<String, String> {
+    let tab_manager = app.state::<TabManager>();
+    let tab = tab_manager
+        .get_tab(&label)
+        .ok_or_else(|| format!("Tab {} not found", label))?;
+    let source_id = tab.window_id.clone();
+
+    let target = match &window_id {
+        Some(id) => get_browser_window(&app, id)?,
+        None => crate::create_browser_window(&app, &next_window_id()).map_err(|e| e.to_string())?,
+    };
+    let target_id = target.label().to_string();
+    if target_id == source_id {
+        return Err("Tab is already in that window".to_string());
+    }
+
+    let adjacent = tab_manager.get_adjacent_tab(&label);
+    let was_active = tab_manager.get_active_tab(&source_id) == Some(label.clone());
+
+    // Discarded tabs have no webview — they simply wake up in the target window.
+    // Activating the tab below lays it out to fit its new window.
+    if let Some(webview) = app.get_webview(&label) {
+        webview.reparent(&target).map_err(|e| e.to_string())?;
+    }
+
+    let index = index.unwrap_or(usize::MAX);
+    let (old_index, new_index) = tab_manager.move_to_window(&label, &target_id, index)?;
+    let moved = tab_manager.get_tab(&label).ok_or("Tab not found")?;
+
+    // Both UIs refresh from this: the source drops the tab, the target inserts it
+    emit(&app, AppEvent::TabMovedToWindow {
+        tab: moved,
+        old_window_id: source_id.clone(),
+        old_index,
+        new_index,
+    });
+    prune_groups(&app);
+    // A split can't span windows — the tab leaves its split
+    prune_splits(&app);
+
+    tab_set_active(app.clone(), label.clone()).await?;
+
+    if was_active {
+        match adjacent {
+            Some(next) => tab_set_active(app.clone(), next).await?,
+            None => {
+                tab_manager.set_active_tab(&source_id, None);
+                if let Some(source) = app.get_window(&source_id) {
+                    let _ = source.close();
+                }
+            }
+        }
+    } else {
+        // The tab may have been a pane of the source window's visible split
+        let _ = layout_window(&app, &source_id);
+    }
+
+    save_session(&app);
+    Ok(target_id)
+}
@@ -0,0 +1,131 @@
+use tauri::{command, AppHandle, Manager, Window};
+
+use crate::events::{emit_tab_update, TabUpdate};
+use crate::state::tab_state::TabManager;
+use crate::state::zoom::{self, clamp_zoom, origin_of, parse_zoom, FALLBACK_ZOOM};
+use crate::storage::database::Database;
+
+/// The `default_zoom` setting, used for sites without a saved level
+fn default_zoom(app: &AppHandle) -> u32 {
+    app.try_state::<Database>()
+        .and_then(|db| db.settings_get("default_zoom").ok().flatten())
+        .and_then(|v| parse_zoom(&v))
+        .unwrap_or(FALLBACK_ZOOM)
+}
+
+/// The zoom a page at `url` should open with: its origin's saved level, else `default_zoom`
+fn zoom_for_url(app: &AppHandle, url: &str) -> u32 {
+    let saved = origin_of(url).and_then(|origin| {
+        app.try_state::<Database>()
+            .and_then(|db| db.site_zoom_get(&origin).ok().flatten())
+    });
+    saved.map(clamp_zoom).unwrap_or_else(|| default_zoom(app))
+}
+
+/// Set a tab's zoom on its webview and in its TabInfo, telling the UI when it changed.
+/// Discarded tabs only keep the level — their page picks it up when they wake.
+fn set_tab_zoom(app: &AppHandle, label: &str, zoom: u32) {
+    if let Some(webview) = app.get_webview(label) {
+        let _ = webview.set_zoom(zoom as f64 / 100.0);
+    }
+
+    let tab_manager = app.state::<TabManager>();
+    let changed = tab_manager
+        .get_tab(label)
+        .is_some_and(|tab| tab.zoom != zoom);
+    if changed {
+        tab_manager.update_tab(label, |tab| tab.zoom = zoom);
+        emit_tab_update(app, TabUpdate {
+            zoom: Some(zoom),
+            ..TabUpdate::new(label)
+        });
+    }
+}
+
+/// Apply the zoom a tab's current page should have. Called from `on_page_load`, so a
+/// revisited site comes back at its saved level and a new webview starts zoomed.
+pub fn restore_zoom(app: &AppHandle, label: &str, url: &str) {
+    let zoom = zoom_for_url(app, url);
+    set_tab_zoom(app, label, zoom);
+}
+
+/// Zoom a tab and remember the level for its site. Other tabs on the same site follow,
+/// and going back to `default_zoom` forgets the saved level.
+fn zoom_tab(app: &AppHandle, label: &str, zoom: u32) -> Result<u32, String> {
+    let tab_manager = app.state::<TabManager>();
+    let tab = tab_manager
+        .get_tab(label)
+        .ok_or_else(|| format!("Tab {} not found", label))?;
+    let zoom = clamp_zoom(zoom);
+
+    let Some(origin) = origin_of(&tab.url) else {
+        // Nowhere to save it (about:blank) — zoom just this page
+        set_tab_zoom(app, label, zoom);
+        return Ok(zoom);
+    };
+
+    if let Some(db) = app.try_state::<Database>() {
+        if zoom == default_zoom(app) {
+            db.site_zoom_clear(&origin)?;
+        } else {
+            db.site_zoom_set(&origin, zoom)?;
+        }
+    }
+
+    for other in tab_manager.get_all_tabs() {
+        if other.label == label || origin_of(&other.url).as_deref() == Some(origin.as_str()) {
+            set_tab_zoom(app, &other.label, zoom);
+        }
+    }
+
+    Ok(zoom)
+}
+
+/// The given tab, or the window's active tab
+fn target_tab(app: &AppHandle, window: &Window, label: Option<String>) -> Result<(String, u32), String> {
+    let tab_manager = app.state::<TabManager>();
+    let label = label
+        .or_else(|| tab_manager.get_active_tab(window.label()))
+        .ok_or("No active tab")?;
+    let tab = tab_manager
+        .get_tab(&label)
+        .ok_or_else(|| format!("Tab {} not found", label))?;
+    Ok((label, tab.zoom))
+}
+
+/// Zoom a tab (default: the active tab) in one step. Returns the new level in percent.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_zoom_in(app: AppHandle, window: Window, label: Option<String>) -> Result<u32, String> {
+    let (label, current) = target_tab(&app, &window, label)?;
+    zoom_tab(&app, &label, zoom::zoom_in(current))
+}
+
+/// Zoom a tab (default: the active tab) out one step. Returns the new level in percent.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_zoom_out(app: AppHandle, window: Window, label: Option<String>) -> Result<u32, String> {
+    let (label, current) = target_tab(&app, &window, label)?;
+    zoom_tab(&app, &label, zoom::zoom_out(current))
+}
+
+/// Put a tab (default: the active tab) back at `default_zoom` and forget its site's level.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_zoom_reset(app: AppHandle, window: Window, label: Option<String>) -> Result<u32, String> {
+    let (label, _) = target_tab(&app, &window, label)?;
+    zoom_tab(&app, &label, default_zoom(&app))
+}
+
+/// Set a tab's (default: the active tab's) zoom in percent, clamped to 25–500.
+/// MUST be async to avoid WebView2 deadlock on Windows.
+#[command]
+pub async fn tab_zoom_set(
+    app: AppHandle,
+    window: Window,
+    zoom: u32,
+    label: Option<String>,
+) -> Result<u32, String> {
+    let (label, _) = target_tab(&app, &window, label)?;
+    zoom_tab(&app, &label, zoom)
+}
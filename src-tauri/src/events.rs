@@ -0,0 +1,321 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::state::layout::Insets;
+use crate::state::load_error::LoadError;
+use crate::state::opensearch::OpenSearchDescription;
+use crate::state::split_view::SplitView;
+use crate::state::tab_groups::TabGroup;
+use crate::state::tab_state::TabInfo;
+use crate::storage::containers::Container;
+use crate::storage::search_engines::SearchEngine;
+
+/// A partial update to a tab, sent as `tab_updated`. Only fields that are set are
+/// serialized — the UI keeps its current value for the rest. For `favicon`,
+/// `group_id` and `load_error`, `Some(None)` is sent as `null` and clears the value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TabUpdate {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loading: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_go_back: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_go_forward: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_pinned: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_discarded: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_crashed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_unresponsive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_error: Option<Option<LoadError>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_audible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<u32>,
+}
+
+impl TabUpdate {
+    /// An update for `label` with nothing set — fill in fields with struct update syntax
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Every event the backend sends to the browser UI. Events go to all windows;
+/// each UI keeps the ones for its own tabs (by `window_id` or tab label).
+/// The payload is the variant's contents, without the variant name.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AppEvent {
+    TabCreated(TabInfo),
+    TabActivated(TabInfo),
+    TabUpdated(TabUpdate),
+    TabClosed {
+        label: String,
+    },
+    /// A tab's web content process died — it stays crashed until `tab_recover`
+    TabCrashed {
+        label: String,
+    },
+    /// Several tabs of one window closed together by `tab_close_many`
+    TabsClosed {
+        window_id: String,
+        labels: Vec<String>,
+    },
+    TabReordered {
+        label: String,
+        old_index: usize,
+        new_index: usize,
+    },
+    TabMovedToWindow {
+        tab: TabInfo,
+        old_window_id: String,
+        old_index: usize,
+        new_index: usize,
+    },
+    /// A page asked for a link to be opened in a new tab of `window_id`
+    OpenInNewTab {
+        url: String,
+        window_id: Option<String>,
+        opener: String,
+        background: bool,
+    },
+    TabGroupUpdated(TabGroup),
+    TabGroupRemoved {
+        group_id: String,
+    },
+    TabGroupMoved {
+        group_id: String,
+        labels: Vec<String>,
+        old_index: usize,
+        new_index: usize,
+    },
+    TabSplitUpdated(SplitView),
+    TabSplitRemoved {
+        split_id: String,
+    },
+    /// The container list changed — sent whole, in list order
+    ContainersUpdated(Vec<Container>),
+    /// The search engine list changed — sent whole, in list order
+    SearchEnginesUpdated(Vec<SearchEngine>),
+    /// A tab's page offers a search engine that isn't added yet
+    SearchEngineFound {
+        label: String,
+        engine: OpenSearchDescription,
+    },
+    LayoutChanged(Insets),
+    FindResult {
+        label: String,
+        total: i32,
+        current: i32,
+    },
+    /// A context menu item was picked for `tab_label`
+    ContextMenuAction {
+        tab_label: String,
+        action: String,
+    },
+}
+
+impl AppEvent {
+    /// The event name the frontend listens for
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::TabCreated(_) => "tab_created",
+            AppEvent::TabActivated(_) => "tab_activated",
+            AppEvent::TabUpdated(_) => "tab_updated",
+            AppEvent::TabClosed { .. } => "tab_closed",
+            AppEvent::TabCrashed { .. } => "tab_crashed",
+            AppEvent::TabsClosed { .. } => "tabs_closed",
+            AppEvent::TabReordered { .. } => "tab_reordered",
+            AppEvent::TabMovedToWindow { .. } => "tab_moved_to_window",
+            AppEvent::OpenInNewTab { .. } => "open_in_new_tab",
+            AppEvent::TabGroupUpdated(_) => "tab_group_updated",
+            AppEvent::TabGroupRemoved { .. } => "tab_group_removed",
+            AppEvent::TabGroupMoved { .. } => "tab_group_moved",
+            AppEvent::TabSplitUpdated(_) => "tab_split_updated",
+            AppEvent::TabSplitRemoved { .. } => "tab_split_removed",
+            AppEvent::ContainersUpdated(_) => "containers_updated",
+            AppEvent::SearchEnginesUpdated(_) => "search_engines_updated",
+            AppEvent::SearchEngineFound { .. } => "search_engine_found",
+            AppEvent::LayoutChanged(_) => "layout_changed",
+            AppEvent::FindResult { .. } => "find_result",
+            AppEvent::ContextMenuAction { .. } => "context_menu_action",
+        }
+    }
+}
+
+/// Send an event to the browser UI of every window
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    let _ = app.emit(event.name(), &event);
+}
+
+/// Shorthand for the most common event: a partial tab update
+pub fn emit_tab_update(app: &AppHandle, update: TabUpdate) {
+    emit(app, AppEvent::TabUpdated(update));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload(event: &AppEvent) -> serde_json::Value {
+        serde_json::to_value(event).unwrap()
+    }
+
+    #[test]
+    fn tab_update_sends_only_set_fields() {
+        let event = AppEvent::TabUpdated(TabUpdate {
+            title: Some("Example".to_string()),
+            ..TabUpdate::new("tab-1")
+        });
+        assert_eq!(event.name(), "tab_updated");
+        assert_eq!(payload(&event), json!({ "label": "tab-1", "title": "Example" }));
+    }
+
+    #[test]
+    fn tab_update_can_clear_nullable_fields() {
+        let update = TabUpdate {
+            favicon: Some(None),
+            group_id: Some(None),
+            is_pinned: Some(true),
+            ..TabUpdate::new("tab-1")
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({ "label": "tab-1", "favicon": null, "group_id": null, "is_pinned": true })
+        );
+    }
+
+    #[test]
+    fn tab_update_field_names() {
+        let update = TabUpdate {
+            label: "tab-1".to_string(),
+            loading: Some(false),
+            url: Some("https://example.com".to_string()),
+            title: Some("Example".to_string()),
+            favicon: Some(Some("https://example.com/favicon.ico".to_string())),
+            can_go_back: Some(true),
+            can_go_forward: Some(false),
+            is_pinned: Some(false),
+            group_id: Some(Some("group-1".to_string())),
+            is_discarded: Some(false),
+            is_crashed: Some(false),
+            is_unresponsive: Some(true),
+            load_error: Some(None),
+            is_audible: Some(true),
+            is_muted: Some(false),
+            zoom: Some(125),
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({
+                "label": "tab-1",
+                "loading": false,
+                "url": "https://example.com",
+                "title": "Example",
+                "favicon": "https://example.com/favicon.ico",
+                "can_go_back": true,
+                "can_go_forward": false,
+                "is_pinned": false,
+                "group_id": "group-1",
+                "is_discarded": false,
+                "is_crashed": false,
+                "is_unresponsive": true,
+                "load_error": null,
+                "is_audible": true,
+                "is_muted": false,
+                "zoom": 125,
+            })
+        );
+    }
+
+    #[test]
+    fn struct_variants_serialize_as_plain_objects() {
+        let event = AppEvent::TabReordered {
+            label: "tab-1".to_string(),
+            old_index: 0,
+            new_index: 2,
+        };
+        assert_eq!(event.name(), "tab_reordered");
+        assert_eq!(
+            payload(&event),
+            json!({ "label": "tab-1", "old_index": 0, "new_index": 2 })
+        );
+
+        let event = AppEvent::OpenInNewTab {
+            url: "https://example.com".to_string(),
+            window_id: None,
+            opener: "tab-1".to_string(),
+            background: true,
+        };
+        assert_eq!(
+            payload(&event),
+            json!({ "url": "https://example.com", "window_id": null, "opener": "tab-1", "background": true })
+        );
+
+        let event = AppEvent::TabsClosed {
+            window_id: "main".to_string(),
+            labels: vec!["tab-2".to_string(), "tab-3".to_string()],
+        };
+        assert_eq!(event.name(), "tabs_closed");
+        assert_eq!(
+            payload(&event),
+            json!({ "window_id": "main", "labels": ["tab-2", "tab-3"] })
+        );
+
+        let event = AppEvent::ContextMenuAction {
+            tab_label: "tab-1".to_string(),
+            action: "duplicate".to_string(),
+        };
+        assert_eq!(payload(&event), json!({ "tab_label": "tab-1", "action": "duplicate" }));
+    }
+
+    #[test]
+    fn newtype_variants_send_their_contents() {
+        let insets = Insets { top: 76.0, left: 0.0, right: 0.0, bottom: 0.0 };
+        let event = AppEvent::LayoutChanged(insets);
+        assert_eq!(event.name(), "layout_changed");
+        assert_eq!(
+            payload(&event),
+            json!({ "top": 76.0, "left": 0.0, "right": 0.0, "bottom": 0.0 })
+        );
+
+        let event = AppEvent::TabCrashed { label: "tab-1".to_string() };
+        assert_eq!(event.name(), "tab_crashed");
+        assert_eq!(payload(&event), json!({ "label": "tab-1" }));
+
+        let event = AppEvent::ContainersUpdated(vec![Container {
+            id: "c1".to_string(),
+            name: "Work".to_string(),
+            color: "blue".to_string(),
+            position: 0,
+        }]);
+        assert_eq!(event.name(), "containers_updated");
+        assert_eq!(
+            payload(&event),
+            json!([{ "id": "c1", "name": "Work", "color": "blue", "position": 0 }])
+        );
+
+        let event = AppEvent::TabSplitRemoved { split_id: "split-1".to_string() };
+        assert_eq!(event.name(), "tab_split_removed");
+        assert_eq!(payload(&event), json!({ "split_id": "split-1" }));
+    }
+}
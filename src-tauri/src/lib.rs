@@ -1,14 +1,79 @@
 mod commands;
+mod events;
 mod state;
 mod storage;
 
-use state::chrome_height::ChromeHeight;
+use state::layout::LayoutInsets;
 use state::tab_state::TabManager;
 use storage::database::Database;
-use tauri::{LogicalPosition, LogicalSize, Manager, WebviewUrl};
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, WebviewUrl, Window};
 
-/// Initial chrome height — will be dynamic via ChromeHeight state
-const CHROME_HEIGHT: f64 = 76.0;
+/// Create a browser window: a borderless window with the browser UI webview
+/// across the top and event handlers that keep its content webviews sized.
+/// Tabs are added by the UI (or session restore) once the window exists.
+pub(crate) fn create_browser_window(app: &AppHandle, window_id: &str) -> tauri::Result<Window> {
+    let width = 1280.0_f64;
+    let height = 800.0_f64;
+
+    // No decorations — we render our own title bar
+    let mut builder = tauri::window::WindowBuilder::new(app, window_id)
+        .title("Aero")
+        .inner_size(width, height)
+        .min_inner_size(400.0, 300.0)
+        .decorations(false)
+        .resizable(true);
+    if window_id == commands::windows::MAIN_WINDOW_ID {
+        builder = builder.center();
+    }
+    let window = builder.build()?;
+
+    // Browser UI webview (SvelteKit app) — covers the layout insets (the chrome)
+    let ui_rect = commands::layout::get_insets(app).ui_rect(width, height);
+    let ui_webview = tauri::webview::WebviewBuilder::new(
+        commands::windows::ui_label(window_id),
+        WebviewUrl::App("index.html".into()),
+    )
+    .transparent(true)
+    .auto_resize();
+
+    window.add_child(
+        ui_webview,
+        LogicalPosition::new(ui_rect.x, ui_rect.y),
+        LogicalSize::new(ui_rect.width, ui_rect.height),
+    )?;
+
+    // Listen for window events — resize webviews, close context menu on move,
+    // and forget the window's tabs once it is gone
+    let app_handle = app.clone();
+    let window_for_events = window.clone();
+    window.on_window_event(move |event| {
+        match event {
+            tauri::WindowEvent::Resized(_) => {
+                // Fit the UI webview and content webviews to the new size
+                let window_id = window_for_events.label();
+                let _ = commands::layout::layout_ui(&app_handle, window_id);
+                let _ = commands::layout::layout_window(&app_handle, window_id);
+
+                // Close context menu popup if open
+                if let Some(w) = app_handle.get_window("ctx-menu") {
+                    let _ = w.close();
+                }
+            }
+            tauri::WindowEvent::Moved(_) => {
+                // Close context menu popup when the window moves
+                if let Some(w) = app_handle.get_window("ctx-menu") {
+                    let _ = w.close();
+                }
+            }
+            tauri::WindowEvent::Destroyed => {
+                commands::windows::on_window_destroyed(&app_handle, window_for_events.label());
+            }
+            _ => {}
+        }
+    });
+
+    Ok(window)
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -16,34 +81,76 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(TabManager::new())
-        .manage(ChromeHeight::new())
+        .manage(LayoutInsets::new())
+        // Internal pages (aero://settings…) come from the app's own protocol handler
+        .register_uri_scheme_protocol(state::aero_pages::AERO_SCHEME, commands::aero_pages::aero_protocol)
         .invoke_handler(tauri::generate_handler![
             // Tab commands
             commands::tabs::tab_create,
             commands::tabs::tab_close,
+            commands::tabs::tab_close_many,
             commands::tabs::tab_set_active,
             commands::tabs::tab_get_all,
+            commands::tabs::tab_search,
             commands::tabs::tab_get_active,
             commands::tabs::tab_resize_all,
             commands::tabs::tab_duplicate,
             commands::tabs::tab_reorder,
+            commands::tabs::tab_pin,
+            commands::tabs::tab_unpin,
+            commands::tabs::tab_reopen_closed,
+            commands::tabs::tab_get_recently_closed,
+            commands::hibernation::tab_discard,
+            commands::watchdog::tab_recover,
             commands::tabs::__tab_title_update,
             commands::tabs::__tab_favicon_update,
+            commands::tabs::__tab_scroll_update,
+            commands::tabs::__tab_spa_navigate,
+            commands::watchdog::__tab_pong,
+            commands::tabs::__tab_open_link,
+            commands::tabs::__tab_audio_update,
+            commands::tabs::tab_set_muted,
             commands::tabs::ui_focus,
             commands::tabs::ui_set_height,
             commands::tabs::show_context_menu,
             commands::tabs::close_context_menu,
+            // Window commands
+            commands::windows::window_create,
+            commands::windows::window_close,
+            commands::windows::tab_move_to_window,
+            // Tab group commands
+            commands::tab_groups::tab_group_create,
+            commands::tab_groups::tab_group_add_tab,
+            commands::tab_groups::tab_group_remove_tab,
+            commands::tab_groups::tab_group_update,
+            commands::tab_groups::tab_group_set_collapsed,
+            commands::tab_groups::tab_group_close,
+            commands::tab_groups::tab_group_get_all,
+            commands::split_view::tab_split,
+            commands::split_view::tab_unsplit,
+            commands::split_view::tab_split_set_ratios,
+            commands::split_view::tab_split_get_all,
+            commands::layout::layout_set_inset,
+            commands::layout::layout_get_insets,
             // Navigation commands
             commands::navigation::navigate_to,
             commands::navigation::navigate_back,
             commands::navigation::navigate_forward,
             commands::navigation::navigate_refresh,
+            commands::navigation::navigate_retry,
             commands::navigation::navigate_stop,
             commands::navigation::navigate_get_url,
+            commands::navigation::navigate_get_history,
+            commands::navigation::navigate_go_to_index,
             // Find commands
             commands::find::find_in_page,
             commands::find::find_clear,
             commands::find::__find_result,
+            // Zoom commands
+            commands::zoom::tab_zoom_in,
+            commands::zoom::tab_zoom_out,
+            commands::zoom::tab_zoom_reset,
+            commands::zoom::tab_zoom_set,
             // Settings commands
             commands::settings::settings_get,
             commands::settings::settings_set,
@@ -64,6 +171,22 @@ pub fn run() {
             commands::bookmarks::bookmark_get_all,
             commands::bookmarks::bookmark_get,
             commands::bookmarks::bookmark_toggle_bar,
+            // Container commands
+            commands::containers::container_get_all,
+            commands::containers::container_create,
+            commands::containers::container_update,
+            commands::containers::container_delete,
+            // Search engine commands
+            commands::search_engines::search_engine_get_all,
+            commands::search_engines::search_engine_add,
+            commands::search_engines::search_engine_update,
+            commands::search_engines::search_engine_set_default,
+            commands::search_engines::search_engine_delete,
+            commands::search_engines::__tab_opensearch_found,
+            // Omnibox commands
+            commands::omnibox::omnibox_suggest,
+            // Internal page commands
+            commands::aero_pages::aero_pages_get_all,
         ])
         .setup(|app| {
             // Open the database in {app_data_dir}/default/browser.db
@@ -82,75 +205,30 @@ pub fn run() {
                 .map_err(|e| format!("Failed to seed settings: {}", e))?;
             db.seed_bookmarks()
                 .map_err(|e| format!("Failed to seed bookmarks: {}", e))?;
+            db.seed_search_engines()
+                .map_err(|e| format!("Failed to seed search engines: {}", e))?;
             app.manage(db);
 
-            let width = 1280.0_f64;
-            let height = 800.0_f64;
-
-            // Create the main window (no decorations — we render our own title bar)
-            let window = tauri::window::WindowBuilder::new(app, "main")
-                .title("Aero")
-                .inner_size(width, height)
-                .min_inner_size(400.0, 300.0)
-                .decorations(false)
-                .resizable(true)
-                .center()
-                .build()?;
-
-            // Browser UI webview (SvelteKit app) — only covers the chrome area (top 76px)
-            let ui_webview = tauri::webview::WebviewBuilder::new(
-                "browser-ui",
-                WebviewUrl::App("index.html".into()),
-            )
-            .transparent(true)
-            .auto_resize();
-
-            window.add_child(
-                ui_webview,
-                LogicalPosition::new(0.0, 0.0),
-                LogicalSize::new(width, CHROME_HEIGHT),
-            )?;
-
-            // Listen for window events — resize webviews and close context menu on move
-            let app_handle = app.handle().clone();
-            window.on_window_event(move |event| {
-                match event {
-                    tauri::WindowEvent::Resized(size) => {
-                        let scale = app_handle
-                            .get_window("main")
-                            .and_then(|w| w.scale_factor().ok())
-                            .unwrap_or(1.0);
-                        let new_width = size.width as f64 / scale;
+            create_browser_window(app.handle(), commands::windows::MAIN_WINDOW_ID)?;
 
-                        // Resize UI webview width (height uses dynamic chrome height)
-                        let chrome_h = app_handle
-                            .try_state::<ChromeHeight>()
-                            .map(|ch| ch.get())
-                            .unwrap_or(CHROME_HEIGHT);
-                        if let Some(ui) = app_handle.get_webview("browser-ui") {
-                            let _ = ui.set_size(LogicalSize::new(new_width, chrome_h));
-                        }
+            // Reopen the previous session's tabs (no-op unless restore_on_startup is "continue").
+            // A broken snapshot must never stop the browser from launching.
+            let _ = tauri::async_runtime::block_on(commands::session::restore_session(
+                app.handle().clone(),
+            ));
 
-                        // Resize all content webviews
-                        let _ = commands::tabs::tab_resize_all(app_handle.clone());
-
-                        // Close context menu popup if open
-                        if let Some(w) = app_handle.get_window("ctx-menu") {
-                            let _ = w.close();
-                        }
-                    }
-                    tauri::WindowEvent::Moved(_) => {
-                        // Close context menu popup when main window moves
-                        if let Some(w) = app_handle.get_window("ctx-menu") {
-                            let _ = w.close();
-                        }
-                    }
-                    _ => {}
-                }
-            });
+            // Hibernate background tabs that sit idle
+            commands::hibernation::spawn_idle_watcher(app.handle().clone());
+            commands::watchdog::spawn_watchdog(app.handle().clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running Aero");
+        .build(tauri::generate_context!())
+        .expect("error while building Aero")
+        .run(|app, event| {
+            // Final snapshot on exit — tab changes are also saved as they happen
+            if let tauri::RunEvent::Exit = event {
+                commands::session::save_session(app);
+            }
+        });
 }
@@ -0,0 +1,168 @@
+use serde::Serialize;
+
+/// Scheme of Aero's internal pages, served by the app's own protocol handler
+pub const AERO_SCHEME: &str = "aero";
+
+/// Where webviews load internal pages from. All pages share this one origin, each under
+/// its own path. WebView2 can't load custom schemes directly, so on Windows wry serves
+/// them as `http://<scheme>.localhost`.
+pub const WEBVIEW_ORIGIN: &str = if cfg!(windows) {
+    "http://aero.localhost"
+} else {
+    "aero://localhost"
+};
+
+/// An internal page: shown as `aero://<name>` and rendered by the SvelteKit route `/<name>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AeroPage {
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Offered on the about page — false for pages that only make sense when linked to
+    pub listed: bool,
+}
+
+/// Every internal page. Nothing else under aero:// loads.
+pub const AERO_PAGES: &[AeroPage] = &[
+    AeroPage { name: "newtab", title: "New Tab", listed: true },
+    AeroPage { name: "settings", title: "Settings", listed: true },
+    AeroPage { name: "history", title: "History", listed: true },
+    AeroPage { name: "bookmarks", title: "Bookmarks", listed: true },
+    AeroPage { name: "downloads", title: "Downloads", listed: true },
+    AeroPage { name: "about", title: "About Aero", listed: true },
+    AeroPage { name: "error", title: "Page Not Loaded", listed: false },
+];
+
+/// The registered page called `name` (case-insensitive)
+pub fn find_page(name: &str) -> Option<&'static AeroPage> {
+    AERO_PAGES.iter().find(|page| page.name.eq_ignore_ascii_case(name))
+}
+
+/// An aero:// URL: a registered page and whatever follows its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AeroUrl {
+    pub page: &'static AeroPage,
+    /// Subpath, query and fragment as given (`/search?q=x#top`), or empty
+    pub rest: String,
+}
+
+impl AeroUrl {
+    /// Parse an address bar URL like `aero://settings/search?q=x`. None for other
+    /// schemes and for pages that aren't registered.
+    pub fn parse(url: &str) -> Option<Self> {
+        let (scheme, rest) = url.split_once("://")?;
+        if !scheme.eq_ignore_ascii_case(AERO_SCHEME) {
+            return None;
+        }
+        let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let page = find_page(&rest[..end])?;
+
+        // A lone trailing slash adds nothing: aero://settings/ is aero://settings
+        let rest = &rest[end..];
+        let rest = match rest.strip_prefix('/') {
+            Some(after) if after.is_empty() || after.starts_with(['?', '#']) => after,
+            _ => rest,
+        };
+        Some(Self {
+            page,
+            rest: rest.to_string(),
+        })
+    }
+
+    /// Read back the URL a webview reports for an internal page. None for anything
+    /// else, including the app's own files on the same origin.
+    pub fn from_webview_url(url: &str) -> Option<Self> {
+        let path = url.strip_prefix(WEBVIEW_ORIGIN)?.strip_prefix('/')?;
+        Self::parse(&format!("{}://{}", AERO_SCHEME, path))
+    }
+
+    /// The URL shown in the address bar and kept in the tab
+    pub fn display(&self) -> String {
+        format!("{}://{}{}", AERO_SCHEME, self.page.name, self.rest)
+    }
+
+    /// The URL the webview loads
+    pub fn webview_url(&self) -> String {
+        format!("{}/{}{}", WEBVIEW_ORIGIN, self.page.name, self.rest)
+    }
+}
+
+/// What a request to the aero origin asks for, by its path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AeroRequest {
+    /// A page document — the SvelteKit app shell, which routes to the page itself
+    Page(&'static AeroPage),
+    /// One of the app's static files (scripts, styles, icons)
+    Asset(String),
+    NotFound,
+}
+
+impl AeroRequest {
+    pub fn from_path(path: &str) -> Self {
+        let path = path.trim_start_matches('/');
+        let first = path.split('/').next().unwrap_or_default();
+        if let Some(page) = find_page(first) {
+            return AeroRequest::Page(page);
+        }
+        // Built files live under _app/, the other static files at the root. HTML files
+        // are the browser UI's app shell, which only loads through a page.
+        let root_file = !path.contains('/') && path.contains('.') && !path.ends_with(".html");
+        if path.starts_with("_app/") || root_file {
+            return AeroRequest::Asset(format!("/{}", path));
+        }
+        AeroRequest::NotFound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_registered_pages_with_subpath_and_query() {
+        let url = AeroUrl::parse("AERO://Settings/search?q=a%20b#engines").unwrap();
+        assert_eq!(url.page.name, "settings");
+        assert_eq!(url.rest, "/search?q=a%20b#engines");
+        assert_eq!(url.display(), "aero://settings/search?q=a%20b#engines");
+
+        assert_eq!(AeroUrl::parse("aero://history/").unwrap().display(), "aero://history");
+        assert_eq!(AeroUrl::parse("aero://error/?url=x").unwrap().display(), "aero://error?url=x");
+        assert!(AeroUrl::parse("aero://nope").is_none());
+        assert!(AeroUrl::parse("aero://").is_none());
+        assert!(AeroUrl::parse("https://settings").is_none());
+    }
+
+    #[test]
+    fn webview_url_round_trips() {
+        for page in AERO_PAGES {
+            for rest in ["", "/sub/path", "?a=1&b=2", "/x?y=z#frag"] {
+                let display = format!("aero://{}{}", page.name, rest);
+                let url = AeroUrl::parse(&display).unwrap();
+                let back = AeroUrl::from_webview_url(&url.webview_url()).unwrap();
+                assert_eq!(back.display(), display);
+            }
+        }
+    }
+
+    #[test]
+    fn only_pages_map_back_from_the_webview() {
+        let origin = WEBVIEW_ORIGIN;
+        assert!(AeroUrl::from_webview_url(&format!("{}/", origin)).is_none());
+        assert!(AeroUrl::from_webview_url(&format!("{}/_app/start.js", origin)).is_none());
+        assert!(AeroUrl::from_webview_url("https://example.com/settings").is_none());
+    }
+
+    #[test]
+    fn requests_are_pages_assets_or_not_found() {
+        assert_eq!(AeroRequest::from_path("/bookmarks"), AeroRequest::Page(&AERO_PAGES[3]));
+        assert_eq!(AeroRequest::from_path("/settings/search"), AeroRequest::Page(&AERO_PAGES[1]));
+        assert_eq!(
+            AeroRequest::from_path("/_app/immutable/entry/start.js"),
+            AeroRequest::Asset("/_app/immutable/entry/start.js".to_string())
+        );
+        assert_eq!(AeroRequest::from_path("/favicon.png"), AeroRequest::Asset("/favicon.png".to_string()));
+        // The browser UI is the app's root page — it never loads in a tab
+        assert_eq!(AeroRequest::from_path("/"), AeroRequest::NotFound);
+        assert_eq!(AeroRequest::from_path("/index.html"), AeroRequest::NotFound);
+        assert_eq!(AeroRequest::from_path("/nope"), AeroRequest::NotFound);
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Default chrome height: 36px tab bar + 40px toolbar = 76px
+pub const DEFAULT_CHROME_HEIGHT: f64 = 76.0;
+
+/// Height added by the bookmarks bar
+pub const BOOKMARKS_BAR_HEIGHT: f64 = 28.0;
+
+/// Inset component for the tab bar + toolbar, always present
+pub const TOOLBAR: &str = "toolbar";
+
+/// Inset component for the bookmarks bar, present while it is shown
+pub const BOOKMARKS_BAR: &str = "bookmarks-bar";
+
+/// A rectangle in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+	pub x: f64,
+	pub y: f64,
+	pub width: f64,
+	pub height: f64,
+}
+
+/// Window edge an inset is taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edge {
+	Top,
+	Left,
+	Right,
+	Bottom,
+}
+
+/// Space reserved for browser UI on each edge of a window, in logical pixels.
+/// Sent to the frontend with `layout_changed`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Insets {
+	pub top: f64,
+	pub left: f64,
+	pub right: f64,
+	pub bottom: f64,
+}
+
+impl Insets {
+	/// The content area of a `width` x `height` window — what's left inside the insets
+	pub fn content_rect(&self, width: f64, height: f64) -> Rect {
+		Rect {
+			x: self.left,
+			y: self.top,
+			width: (width - self.left - self.right).max(0.0),
+			height: (height - self.top - self.bottom).max(0.0),
+		}
+	}
+
+	/// The area the browser UI webview covers. With only a top inset that is the
+	/// strip above the content; once UI sits on other edges (side panel, vertical tabs)
+	/// it spans the whole window and the content webviews are layered over it.
+	pub fn ui_rect(&self, width: f64, height: f64) -> Rect {
+		let top_only = self.left == 0.0 && self.right == 0.0 && self.bottom == 0.0;
+		Rect {
+			x: 0.0,
+			y: 0.0,
+			width,
+			height: if top_only { self.top.min(height) } else { height },
+		}
+	}
+}
+
+/// Thread-safe layout insets — each UI component (toolbar, bookmarks bar, side panel…)
+/// contributes a size on one edge under its own name, and the insets are their sums.
+pub struct LayoutInsets {
+	components: Mutex<HashMap<String, (Edge, f64)>>,
+}
+
+impl LayoutInsets {
+	pub fn new() -> Self {
+		let mut components = HashMap::new();
+		components.insert(TOOLBAR.to_string(), (Edge::Top, DEFAULT_CHROME_HEIGHT));
+		Self {
+			components: Mutex::new(components),
+		}
+	}
+
+	pub fn get(&self) -> Insets {
+		let components = self.components.lock().unwrap();
+		let mut insets = Insets::default();
+		for (edge, size) in components.values() {
+			match edge {
+				Edge::Top => insets.top += size,
+				Edge::Left => insets.left += size,
+				Edge::Right => insets.right += size,
+				Edge::Bottom => insets.bottom += size,
+			}
+		}
+		insets
+	}
+
+	/// Set a component's inset. A size of 0 (or less) removes the component.
+	/// Returns whether the insets changed.
+	pub fn set(&self, component: &str, edge: Edge, size: f64) -> bool {
+		let mut components = self.components.lock().unwrap();
+		if size <= 0.0 || !size.is_finite() {
+			return components.remove(component).is_some();
+		}
+		components.insert(component.to_string(), (edge, size)) != Some((edge, size))
+	}
+
+	pub fn set_bookmarks_bar(&self, visible: bool) -> bool {
+		let size = if visible { BOOKMARKS_BAR_HEIGHT } else { 0.0 };
+		self.set(BOOKMARKS_BAR, Edge::Top, size)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_insets() {
+		let layout = LayoutInsets::new();
+		assert_eq!(
+			layout.get(),
+			Insets { top: DEFAULT_CHROME_HEIGHT, ..Insets::default() }
+		);
+	}
+
+	#[test]
+	fn bookmarks_bar_toggle() {
+		let layout = LayoutInsets::new();
+		assert!(layout.set_bookmarks_bar(true));
+		assert_eq!(layout.get().top, DEFAULT_CHROME_HEIGHT + BOOKMARKS_BAR_HEIGHT);
+		assert!(!layout.set_bookmarks_bar(true));
+		assert!(layout.set_bookmarks_bar(false));
+		assert_eq!(layout.get().top, DEFAULT_CHROME_HEIGHT);
+	}
+
+	#[test]
+	fn components_add_up_per_edge() {
+		let layout = LayoutInsets::new();
+		layout.set("vertical-tabs", Edge::Left, 240.0);
+		layout.set("side-panel", Edge::Right, 320.0);
+		layout.set("find-bar", Edge::Bottom, 40.0);
+		layout.set("infobar", Edge::Top, 32.0);
+
+		let insets = layout.get();
+		assert_eq!(insets.top, DEFAULT_CHROME_HEIGHT + 32.0);
+		assert_eq!(insets.left, 240.0);
+		assert_eq!(insets.right, 320.0);
+		assert_eq!(insets.bottom, 40.0);
+
+		// Re-setting a component replaces its inset, possibly on another edge
+		layout.set("side-panel", Edge::Left, 100.0);
+		assert_eq!(layout.get().left, 340.0);
+		assert_eq!(layout.get().right, 0.0);
+
+		assert!(layout.set("vertical-tabs", Edge::Left, 0.0));
+		assert!(!layout.set("vertical-tabs", Edge::Left, 0.0));
+		assert_eq!(layout.get().left, 100.0);
+	}
+
+	#[test]
+	fn zero_size_removes_component() {
+		let layout = LayoutInsets::new();
+		layout.set("side-panel", Edge::Right, 320.0);
+		assert!(layout.set("side-panel", Edge::Right, 0.0));
+		assert_eq!(layout.get().right, 0.0);
+		assert!(!layout.set("side-panel", Edge::Right, f64::NAN));
+	}
+
+	#[test]
+	fn content_rect_sits_inside_insets() {
+		let insets = Insets { top: 76.0, left: 240.0, right: 40.0, bottom: 20.0 };
+		assert_eq!(
+			insets.content_rect(1280.0, 800.0),
+			Rect { x: 240.0, y: 76.0, width: 1000.0, height: 704.0 }
+		);
+		// Never negative on a tiny window
+		let rect = insets.content_rect(100.0, 50.0);
+		assert_eq!((rect.width, rect.height), (0.0, 0.0));
+	}
+
+	#[test]
+	fn ui_rect_covers_chrome_or_whole_window() {
+		let top_only = Insets { top: 76.0, ..Insets::default() };
+		assert_eq!(top_only.ui_rect(1280.0, 800.0).height, 76.0);
+
+		let sidebar = Insets { top: 76.0, left: 240.0, ..Insets::default() };
+		assert_eq!(
+			sidebar.ui_rect(1280.0, 800.0),
+			Rect { x: 0.0, y: 0.0, width: 1280.0, height: 800.0 }
+		);
+	}
+}
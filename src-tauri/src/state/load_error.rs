@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+/// Internal page shown in place of a page that failed to load
+pub const ERROR_PAGE: &str = "aero://error";
+
+/// Why a page couldn't be loaded, as told to the user on the error page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadErrorKind {
+    /// The host name didn't resolve
+    Dns,
+    /// Nothing accepted the connection
+    Refused,
+    /// The secure connection failed (bad certificate, handshake error…)
+    Tls,
+    /// The server took too long to answer
+    Timeout,
+    /// Any other network failure (connection reset, unreachable network…)
+    Connection,
+}
+
+impl LoadErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadErrorKind::Dns => "dns",
+            LoadErrorKind::Refused => "refused",
+            LoadErrorKind::Tls => "tls",
+            LoadErrorKind::Timeout => "timeout",
+            LoadErrorKind::Connection => "connection",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "dns" => Some(LoadErrorKind::Dns),
+            "refused" => Some(LoadErrorKind::Refused),
+            "tls" => Some(LoadErrorKind::Tls),
+            "timeout" => Some(LoadErrorKind::Timeout),
+            "connection" => Some(LoadErrorKind::Connection),
+            _ => None,
+        }
+    }
+
+    /// Classify a WebView2 `COREWEBVIEW2_WEB_ERROR_STATUS`. Cancelled navigations
+    /// (the user stopped or left the page) and authentication challenges aren't load failures.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn from_webview2_status(status: i32) -> Option<Self> {
+        match status {
+            // Certificate problems
+            1..=5 => Some(LoadErrorKind::Tls),
+            7 => Some(LoadErrorKind::Timeout),
+            // Cannot connect
+            12 => Some(LoadErrorKind::Refused),
+            // Host name not resolved
+            13 => Some(LoadErrorKind::Dns),
+            // Operation canceled
+            14 => None,
+            // Valid (proxy) authentication credentials required — the server answered
+            17 | 18 => None,
+            // Unknown, server unreachable, invalid response, connection aborted or reset,
+            // disconnected, redirect failed, unexpected error
+            0 | 6 | 8..=11 | 15 | 16 => Some(LoadErrorKind::Connection),
+            _ => None,
+        }
+    }
+}
+
+/// A navigation that failed: the URL the user asked for and why it didn't load
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadError {
+    pub url: String,
+    pub kind: LoadErrorKind,
+}
+
+impl LoadError {
+    pub fn new(url: &str, kind: LoadErrorKind) -> Self {
+        Self {
+            url: url.to_string(),
+            kind,
+        }
+    }
+
+    /// The error page for this failure: `aero://error?url=<failed url>&kind=<kind>`
+    pub fn page_url(&self) -> String {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("url", &self.url)
+            .append_pair("kind", self.kind.as_str())
+            .finish();
+        format!("{}?{}", ERROR_PAGE, query)
+    }
+
+    /// Read the failure back from an error page URL. None for any other URL.
+    pub fn from_page_url(page_url: &str) -> Option<Self> {
+        let query = page_url.strip_prefix(ERROR_PAGE)?.trim_start_matches('/').strip_prefix('?')?;
+        let mut url = None;
+        let mut kind = None;
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "url" => url = Some(value.into_owned()),
+                "kind" => kind = LoadErrorKind::parse(&value),
+                _ => {}
+            }
+        }
+        Some(Self {
+            url: url.filter(|u| !u.is_empty())?,
+            kind: kind?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_url_round_trips() {
+        let error = LoadError::new("https://example.com/a?b=1&c=d#top", LoadErrorKind::Dns);
+        let page = error.page_url();
+        assert!(page.starts_with("aero://error?url=https%3A%2F%2Fexample.com"));
+        assert_eq!(LoadError::from_page_url(&page), Some(error));
+    }
+
+    #[test]
+    fn other_urls_are_not_error_pages() {
+        assert_eq!(LoadError::from_page_url("aero://settings"), None);
+        assert_eq!(LoadError::from_page_url("aero://error"), None);
+        assert_eq!(LoadError::from_page_url("aero://error?url=https%3A%2F%2Fa.com"), None);
+        assert_eq!(LoadError::from_page_url("aero://error?url=https%3A%2F%2Fa.com&kind=bogus"), None);
+        assert_eq!(LoadError::from_page_url("https://error.com/?url=x&kind=dns"), None);
+    }
+
+    #[test]
+    fn webview2_statuses_are_classified() {
+        use LoadErrorKind::*;
+        let expected = [
+            (0, Some(Connection)),  // UNKNOWN
+            (1, Some(Tls)),         // CERTIFICATE_COMMON_NAME_IS_INCORRECT
+            (2, Some(Tls)),         // CERTIFICATE_EXPIRED
+            (3, Some(Tls)),         // CLIENT_CERTIFICATE_CONTAINS_ERRORS
+            (4, Some(Tls)),         // CERTIFICATE_REVOKED
+            (5, Some(Tls)),         // CERTIFICATE_IS_INVALID
+            (6, Some(Connection)),  // SERVER_UNREACHABLE
+            (7, Some(Timeout)),     // TIMEOUT
+            (8, Some(Connection)),  // ERROR_HTTP_INVALID_SERVER_RESPONSE
+            (9, Some(Connection)),  // CONNECTION_ABORTED
+            (10, Some(Connection)), // CONNECTION_RESET
+            (11, Some(Connection)), // DISCONNECTED
+            (12, Some(Refused)),    // CANNOT_CONNECT
+            (13, Some(Dns)),        // HOST_NAME_NOT_RESOLVED
+            (14, None),             // OPERATION_CANCELED
+            (15, Some(Connection)), // REDIRECT_FAILED
+            (16, Some(Connection)), // UNEXPECTED_ERROR
+            (17, None),             // VALID_AUTHENTICATION_CREDENTIALS_REQUIRED
+            (18, None),             // VALID_PROXY_AUTHENTICATION_CREDENTIALS_REQUIRED
+        ];
+        for (status, kind) in expected {
+            assert_eq!(LoadErrorKind::from_webview2_status(status), kind, "status {}", status);
+        }
+    }
+}
@@ -1,2 +1,14 @@
-pub mod chrome_height;
+pub mod aero_pages;
+pub mod layout;
+pub mod load_error;
+pub mod omnibox;
+pub mod opensearch;
+pub mod split_view;
+pub mod suggest;
+pub mod tab_close;
+pub mod tab_controller;
+pub mod tab_groups;
+pub mod tab_search;
 pub mod tab_state;
+pub mod watchdog;
+pub mod zoom;
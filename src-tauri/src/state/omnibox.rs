@@ -0,0 +1,319 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::Serialize;
+use url::form_urlencoded;
+
+use super::aero_pages::{AeroUrl, AERO_SCHEME};
+use crate::storage::search_engines::SearchEngine;
+
+/// Search URL template used when there is no default search engine
+pub const DEFAULT_SEARCH_ENGINE: &str = "https://www.google.com/search?q=";
+
+/// Schemes that are taken as-is even without `//` after the colon
+const OPAQUE_SCHEMES: &[&str] = &["about", "data", "mailto", "view-source"];
+
+/// What the address bar input turned out to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    /// A full URL with a scheme
+    Url,
+    /// A host with a public-looking domain name, loaded over https
+    Domain,
+    /// localhost or a *.localhost name, loaded over http
+    Localhost,
+    /// An IPv4 or bracketed IPv6 address, loaded over http
+    Ip,
+    /// A dotless host name given with a port or path (`nas:5000`, `router/`), loaded over http
+    Intranet,
+    /// An internal aero:// page
+    Aero,
+    /// Anything else — sent to the search engine
+    Search,
+}
+
+/// Address bar input resolved to the URL a tab should load
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedInput {
+    pub kind: InputKind,
+    pub url: String,
+    /// The search engine used, for searches
+    pub engine_id: Option<String>,
+}
+
+/// Build a search URL for `query` from an engine template. `%s` (or OpenSearch's
+/// `{searchTerms}`) marks where the query goes; templates without one get it appended.
+pub fn search_url(template: &str, query: &str) -> String {
+    let template = match template.trim() {
+        "" => DEFAULT_SEARCH_ENGINE,
+        t => t,
+    };
+    let encoded: String = form_urlencoded::byte_serialize(query.as_bytes()).collect();
+
+    if template.contains("%s") {
+        template.replace("%s", &encoded)
+    } else if template.contains("{searchTerms}") {
+        template.replace("{searchTerms}", &encoded)
+    } else {
+        format!("{}{}", template, encoded)
+    }
+}
+
+/// The scheme of `input` if it starts with one (`https:`, `aero:`…)
+fn scheme_of(input: &str) -> Option<&str> {
+    let (scheme, _) = input.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next()?.is_ascii_alphabetic()
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// A DNS label: letters, digits and inner hyphens
+fn is_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// How a scheme-less host (without its port) should be loaded, if it is one at all
+fn host_kind(host: &str, explicit: bool) -> Option<InputKind> {
+    let lower = host.to_ascii_lowercase();
+
+    if let Some(inner) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        return inner.parse::<Ipv6Addr>().is_ok().then_some(InputKind::Ip);
+    }
+    if host.parse::<Ipv4Addr>().is_ok() {
+        return Some(InputKind::Ip);
+    }
+    if lower == "localhost" || lower.ends_with(".localhost") {
+        return lower.split('.').all(is_label).then_some(InputKind::Localhost);
+    }
+
+    let labels: Vec<&str> = host.trim_end_matches('.').split('.').collect();
+    if !labels.iter().all(|l| is_label(l)) {
+        return None;
+    }
+    match labels.as_slice() {
+        // A single word is only a host when a port or path says so
+        [_] => explicit.then_some(InputKind::Intranet),
+        [.., tld] => {
+            let tld = tld.to_ascii_lowercase();
+            let is_tld = tld.starts_with("xn--")
+                || (tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+            is_tld.then_some(InputKind::Domain)
+        }
+        [] => None,
+    }
+}
+
+/// Work out what the user meant by what they typed in the address bar: a URL, a
+/// bare host (domain, localhost, IP, intranet name), an aero:// page, or a search.
+/// A search goes to the engine whose keyword starts the input (`w rust`), otherwise
+/// to the default one of `engines` (see `search_url`). A leading `?` forces a
+/// search. Returns None for blank input.
+pub fn resolve_input(input: &str, engines: &[SearchEngine]) -> Option<ResolvedInput> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    let search_with = |engine: Option<&SearchEngine>, query: &str| ResolvedInput {
+        kind: InputKind::Search,
+        url: search_url(engine.map_or("", |e| e.url.as_str()), query),
+        engine_id: engine.map(|e| e.id.clone()),
+    };
+    let search = |query: &str| search_with(engines.iter().find(|e| e.is_default), query);
+
+    if let Some(query) = input.strip_prefix('?') {
+        let query = query.trim();
+        return (!query.is_empty()).then(|| search(query));
+    }
+
+    if let Some((word, query)) = input.split_once(char::is_whitespace) {
+        let word = word.to_lowercase();
+        let keyword_engine = engines.iter().find(|e| e.keyword.as_deref() == Some(word.as_str()));
+        if let Some(engine) = keyword_engine {
+            return Some(search_with(Some(engine), query.trim()));
+        }
+    }
+
+    if let Some(scheme) = scheme_of(input) {
+        let scheme = scheme.to_ascii_lowercase();
+        let rest = &input[scheme.len() + 1..];
+        if scheme == AERO_SCHEME && rest.starts_with("//") {
+            let url = format!("{}:{}", AERO_SCHEME, rest);
+            return Some(ResolvedInput {
+                kind: InputKind::Aero,
+                // Registered pages in their canonical form; others fail when loaded
+                url: AeroUrl::parse(&url).map_or(url, |page| page.display()),
+                engine_id: None,
+            });
+        }
+        if (rest.starts_with("//") || OPAQUE_SCHEMES.contains(&scheme.as_str()))
+            && !input.contains(char::is_whitespace)
+        {
+            if let Ok(url) = url::Url::parse(input) {
+                return Some(ResolvedInput {
+                    kind: InputKind::Url,
+                    url: url.to_string(),
+                    engine_id: None,
+                });
+            }
+        }
+    }
+
+    if input.contains(char::is_whitespace) {
+        return Some(search(input));
+    }
+
+    // host[:port] then an optional path, query or fragment
+    let host_end = input.find(['/', '?', '#']).unwrap_or(input.len());
+    let (authority, tail) = input.split_at(host_end);
+    let (host, port) = match authority.rfind(':') {
+        // The colon inside an IPv6 address isn't a port separator
+        Some(i) if !authority[i..].contains(']') => (&authority[..i], Some(&authority[i + 1..])),
+        _ => (authority, None),
+    };
+    if port.is_some_and(|p| p.is_empty() || p.len() > 5 || !p.chars().all(|c| c.is_ascii_digit())) {
+        return Some(search(input));
+    }
+
+    let explicit = port.is_some() || tail.starts_with('/');
+    let kind = match host_kind(host, explicit) {
+        Some(kind) => kind,
+        None => return Some(search(input)),
+    };
+    let scheme = if kind == InputKind::Domain { "https" } else { "http" };
+
+    match url::Url::parse(&format!("{}://{}", scheme, input)) {
+        Ok(url) => Some(ResolvedInput { kind, url: url.to_string(), engine_id: None }),
+        Err(_) => Some(search(input)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(id: &str, keyword: Option<&str>, url: &str, is_default: bool) -> SearchEngine {
+        SearchEngine {
+            id: id.to_string(),
+            name: id.to_string(),
+            keyword: keyword.map(str::to_string),
+            url: url.to_string(),
+            suggest_url: None,
+            favicon: None,
+            is_default,
+            position: 0,
+        }
+    }
+
+    fn engines() -> Vec<SearchEngine> {
+        vec![
+            engine("wiki", Some("w"), "https://wiki.example/?search=%s", false),
+            engine("example", Some("ex"), "https://search.example/?q=%s", true),
+        ]
+    }
+
+    fn resolve(input: &str) -> (InputKind, String) {
+        let resolved = resolve_input(input, &engines()).unwrap();
+        (resolved.kind, resolved.url)
+    }
+
+    #[test]
+    fn resolves_input_table() {
+        use InputKind::*;
+
+        let cases: &[(&str, InputKind, &str)] = &[
+            // Full URLs are kept (normalised by the URL parser)
+            ("http://example.com", Url, "http://example.com/"),
+            ("https://github.com/foo", Url, "https://github.com/foo"),
+            ("HTTPS://Example.COM/Path", Url, "https://example.com/Path"),
+            ("file:///home/user/index.html", Url, "file:///home/user/index.html"),
+            ("about:blank", Url, "about:blank"),
+            ("view-source:https://example.com", Url, "view-source:https://example.com"),
+            // Internal pages, in the registry's form
+            ("aero://settings", Aero, "aero://settings"),
+            ("aero://history/", Aero, "aero://history"),
+            ("AERO://Bookmarks", Aero, "aero://bookmarks"),
+            ("aero://settings/search?q=x", Aero, "aero://settings/search?q=x"),
+            // Bare domains load over https
+            ("google.com", Domain, "https://google.com/"),
+            ("example.co.uk", Domain, "https://example.co.uk/"),
+            ("sub.domain.org/path?x=1#top", Domain, "https://sub.domain.org/path?x=1#top"),
+            ("example.com:8443", Domain, "https://example.com:8443/"),
+            ("  google.com  ", Domain, "https://google.com/"),
+            ("xn--bcher-kva.example", Domain, "https://xn--bcher-kva.example/"),
+            // Local and private hosts load over http
+            ("localhost", Localhost, "http://localhost/"),
+            ("localhost:3000", Localhost, "http://localhost:3000/"),
+            ("app.localhost:5173/dev", Localhost, "http://app.localhost:5173/dev"),
+            ("127.0.0.1", Ip, "http://127.0.0.1/"),
+            ("192.168.1.1:8080/admin", Ip, "http://192.168.1.1:8080/admin"),
+            ("[::1]:8080", Ip, "http://[::1]:8080/"),
+            ("nas:5000", Intranet, "http://nas:5000/"),
+            ("router/", Intranet, "http://router/"),
+            ("intranet/wiki/Home", Intranet, "http://intranet/wiki/Home"),
+            // Everything else is a search
+            ("hello", Search, "https://search.example/?q=hello"),
+            ("how to cook pasta", Search, "https://search.example/?q=how+to+cook+pasta"),
+            ("c++ tutorial", Search, "https://search.example/?q=c%2B%2B+tutorial"),
+            ("what is example.com", Search, "https://search.example/?q=what+is+example.com"),
+            ("1.5", Search, "https://search.example/?q=1.5"),
+            ("e.g.", Search, "https://search.example/?q=e.g."),
+            ("foo:bar", Search, "https://search.example/?q=foo%3Abar"),
+            ("-bad-.com", Search, "https://search.example/?q=-bad-.com"),
+            ("?google.com", Search, "https://search.example/?q=google.com"),
+            ("100%", Search, "https://search.example/?q=100%25"),
+            // A leading keyword picks the engine
+            ("w rust", Search, "https://wiki.example/?search=rust"),
+            ("W  Rust lang ", Search, "https://wiki.example/?search=Rust+lang"),
+            ("w", Search, "https://search.example/?q=w"),
+            ("wx rust", Search, "https://search.example/?q=wx+rust"),
+            ("?w rust", Search, "https://search.example/?q=w+rust"),
+        ];
+
+        for (input, kind, url) in cases {
+            assert_eq!(resolve(input), (*kind, url.to_string()), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn blank_input_resolves_to_nothing() {
+        assert_eq!(resolve_input("", &engines()), None);
+        assert_eq!(resolve_input("   ", &engines()), None);
+        assert_eq!(resolve_input("?  ", &engines()), None);
+    }
+
+    #[test]
+    fn searches_record_their_engine() {
+        let engine_of = |input: &str, engines: &[SearchEngine]| resolve_input(input, engines).unwrap().engine_id;
+        assert_eq!(engine_of("w rust", &engines()).as_deref(), Some("wiki"));
+        assert_eq!(engine_of("rust", &engines()).as_deref(), Some("example"));
+        assert_eq!(engine_of("example.com", &engines()), None);
+
+        // Without a default engine searches still work
+        let resolved = resolve_input("rust", &[]).unwrap();
+        assert_eq!(resolved.url, "https://www.google.com/search?q=rust");
+        assert_eq!(resolved.engine_id, None);
+    }
+
+    #[test]
+    fn search_url_fills_template() {
+        assert_eq!(
+            search_url("https://duckduckgo.com/?q=", "rust lang"),
+            "https://duckduckgo.com/?q=rust+lang"
+        );
+        assert_eq!(
+            search_url("https://example.com/search?q=%s&src=aero", "a&b"),
+            "https://example.com/search?q=a%26b&src=aero"
+        );
+        assert_eq!(
+            search_url("https://example.com/?q={searchTerms}", "ü"),
+            "https://example.com/?q=%C3%BC"
+        );
+        // An empty setting falls back to the default engine
+        assert_eq!(search_url("", "cats"), "https://www.google.com/search?q=cats");
+    }
+}
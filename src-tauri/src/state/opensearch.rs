@@ -0,0 +1,217 @@
+use roxmltree::{Document, Node};
+use serde::Serialize;
+
+/// A search engine a site offers through an OpenSearch description
+/// (`<link rel="search" type="application/opensearchdescription+xml">`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpenSearchDescription {
+    pub name: String,
+    /// Results page URL with `%s` where the query goes
+    pub url: String,
+    /// JSON suggestions URL with `%s` where the query goes
+    pub suggest_url: Option<String>,
+    pub favicon: Option<String>,
+}
+
+/// `os:searchTerms` → `searchTerms`
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name().eq_ignore_ascii_case(name))
+        .map(|a| a.value())
+}
+
+/// Element children of `node` called `name`, whatever their namespace
+fn children<'a, 'input>(node: Node<'a, 'input>, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// All the text inside an element, CDATA included
+fn text_of(node: Node<'_, '_>) -> String {
+    node.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).collect()
+}
+
+/// Turn an OpenSearch URL template (plus any Mozilla `<Param>`s) into a `%s`
+/// template. Returns None unless it's an http(s) URL that takes the query.
+fn fill_template(template: &str, params: &[(String, String)]) -> Option<String> {
+    let fill = |text: &str| -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else { break };
+            out.push_str(&rest[..start]);
+            let inner = &rest[start + 1..start + len];
+            let optional = inner.ends_with('?');
+            let value = match local_name(inner.trim_end_matches('?')) {
+                "searchTerms" => "%s",
+                "inputEncoding" | "outputEncoding" => "UTF-8",
+                "language" => "*",
+                "startIndex" | "startPage" if !optional => "1",
+                "count" if !optional => "20",
+                _ => "",
+            };
+            out.push_str(value);
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+        out
+    };
+
+    let mut url = fill(template.trim());
+    for (name, value) in params {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(name);
+        url.push('=');
+        url.push_str(&fill(value));
+    }
+
+    let parsed = url::Url::parse(&url.replace("%s", "test")).ok()?;
+    let web = parsed.scheme() == "http" || parsed.scheme() == "https";
+    (web && url.contains("%s")).then_some(url)
+}
+
+/// Parse an OpenSearch description document. It needs a name and a GET
+/// `text/html` URL template; a JSON suggestions URL and an icon are picked up
+/// when present (16px icons preferred).
+pub fn parse_opensearch(xml: &str) -> Result<OpenSearchDescription, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("Invalid OpenSearch description: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "OpenSearchDescription" {
+        return Err("Not an OpenSearch description".to_string());
+    }
+
+    let name = children(root, "ShortName")
+        .map(|n| text_of(n).trim().to_string())
+        .find(|name| !name.is_empty())
+        .ok_or("OpenSearch description has no ShortName")?;
+
+    let mut url = None;
+    let mut suggest_url = None;
+    for node in children(root, "Url") {
+        let method = attr(node, "method").unwrap_or("get");
+        let rel = attr(node, "rel").unwrap_or("results");
+        if !method.eq_ignore_ascii_case("get") || (rel != "results" && rel != "suggestions") {
+            continue;
+        }
+        let slot = match attr(node, "type").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "text/html" => &mut url,
+            "application/x-suggestions+json" => &mut suggest_url,
+            _ => continue,
+        };
+        if slot.is_none() {
+            let params: Vec<(String, String)> = children(node, "Param")
+                .filter_map(|p| Some((attr(p, "name")?.to_string(), attr(p, "value")?.to_string())))
+                .collect();
+            *slot = fill_template(attr(node, "template").unwrap_or_default(), &params);
+        }
+    }
+
+    let mut favicon: Option<(bool, String)> = None;
+    for node in children(root, "Image") {
+        let is_16 = attr(node, "width") == Some("16");
+        let src = text_of(node).trim().to_string();
+        let better = favicon.as_ref().is_none_or(|(was_16, _)| is_16 && !was_16);
+        if !src.is_empty() && better {
+            favicon = Some((is_16, src));
+        }
+    }
+
+    let url = url.ok_or("OpenSearch description has no usable search URL")?;
+    Ok(OpenSearchDescription {
+        name,
+        url,
+        suggest_url,
+        favicon: favicon.map(|(_, src)| src),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_standard_description() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- Wikipedia -->
+            <OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/">
+                <ShortName>Wikipedia (en)</ShortName>
+                <Description>Wikipedia (en)</Description>
+                <Image height="64" width="64" type="image/png">https://en.wikipedia.org/static/apple-touch.png</Image>
+                <Image height="16" width="16" type="image/x-icon">https://en.wikipedia.org/favicon.ico</Image>
+                <Url type="text/html" method="get"
+                     template="https://en.wikipedia.org/w/index.php?title=Special:Search&amp;search={searchTerms}"/>
+                <Url type="application/x-suggestions+json" method="get"
+                     template="https://en.wikipedia.org/w/api.php?action=opensearch&amp;search={searchTerms}&amp;namespace=0"/>
+                <InputEncoding>UTF-8</InputEncoding>
+            </OpenSearchDescription>"#;
+
+        assert_eq!(
+            parse_opensearch(xml).unwrap(),
+            OpenSearchDescription {
+                name: "Wikipedia (en)".to_string(),
+                url: "https://en.wikipedia.org/w/index.php?title=Special:Search&search=%s".to_string(),
+                suggest_url: Some(
+                    "https://en.wikipedia.org/w/api.php?action=opensearch&search=%s&namespace=0"
+                        .to_string()
+                ),
+                favicon: Some("https://en.wikipedia.org/favicon.ico".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn handles_prefixes_params_and_cdata() {
+        let xml = r#"<os:OpenSearchDescription xmlns:os="http://a9.com/-/spec/opensearch/1.1/"
+                xmlns:moz="http://www.mozilla.org/2006/browser/search/">
+            <os:ShortName><![CDATA[Docs & More]]></os:ShortName>
+            <os:Url type="application/x-suggestions+json" template="https://docs.example/suggest?q={searchTerms}"/>
+            <os:Url type="text/html" method="GET" template='https://docs.example/search'>
+                <moz:Param name="q" value="{searchTerms}"/>
+                <moz:Param name="lang" value="{language?}"/>
+                <moz:Param name="page" value="{startPage}"/>
+            </os:Url>
+        </os:OpenSearchDescription>"#;
+
+        let desc = parse_opensearch(xml).unwrap();
+        assert_eq!(desc.name, "Docs & More");
+        assert_eq!(desc.url, "https://docs.example/search?q=%s&lang=*&page=1");
+        assert_eq!(desc.suggest_url.as_deref(), Some("https://docs.example/suggest?q=%s"));
+        assert_eq!(desc.favicon, None);
+    }
+
+    #[test]
+    fn rejects_unusable_descriptions() {
+        // Not OpenSearch at all
+        assert!(parse_opensearch("<html><body>Not found</body></html>").is_err());
+        assert!(parse_opensearch("").is_err());
+        // No name
+        assert!(parse_opensearch(
+            r#"<OpenSearchDescription><Url type="text/html" template="https://a.com/?q={searchTerms}"/></OpenSearchDescription>"#
+        )
+        .is_err());
+        // POST only, or a template that doesn't take the query
+        assert!(parse_opensearch(
+            r#"<OpenSearchDescription><ShortName>A</ShortName><Url type="text/html" method="post" template="https://a.com/?q={searchTerms}"/></OpenSearchDescription>"#
+        )
+        .is_err());
+        assert!(parse_opensearch(
+            r#"<OpenSearchDescription><ShortName>A</ShortName><Url type="text/html" template="https://a.com/"/></OpenSearchDescription>"#
+        )
+        .is_err());
+        // Not a web URL
+        assert!(parse_opensearch(
+            r#"<OpenSearchDescription><ShortName>A</ShortName><Url type="text/html" template="javascript:{searchTerms}"/></OpenSearchDescription>"#
+        )
+        .is_err());
+        // DTDs (and the entity expansion they bring) aren't accepted from sites
+        assert!(parse_opensearch(
+            r#"<!DOCTYPE d [<!ENTITY n "A">]><OpenSearchDescription><ShortName>&n;</ShortName><Url type="text/html" template="https://a.com/?q={searchTerms}"/></OpenSearchDescription>"#
+        )
+        .is_err());
+        // Broken markup
+        assert!(parse_opensearch(r#"<OpenSearchDescription><ShortName>A</ShortName><Url type="text/html template=""#).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::layout::Rect;
+use super::tab_state::TabManager;
+
+/// Global split ID counter — ensures unique split IDs across the app lifetime
+static SPLIT_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Gap between split panes in logical pixels (where the divider sits)
+pub const SPLIT_GAP: f64 = 4.0;
+
+/// Smallest share of the content width a pane can be dragged down to
+pub const MIN_PANE_RATIO: f64 = 0.1;
+
+/// Generate the next unique split ID (e.g. "split-1", "split-2")
+pub fn next_split_id() -> String {
+    let id = SPLIT_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("split-{}", id)
+}
+
+/// Two or more tabs of one window shown side by side, sent to the frontend.
+/// The split is shown whenever one of its tabs is the window's active tab.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitView {
+    pub id: String,
+    pub window_id: String,
+    /// Member tabs, left to right
+    pub labels: Vec<String>,
+    /// Share of the content width for each pane — same length as `labels`, sums to 1
+    pub ratios: Vec<f64>,
+}
+
+/// Equal shares for `n` panes
+pub fn even_ratios(n: usize) -> Vec<f64> {
+    vec![1.0 / n as f64; n]
+}
+
+/// Make `ratios` usable for `n` panes: no pane below MIN_PANE_RATIO and a total of 1.
+/// Falls back to equal shares when the count is wrong or a value isn't a positive number.
+pub fn normalize_ratios(ratios: &[f64], n: usize) -> Vec<f64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    if ratios.len() != n || ratios.iter().any(|r| !r.is_finite() || *r <= 0.0) {
+        return even_ratios(n);
+    }
+
+    let total: f64 = ratios.iter().sum();
+    let min = MIN_PANE_RATIO.min(1.0 / n as f64);
+    let clamped: Vec<f64> = ratios.iter().map(|r| (r / total).max(min)).collect();
+
+    // Clamping small panes up adds width — take it back from the panes above the minimum
+    let excess: f64 = clamped.iter().sum::<f64>() - 1.0;
+    let room: f64 = clamped.iter().map(|r| r - min).sum();
+    if excess <= 0.0 || room <= 0.0 {
+        return clamped;
+    }
+    clamped.iter().map(|r| r - (r - min) / room * excess).collect()
+}
+
+/// Lay panes out left to right across `area`, `gap` apart.
+/// The last pane takes whatever width is left so rounding never leaves a sliver.
+pub fn split_rects(area: Rect, ratios: &[f64], gap: f64) -> Vec<Rect> {
+    if ratios.is_empty() {
+        return Vec::new();
+    }
+
+    let gaps = gap * (ratios.len() - 1) as f64;
+    let available = (area.width - gaps).max(0.0);
+    let mut x = area.x;
+    let mut rects = Vec::with_capacity(ratios.len());
+
+    for (i, ratio) in ratios.iter().enumerate() {
+        let width = if i + 1 == ratios.len() {
+            (area.x + area.width - x).max(0.0)
+        } else {
+            (available * ratio).floor()
+        };
+        rects.push(Rect { x, y: area.y, width, height: area.height });
+        x += width + gap;
+    }
+    rects
+}
+
+impl TabManager {
+    pub fn get_splits(&self) -> Vec<SplitView> {
+        let splits = self.splits.lock().unwrap();
+        splits.clone()
+    }
+
+    /// The split a tab is part of, if any
+    pub fn split_of(&self, label: &str) -> Option<SplitView> {
+        let splits = self.splits.lock().unwrap();
+        splits.iter().find(|s| s.labels.iter().any(|l| l == label)).cloned()
+    }
+
+    /// Whether a tab is on screen: its window's active tab, or a pane of the active tab's split
+    pub fn is_visible(&self, label: &str) -> bool {
+        match self.split_of(label) {
+            Some(split) => split.labels.iter().any(|l| self.is_active(l)),
+            None => self.is_active(label),
+        }
+    }
+
+    /// Put tabs side by side. They must all be in one window; tabs already in
+    /// another split leave it first. Returns the new split and any splits that
+    /// were dissolved because too few tabs were left in them.
+    pub fn add_split(
+        &self,
+        labels: &[String],
+        ratios: Option<&[f64]>,
+    ) -> Result<(SplitView, Vec<String>), String> {
+        if labels.len() < 2 {
+            return Err("A split needs at least two tabs".to_string());
+        }
+        let unique = labels
+            .iter()
+            .enumerate()
+            .all(|(i, l)| !labels[..i].contains(l));
+        if !unique {
+            return Err("A tab can only appear once in a split".to_string());
+        }
+
+        let tabs = self.tabs.lock().unwrap();
+        let mut window_id = None;
+        for label in labels {
+            let tab = tabs.iter().find(|t| &t.label == label).ok_or("Tab not found")?;
+            match &window_id {
+                None => window_id = Some(tab.window_id.clone()),
+                Some(id) if *id != tab.window_id => {
+                    return Err("Split tabs must be in the same window".to_string());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let split = SplitView {
+            id: next_split_id(),
+            window_id: window_id.unwrap_or_default(),
+            labels: labels.to_vec(),
+            ratios: normalize_ratios(ratios.unwrap_or(&[]), labels.len()),
+        };
+
+        let mut splits = self.splits.lock().unwrap();
+        let mut dissolved = Vec::new();
+        for existing in splits.iter_mut() {
+            existing.labels.retain(|l| !labels.contains(l));
+        }
+        splits.retain(|s| {
+            let keep = s.labels.len() >= 2;
+            if !keep {
+                dissolved.push(s.id.clone());
+            }
+            keep
+        });
+        for existing in splits.iter_mut() {
+            if existing.ratios.len() != existing.labels.len() {
+                existing.ratios = even_ratios(existing.labels.len());
+            }
+        }
+        splits.push(split.clone());
+
+        Ok((split, dissolved))
+    }
+
+    pub fn remove_split(&self, id: &str) -> Option<SplitView> {
+        let mut splits = self.splits.lock().unwrap();
+        let pos = splits.iter().position(|s| s.id == id)?;
+        Some(splits.remove(pos))
+    }
+
+    /// Set a split's pane widths (normalised) and return its new state
+    pub fn set_split_ratios(&self, id: &str, ratios: &[f64]) -> Option<SplitView> {
+        let mut splits = self.splits.lock().unwrap();
+        let split = splits.iter_mut().find(|s| s.id == id)?;
+        split.ratios = normalize_ratios(ratios, split.labels.len());
+        Some(split.clone())
+    }
+
+    /// Drop split members that were closed or moved to another window.
+    /// Returns the splits that changed and the IDs of those dissolved
+    /// because fewer than two tabs were left.
+    pub fn prune_splits(&self) -> (Vec<SplitView>, Vec<String>) {
+        let tabs = self.tabs.lock().unwrap();
+        let mut splits = self.splits.lock().unwrap();
+
+        let mut changed = Vec::new();
+        let mut dissolved = Vec::new();
+        splits.retain_mut(|split| {
+            let before = split.labels.len();
+            split.labels.retain(|l| {
+                tabs.iter()
+                    .any(|t| &t.label == l && t.window_id == split.window_id)
+            });
+            if split.labels.len() < 2 {
+                dissolved.push(split.id.clone());
+                return false;
+            }
+            if split.labels.len() != before {
+                split.ratios = even_ratios(split.labels.len());
+                changed.push(split.clone());
+            }
+            true
+        });
+        (changed, dissolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tab_state::TabInfo;
+
+    fn make_tab(label: &str, window_id: &str) -> TabInfo {
+        TabInfo::for_test(label, "https://example.com", window_id)
+    }
+
+    fn labels(list: &[&str]) -> Vec<String> {
+        list.iter().map(|l| l.to_string()).collect()
+    }
+
+    /// Helper: main has t1, t2, t3; w2 has u1
+    fn manager() -> TabManager {
+        let tm = TabManager::new();
+        for label in ["t1", "t2", "t3"] {
+            tm.add_tab(make_tab(label, "main"));
+        }
+        tm.add_tab(make_tab("u1", "w2"));
+        tm
+    }
+
+    fn area() -> Rect {
+        Rect { x: 0.0, y: 76.0, width: 1004.0, height: 700.0 }
+    }
+
+    // ── Layout engine ──────────────────────────────────────
+
+    #[test]
+    fn even_split_of_two() {
+        let rects = split_rects(area(), &even_ratios(2), SPLIT_GAP);
+        assert_eq!(rects[0], Rect { x: 0.0, y: 76.0, width: 500.0, height: 700.0 });
+        assert_eq!(rects[1], Rect { x: 504.0, y: 76.0, width: 500.0, height: 700.0 });
+    }
+
+    #[test]
+    fn panes_fill_the_area_exactly() {
+        let rects = split_rects(area(), &even_ratios(3), SPLIT_GAP);
+        let last = rects.last().unwrap();
+        assert_eq!(last.x + last.width, 1004.0);
+        for pair in rects.windows(2) {
+            assert_eq!(pair[1].x - (pair[0].x + pair[0].width), SPLIT_GAP);
+        }
+    }
+
+    #[test]
+    fn uneven_ratios_follow_the_divider() {
+        let rects = split_rects(area(), &[0.25, 0.75], SPLIT_GAP);
+        assert_eq!(rects[0].width, 250.0);
+        assert_eq!(rects[1].x, 254.0);
+        assert_eq!(rects[1].width, 750.0);
+    }
+
+    #[test]
+    fn tiny_area_never_goes_negative() {
+        let tiny = Rect { x: 0.0, y: 0.0, width: 2.0, height: 10.0 };
+        for rect in split_rects(tiny, &even_ratios(3), SPLIT_GAP) {
+            assert!(rect.width >= 0.0);
+        }
+    }
+
+    #[test]
+    fn normalize_scales_to_one() {
+        assert_eq!(normalize_ratios(&[1.0, 3.0], 2), vec![0.25, 0.75]);
+    }
+
+    #[test]
+    fn normalize_falls_back_to_even() {
+        assert_eq!(normalize_ratios(&[0.5], 2), even_ratios(2));
+        assert_eq!(normalize_ratios(&[0.5, -1.0], 2), even_ratios(2));
+        assert_eq!(normalize_ratios(&[f64::NAN, 0.5], 2), even_ratios(2));
+        assert!(normalize_ratios(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn normalize_clamps_to_min_pane() {
+        let ratios = normalize_ratios(&[0.01, 0.99], 2);
+        assert!((ratios[0] - MIN_PANE_RATIO).abs() < 1e-9);
+        assert!((ratios.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    // ── Splits in TabManager ───────────────────────────────
+
+    #[test]
+    fn add_split_records_members() {
+        let tm = manager();
+        let (split, dissolved) = tm.add_split(&labels(&["t1", "t3"]), None).unwrap();
+        assert_eq!(split.window_id, "main");
+        assert_eq!(split.ratios, even_ratios(2));
+        assert!(dissolved.is_empty());
+        assert_eq!(tm.split_of("t3").unwrap().id, split.id);
+        assert!(tm.split_of("t2").is_none());
+    }
+
+    #[test]
+    fn add_split_validates_tabs() {
+        let tm = manager();
+        assert!(tm.add_split(&labels(&["t1"]), None).is_err());
+        assert!(tm.add_split(&labels(&["t1", "t1"]), None).is_err());
+        assert!(tm.add_split(&labels(&["t1", "missing"]), None).is_err());
+        assert!(tm.add_split(&labels(&["t1", "u1"]), None).is_err());
+        assert!(tm.get_splits().is_empty());
+    }
+
+    #[test]
+    fn add_split_takes_tabs_from_existing_split() {
+        let tm = manager();
+        let (first, _) = tm.add_split(&labels(&["t1", "t2"]), None).unwrap();
+        let (_, dissolved) = tm.add_split(&labels(&["t2", "t3"]), None).unwrap();
+        assert_eq!(dissolved, vec![first.id]);
+        assert_eq!(tm.get_splits().len(), 1);
+    }
+
+    #[test]
+    fn set_ratios_normalises() {
+        let tm = manager();
+        let (split, _) = tm.add_split(&labels(&["t1", "t2"]), None).unwrap();
+        let updated = tm.set_split_ratios(&split.id, &[3.0, 1.0]).unwrap();
+        assert_eq!(updated.ratios, vec![0.75, 0.25]);
+        assert!(tm.set_split_ratios("missing", &[1.0]).is_none());
+    }
+
+    #[test]
+    fn visible_follows_active_split_member() {
+        let tm = manager();
+        tm.add_split(&labels(&["t1", "t2"]), None).unwrap();
+        tm.set_active_tab("main", Some("t2".to_string()));
+        assert!(tm.is_visible("t1"));
+        assert!(tm.is_visible("t2"));
+        assert!(!tm.is_visible("t3"));
+
+        // Switching to a tab outside the split hides the split but keeps it
+        tm.set_active_tab("main", Some("t3".to_string()));
+        assert!(!tm.is_visible("t1"));
+        assert!(tm.split_of("t1").is_some());
+    }
+
+    #[test]
+    fn prune_drops_closed_and_moved_members() {
+        let tm = manager();
+        let (split, _) = tm.add_split(&labels(&["t1", "t2", "t3"]), Some(&[0.5, 0.25, 0.25])).unwrap();
+
+        tm.remove_tab("t2");
+        let (changed, dissolved) = tm.prune_splits();
+        assert_eq!(changed[0].labels, vec!["t1", "t3"]);
+        assert_eq!(changed[0].ratios, even_ratios(2));
+        assert!(dissolved.is_empty());
+
+        tm.update_tab("t3", |t| t.window_id = "w2".to_string());
+        let (_, dissolved) = tm.prune_splits();
+        assert_eq!(dissolved, vec![split.id]);
+        assert!(tm.get_splits().is_empty());
+    }
+}
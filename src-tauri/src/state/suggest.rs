@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::omnibox::{resolve_input, search_url, InputKind, ResolvedInput, DEFAULT_SEARCH_ENGINE};
+use super::tab_search::TabSearchResult;
+use crate::storage::bookmarks::Bookmark;
+use crate::storage::history::HistoryMatch;
+use crate::storage::search_engines::SearchEngine;
+
+/// Points each visit is worth by how long ago the page was last visited: (under
+/// this many days, points). Older visits get `OLD_VISIT_POINTS`.
+const RECENCY_BUCKETS: &[(f64, f64)] = &[(4.0, 100.0), (14.0, 70.0), (31.0, 50.0), (90.0, 30.0)];
+const OLD_VISIT_POINTS: f64 = 10.0;
+
+/// Bookmarked and open pages are worth this much on top of their visits
+const BOOKMARK_POINTS: f64 = 140.0;
+const OPEN_TAB_POINTS: f64 = 100.0;
+
+/// Match boosts: the query starts the URL (after scheme and www.), starts the title
+/// or a word of it, or starts a part of the URL (`/docs`, `.github`)
+const URL_PREFIX_BOOST: f64 = 4.0;
+const TITLE_WORD_BOOST: f64 = 2.0;
+const URL_PART_BOOST: f64 = 1.5;
+
+/// What a suggestion does when picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    /// Go to the typed URL or host, or the inline-completed one
+    Url,
+    /// Search for the input with a search engine
+    Search,
+    History,
+    Bookmark,
+    /// Switch to an open tab showing the page
+    Tab,
+}
+
+/// One row of the address bar dropdown
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub title: String,
+    pub url: String,
+    pub favicon: Option<String>,
+    /// For `Tab`: the tab to switch to
+    pub tab_label: Option<String>,
+    /// For `Search`: the engine searched with
+    pub engine_id: Option<String>,
+}
+
+/// Inline completion for the input: `git` → `github.com/`, with the rest selected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Autocomplete {
+    /// The completed input — starts with what was typed, ignoring case
+    pub text: String,
+    pub url: String,
+}
+
+/// The ranked dropdown for some address bar input
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OmniboxSuggestions {
+    pub autocomplete: Option<Autocomplete>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Everything `suggest` ranks, already narrowed down to the input
+pub struct SuggestSources<'a> {
+    pub history: &'a [HistoryMatch],
+    pub bookmarks: &'a [Bookmark],
+    /// Open tabs to offer switching to — not the one being typed in
+    pub tabs: &'a [TabSearchResult],
+    pub engines: &'a [SearchEngine],
+}
+
+/// Frecency: how often a page is visited, weighted by how recently. Typed visits
+/// count double — they're the pages people go back to on purpose.
+pub fn frecency(visit_count: i64, typed_count: i64, days_ago: f64) -> f64 {
+    let points = RECENCY_BUCKETS
+        .iter()
+        .find(|(days, _)| days_ago < *days)
+        .map_or(OLD_VISIT_POINTS, |(_, points)| *points);
+    (visit_count.max(0) + typed_count.max(0)) as f64 * points
+}
+
+/// A URL without its scheme
+fn strip_scheme(url: &str) -> &str {
+    url.split_once("://").map_or(url, |(_, rest)| rest)
+}
+
+/// A URL as people type it: without the scheme or a leading `www.`
+fn strip_url(url: &str) -> &str {
+    let rest = strip_scheme(url);
+    rest.strip_prefix("www.").unwrap_or(rest)
+}
+
+/// The same page however it's written: `https://www.a.com/` and `http://a.com` match
+fn dedupe_key(url: &str) -> String {
+    let url = strip_url(url);
+    let url = url.split('#').next().unwrap_or(url);
+    url.trim_end_matches('/').to_lowercase()
+}
+
+/// How well the query's words match a page: 0 when a word is in neither its URL nor
+/// its title, more the closer the match is to how people type the page's address
+fn match_boost(query: &str, words: &[&str], url: &str, title: &str) -> f64 {
+    let url = strip_scheme(url).to_lowercase();
+    let title = title.to_lowercase();
+    if !words.iter().all(|w| url.contains(w) || title.contains(w)) {
+        return 0.0;
+    }
+
+    let starts_word = |text: &str, word: &str, separators: &[char]| {
+        text.match_indices(word)
+            .any(|(i, _)| i == 0 || text[..i].ends_with(separators))
+    };
+    if url.starts_with(query) || strip_url(&url).starts_with(query) {
+        URL_PREFIX_BOOST
+    } else if starts_word(&title, query, &[' ', '-', '(', '[']) {
+        TITLE_WORD_BOOST
+    } else if starts_word(&url, words[0], &['.', '/', '-', '?', '=']) {
+        URL_PART_BOOST
+    } else {
+        1.0
+    }
+}
+
+/// A page from history, bookmarks or open tabs, merged by URL
+struct Candidate {
+    suggestion: Suggestion,
+    frecency: f64,
+    bookmarked: bool,
+    boost: f64,
+}
+
+impl Candidate {
+    fn score(&self) -> f64 {
+        let mut points = self.frecency;
+        if self.bookmarked {
+            points += BOOKMARK_POINTS;
+        }
+        if self.suggestion.kind == SuggestionKind::Tab {
+            points += OPEN_TAB_POINTS;
+        }
+        points * self.boost
+    }
+}
+
+/// Merge pages from every source into one list, a page at most once. A page that's
+/// open becomes a switch-to-tab row and a bookmarked one keeps its bookmark title.
+fn candidates(query: &str, words: &[&str], sources: &SuggestSources) -> Vec<Candidate> {
+    let mut list: Vec<Candidate> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut add = |url: &str, update: &mut dyn FnMut(&mut Candidate)| {
+        let i = *index.entry(dedupe_key(url)).or_insert_with(|| {
+            list.push(Candidate {
+                suggestion: Suggestion {
+                    kind: SuggestionKind::History,
+                    title: String::new(),
+                    url: url.to_string(),
+                    favicon: None,
+                    tab_label: None,
+                    engine_id: None,
+                },
+                frecency: 0.0,
+                bookmarked: false,
+                boost: 0.0,
+            });
+            list.len() - 1
+        });
+        update(&mut list[i]);
+    };
+
+    for m in sources.history {
+        let entry = &m.entry;
+        let title = entry.title.clone().unwrap_or_default();
+        add(&entry.url, &mut |c| {
+            c.frecency += frecency(entry.visit_count, entry.typed_count, m.days_ago);
+            c.boost = c.boost.max(match_boost(query, words, &entry.url, &title));
+            if c.suggestion.title.is_empty() {
+                c.suggestion.title = title.clone();
+            }
+        });
+    }
+
+    for bookmark in sources.bookmarks {
+        let Some(url) = bookmark.url.as_deref() else { continue };
+        add(url, &mut |c| {
+            c.bookmarked = true;
+            c.boost = c.boost.max(match_boost(query, words, url, &bookmark.title));
+            c.suggestion.title = bookmark.title.clone();
+            if c.suggestion.kind != SuggestionKind::Tab {
+                c.suggestion.kind = SuggestionKind::Bookmark;
+            }
+        });
+    }
+
+    for tab in sources.tabs {
+        add(&tab.url, &mut |c| {
+            // Tab search matches fuzzily — keep those matches, ranked low
+            c.boost = c.boost.max(match_boost(query, words, &tab.url, &tab.title)).max(0.5);
+            c.suggestion.kind = SuggestionKind::Tab;
+            c.suggestion.url = tab.url.clone();
+            c.suggestion.tab_label = Some(tab.label.clone());
+            c.suggestion.favicon = tab.favicon.clone();
+            if c.suggestion.title.is_empty() {
+                c.suggestion.title = tab.title.clone();
+            }
+        });
+    }
+
+    list.retain(|c| c.boost > 0.0);
+    for c in &mut list {
+        if c.suggestion.title.is_empty() {
+            c.suggestion.title = c.suggestion.url.clone();
+        }
+    }
+    list.sort_by(|a, b| b.score().total_cmp(&a.score()));
+    list
+}
+
+/// Complete the input inline from the best page whose address starts with it. While
+/// the input is part of the host, it completes to the site's root (`git` → `github.com/`).
+fn autocomplete(query: &str, list: &[Candidate]) -> Option<Autocomplete> {
+    if query.contains(char::is_whitespace) || query.starts_with('?') {
+        return None;
+    }
+    list.iter().find_map(|c| {
+        let url = &c.suggestion.url;
+        let typed = [strip_url(url), strip_scheme(url)]
+            .into_iter()
+            .find(|text| text.to_lowercase().starts_with(query))?;
+
+        let host_end = typed.find('/').unwrap_or(typed.len());
+        if query.len() <= host_end {
+            let parsed = url::Url::parse(url).ok()?;
+            let root = format!("{}/", parsed.origin().ascii_serialization());
+            Some(Autocomplete { text: format!("{}/", &typed[..host_end]), url: root })
+        } else {
+            Some(Autocomplete { text: typed.to_string(), url: url.clone() })
+        }
+    })
+}
+
+/// Build the address bar dropdown for `query`. The first row is what Enter does: go
+/// to the inline-completed page, go to the typed URL, or search. A search row is
+/// always offered; the rest are pages ranked by frecency and how well they match.
+pub fn suggest(query: &str, sources: &SuggestSources, limit: usize) -> OmniboxSuggestions {
+    let query = query.trim();
+    let Some(resolved) = resolve_input(query, sources.engines) else {
+        return OmniboxSuggestions::default();
+    };
+    let lower = query.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+
+    let mut list = candidates(&lower, &words, sources);
+    let autocomplete = autocomplete(&lower, &list);
+
+    let go_to = match (&autocomplete, resolved.kind) {
+        (Some(completion), _) => Some(completion.url.clone()),
+        (None, InputKind::Search) => None,
+        (None, _) => Some(resolved.url.clone()),
+    };
+    let search = match resolved.kind {
+        InputKind::Search => resolved.clone(),
+        _ => {
+            let default = sources.engines.iter().find(|e| e.is_default);
+            let template = default.map_or(DEFAULT_SEARCH_ENGINE, |e| e.url.as_str());
+            ResolvedInput {
+                kind: InputKind::Search,
+                url: search_url(template, query),
+                engine_id: default.map(|e| e.id.clone()),
+            }
+        }
+    };
+
+    let mut suggestions = Vec::new();
+    if let Some(url) = go_to {
+        // The page itself moves up to the first row, unless it's an open tab to switch to
+        let key = dedupe_key(&url);
+        let same = list.iter().position(|c| dedupe_key(&c.suggestion.url) == key);
+        let title = same.map(|i| list[i].suggestion.title.clone()).unwrap_or_else(|| url.clone());
+        if let Some(i) = same.filter(|&i| list[i].suggestion.kind != SuggestionKind::Tab) {
+            list.remove(i);
+        }
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::Url,
+            title,
+            url,
+            favicon: None,
+            tab_label: None,
+            engine_id: None,
+        });
+    }
+    suggestions.push(Suggestion {
+        kind: SuggestionKind::Search,
+        title: query.to_string(),
+        url: search.url,
+        favicon: None,
+        tab_label: None,
+        engine_id: search.engine_id,
+    });
+    // `limit` caps the pages — what Enter does and the search row always show
+    let first_rows = suggestions.len();
+    suggestions.extend(list.into_iter().map(|c| c.suggestion));
+    suggestions.truncate(limit.max(first_rows));
+
+    OmniboxSuggestions { autocomplete, suggestions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::HistoryEntry;
+
+    fn visit(url: &str, title: &str, visits: i64, typed: i64, days_ago: f64) -> HistoryMatch {
+        HistoryMatch {
+            entry: HistoryEntry {
+                id: url.to_string(),
+                url: url.to_string(),
+                title: Some(title.to_string()),
+                visit_count: visits,
+                typed_count: typed,
+                last_error: None,
+                last_visited: String::new(),
+                first_visited: String::new(),
+            },
+            days_ago,
+        }
+    }
+
+    fn bookmark(url: &str, title: &str) -> Bookmark {
+        Bookmark {
+            id: url.to_string(),
+            parent_id: None,
+            title: title.to_string(),
+            url: Some(url.to_string()),
+            is_folder: false,
+            position: 0,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn tab(label: &str, url: &str, title: &str) -> TabSearchResult {
+        TabSearchResult {
+            label: label.to_string(),
+            window_id: "main".to_string(),
+            title: title.to_string(),
+            url: url.to_string(),
+            favicon: None,
+            is_active: false,
+            score: 3.0,
+        }
+    }
+
+    fn engines() -> Vec<SearchEngine> {
+        vec![SearchEngine {
+            id: "ex".to_string(),
+            name: "Example".to_string(),
+            keyword: Some("ex".to_string()),
+            url: "https://search.example/?q=%s".to_string(),
+            suggest_url: None,
+            favicon: None,
+            is_default: true,
+            position: 0,
+        }]
+    }
+
+    fn rows(result: &OmniboxSuggestions) -> Vec<(SuggestionKind, &str)> {
+        result.suggestions.iter().map(|s| (s.kind, s.url.as_str())).collect()
+    }
+
+    #[test]
+    fn frecency_weighs_visits_by_recency() {
+        assert_eq!(frecency(1, 0, 0.5), 100.0);
+        assert_eq!(frecency(3, 0, 20.0), 150.0);
+        assert_eq!(frecency(2, 2, 400.0), 40.0);
+        // A few recent typed visits beat many old ones
+        assert!(frecency(2, 2, 1.0) > frecency(20, 0, 200.0));
+    }
+
+    #[test]
+    fn ranks_by_frecency_and_match() {
+        let history = [
+            visit("https://news.example.com/github-story", "Story about GitHub", 30, 0, 1.0),
+            visit("https://github.com/rust-lang/rust", "rust-lang/rust", 5, 0, 2.0),
+            visit("https://gist.github.com/", "Gists", 1, 0, 60.0),
+        ];
+        let engines = engines();
+        let sources = SuggestSources { history: &history, bookmarks: &[], tabs: &[], engines: &engines };
+        let result = suggest("github", &sources, 10);
+
+        use SuggestionKind::*;
+        assert_eq!(
+            rows(&result),
+            vec![
+                (Url, "https://github.com/"),
+                (Search, "https://search.example/?q=github"),
+                (History, "https://news.example.com/github-story"),
+                (History, "https://github.com/rust-lang/rust"),
+                (History, "https://gist.github.com/"),
+            ]
+        );
+        assert_eq!(
+            result.autocomplete,
+            Some(Autocomplete { text: "github.com/".to_string(), url: "https://github.com/".to_string() })
+        );
+    }
+
+    #[test]
+    fn merges_sources_by_url() {
+        let history = [
+            visit("https://docs.rs/serde/", "serde - Docs.rs", 4, 1, 1.0),
+            visit("https://www.rust-lang.org/", "Rust", 2, 0, 3.0),
+        ];
+        let bookmarks = [bookmark("https://docs.rs/serde", "Serde docs"), bookmark("https://serde.rs/", "Serde")];
+        let engines = engines();
+        let sources = SuggestSources { history: &history, bookmarks: &bookmarks, tabs: &[], engines: &engines };
+
+        // The bookmarked page from history keeps the bookmark's title; the completed
+        // page moves up to the first row
+        let result = suggest("serde", &sources, 10);
+        let rows: Vec<(SuggestionKind, &str, &str)> = result
+            .suggestions
+            .iter()
+            .map(|s| (s.kind, s.url.as_str(), s.title.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (SuggestionKind::Url, "https://serde.rs/", "Serde"),
+                (SuggestionKind::Search, "https://search.example/?q=serde", "serde"),
+                (SuggestionKind::Bookmark, "https://docs.rs/serde/", "Serde docs"),
+            ]
+        );
+
+        // The open tab wins over its history entry, and stays a switch-to-tab row
+        let tabs = [tab("tab-2", "http://rust-lang.org", "Rust Programming Language")];
+        let sources = SuggestSources { tabs: &tabs, ..sources };
+        let result = suggest("rust", &sources, 10);
+        let tab_row = result.suggestions.iter().find(|s| s.kind == SuggestionKind::Tab).unwrap();
+        assert_eq!(tab_row.tab_label.as_deref(), Some("tab-2"));
+        assert_eq!(tab_row.title, "Rust");
+        assert_eq!(result.suggestions.iter().filter(|s| s.url.contains("rust-lang.org")).count(), 2);
+    }
+
+    #[test]
+    fn typed_urls_and_searches_come_first() {
+        let history = [visit("https://example.com/page", "Example page", 1, 0, 1.0)];
+        let engines = engines();
+        let sources = SuggestSources { history: &history, bookmarks: &[], tabs: &[], engines: &engines };
+
+        use SuggestionKind::*;
+        let result = suggest("localhost:3000", &sources, 10);
+        assert_eq!(rows(&result)[0], (Url, "http://localhost:3000/"));
+        assert_eq!(rows(&result)[1], (Search, "https://search.example/?q=localhost%3A3000"));
+
+        let result = suggest("example page", &sources, 10);
+        assert_eq!(
+            rows(&result),
+            vec![(Search, "https://search.example/?q=example+page"), (History, "https://example.com/page")]
+        );
+        assert_eq!(result.autocomplete, None);
+
+        // Keyword searches name their engine
+        let result = suggest("ex kittens", &sources, 10);
+        assert_eq!(result.suggestions[0].engine_id.as_deref(), Some("ex"));
+        assert_eq!(result.suggestions[0].url, "https://search.example/?q=kittens");
+    }
+
+    #[test]
+    fn autocompletes_host_then_path() {
+        let history = [visit("https://www.example.com/docs/intro", "Intro", 3, 1, 1.0)];
+        let engines = engines();
+        let sources = SuggestSources { history: &history, bookmarks: &[], tabs: &[], engines: &engines };
+
+        let completion = |input: &str| suggest(input, &sources, 10).autocomplete.map(|a| (a.text, a.url));
+        assert_eq!(completion("exa"), Some(("example.com/".to_string(), "https://www.example.com/".to_string())));
+        assert_eq!(completion("www.ex"), Some(("www.example.com/".to_string(), "https://www.example.com/".to_string())));
+        assert_eq!(
+            completion("example.com/d"),
+            Some(("example.com/docs/intro".to_string(), "https://www.example.com/docs/intro".to_string()))
+        );
+        assert_eq!(completion("intro"), None);
+        assert_eq!(completion("   "), None);
+    }
+
+    #[test]
+    fn respects_limit_but_keeps_first_rows() {
+        let history: Vec<HistoryMatch> =
+            (0..10).map(|i| visit(&format!("https://site{}.com/", i), "Site", 1, 0, 1.0)).collect();
+        let engines = engines();
+        let sources = SuggestSources { history: &history, bookmarks: &[], tabs: &[], engines: &engines };
+        assert_eq!(suggest("site", &sources, 6).suggestions.len(), 6);
+        assert_eq!(suggest("site", &sources, 0).suggestions.len(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+use url::Url;
+
+use super::tab_state::{window_of, TabInfo, TabManager};
+
+/// Which tabs of a window `tab_close_many` closes. Pinned tabs are never selected.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloseSelector {
+    /// Every tab in `label`'s window except `label`
+    Others { label: String },
+    /// Every tab after `label` in its window
+    ToRight { label: String },
+    /// Tabs on `domain` or one of its subdomains ("example.com" matches "docs.example.com")
+    Domain { domain: String },
+    /// Tabs showing the same page as an earlier tab (the active tab is always the one kept)
+    Duplicates,
+}
+
+/// Tabs removed by `TabManager::close_many`
+#[derive(Debug, Clone)]
+pub struct ClosedBatch {
+    pub window_id: String,
+    /// Removed tabs with their former index in the window, highest index first —
+    /// the order to push them to the closed-tab stack so reopening restores positions
+    pub closed: Vec<(usize, TabInfo)>,
+    /// Whether the window's active tab was among them
+    pub active_closed: bool,
+    /// The tab to activate instead: the nearest survivor, preferring the right
+    pub next_active: Option<String>,
+}
+
+/// Whether `url`'s host is `domain` or a subdomain of it (ignoring "www.")
+pub fn host_matches(url: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() {
+        return false;
+    }
+    let Some(host) = Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+        return false;
+    };
+    let host = host.trim_start_matches("www.");
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Pages count as duplicates when their URLs only differ in the fragment
+fn page_key(url: &str) -> &str {
+    url.split_once('#').map(|(page, _)| page).unwrap_or(url)
+}
+
+impl TabManager {
+    /// Remove the tabs a selector picks in one go. `window_id` is the window searched
+    /// by selectors that don't name a tab. Returns what was removed; webviews, closed-tab
+    /// history and events are left to the caller.
+    pub fn close_many(&self, window_id: &str, selector: &CloseSelector) -> Result<ClosedBatch, String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut active_tabs = self.active_tabs.lock().unwrap();
+
+        let window_id = match selector {
+            CloseSelector::Others { label } | CloseSelector::ToRight { label } => window_of(&tabs, label)?,
+            _ => window_id.to_string(),
+        };
+        let active = active_tabs.get(&window_id).cloned();
+        let window: Vec<&TabInfo> = tabs.iter().filter(|t| t.window_id == window_id).collect();
+
+        let selected: Vec<bool> = match selector {
+            CloseSelector::Others { label } => window.iter().map(|t| &t.label != label).collect(),
+            CloseSelector::ToRight { label } => {
+                let pos = window.iter().position(|t| &t.label == label).unwrap_or(window.len());
+                (0..window.len()).map(|i| i > pos).collect()
+            }
+            CloseSelector::Domain { domain } => {
+                window.iter().map(|t| host_matches(&t.url, domain)).collect()
+            }
+            CloseSelector::Duplicates => {
+                // Claim the active tab's page first so it's the copy that stays
+                let mut seen: HashSet<&str> = window
+                    .iter()
+                    .filter(|t| Some(&t.label) == active.as_ref())
+                    .map(|t| page_key(&t.url))
+                    .collect();
+                window
+                    .iter()
+                    .map(|t| Some(&t.label) != active.as_ref() && !seen.insert(page_key(&t.url)))
+                    .collect()
+            }
+        };
+        let selected: Vec<bool> = selected
+            .into_iter()
+            .zip(&window)
+            .map(|(selected, t)| selected && !t.is_pinned)
+            .collect();
+
+        // Pick the survivor to focus before the strip changes
+        let active_pos = window.iter().position(|t| Some(&t.label) == active.as_ref());
+        let active_closed = active_pos.is_some_and(|pos| selected[pos]);
+        let next_active = active_pos.filter(|_| active_closed).and_then(|pos| {
+            let right = (pos + 1..window.len()).find(|&i| !selected[i]);
+            let left = (0..pos).rev().find(|&i| !selected[i]);
+            right.or(left).map(|i| window[i].label.clone())
+        });
+
+        let doomed: HashSet<String> = window
+            .iter()
+            .zip(&selected)
+            .filter(|(_, &selected)| selected)
+            .map(|(t, _)| t.label.clone())
+            .collect();
+        let mut closed = Vec::new();
+        let mut index = 0;
+        let mut i = 0;
+        while i < tabs.len() {
+            if tabs[i].window_id != window_id {
+                i += 1;
+                continue;
+            }
+            if doomed.contains(&tabs[i].label) {
+                closed.push((index, tabs.remove(i)));
+            } else {
+                i += 1;
+            }
+            index += 1;
+        }
+        closed.reverse();
+
+        if active_closed {
+            active_tabs.remove(&window_id);
+        }
+
+        Ok(ClosedBatch {
+            window_id,
+            closed,
+            active_closed,
+            next_active,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tab(label: &str, url: &str) -> TabInfo {
+        TabInfo {
+            title: String::new(),
+            ..TabInfo::for_test(label, url, "main")
+        }
+    }
+
+    fn manager(urls: &[(&str, &str)]) -> TabManager {
+        let tm = TabManager::new();
+        for (label, url) in urls {
+            tm.add_tab(make_tab(label, url));
+        }
+        tm
+    }
+
+    fn closed_labels(batch: &ClosedBatch) -> Vec<&str> {
+        batch.closed.iter().map(|(_, t)| t.label.as_str()).collect()
+    }
+
+    fn others(label: &str) -> CloseSelector {
+        CloseSelector::Others { label: label.to_string() }
+    }
+
+    #[test]
+    fn close_others_keeps_one_tab() {
+        let tm = manager(&[("t1", "https://a.com"), ("t2", "https://b.com"), ("t3", "https://c.com")]);
+        tm.set_active_tab("main", Some("t1".to_string()));
+
+        let batch = tm.close_many("main", &others("t2")).unwrap();
+        assert_eq!(closed_labels(&batch), vec!["t3", "t1"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["t2"]);
+        assert!(batch.active_closed);
+        assert_eq!(batch.next_active.as_deref(), Some("t2"));
+        assert_eq!(tm.get_active_tab("main"), None);
+    }
+
+    #[test]
+    fn closed_tabs_come_highest_index_first() {
+        let tm = manager(&[("t1", "https://a.com"), ("t2", "https://b.com"), ("t3", "https://c.com")]);
+        let batch = tm.close_many("main", &others("t2")).unwrap();
+        let indices: Vec<usize> = batch.closed.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, vec![2, 0]);
+    }
+
+    #[test]
+    fn close_to_right_keeps_active_when_it_survives() {
+        let tm = manager(&[("t1", "https://a.com"), ("t2", "https://b.com"), ("t3", "https://c.com")]);
+        tm.set_active_tab("main", Some("t1".to_string()));
+
+        let batch = tm
+            .close_many("main", &CloseSelector::ToRight { label: "t1".to_string() })
+            .unwrap();
+        assert_eq!(closed_labels(&batch), vec!["t3", "t2"]);
+        assert!(!batch.active_closed);
+        assert_eq!(batch.next_active, None);
+        assert_eq!(tm.get_active_tab("main").as_deref(), Some("t1"));
+    }
+
+    #[test]
+    fn next_active_prefers_the_right() {
+        let tm = manager(&[
+            ("t1", "https://a.com"),
+            ("t2", "https://x.com"),
+            ("t3", "https://x.com/b"),
+            ("t4", "https://d.com"),
+        ]);
+        tm.set_active_tab("main", Some("t2".to_string()));
+        let batch = tm
+            .close_many("main", &CloseSelector::Domain { domain: "x.com".to_string() })
+            .unwrap();
+        assert_eq!(batch.next_active.as_deref(), Some("t4"));
+
+        // Nothing to the right — fall back to the left
+        let tm = manager(&[("t1", "https://a.com"), ("t2", "https://x.com")]);
+        tm.set_active_tab("main", Some("t2".to_string()));
+        let batch = tm
+            .close_many("main", &CloseSelector::Domain { domain: "x.com".to_string() })
+            .unwrap();
+        assert_eq!(batch.next_active.as_deref(), Some("t1"));
+    }
+
+    #[test]
+    fn domain_matches_subdomains_only() {
+        assert!(host_matches("https://example.com/a", "example.com"));
+        assert!(host_matches("https://www.example.com", "example.com"));
+        assert!(host_matches("https://docs.example.com", "www.example.com"));
+        assert!(!host_matches("https://notexample.com", "example.com"));
+        assert!(!host_matches("aero://settings", "example.com"));
+        assert!(!host_matches("https://example.com", ""));
+    }
+
+    #[test]
+    fn duplicates_keep_the_first_or_active_copy() {
+        let tm = manager(&[
+            ("t1", "https://a.com/"),
+            ("t2", "https://b.com/"),
+            ("t3", "https://a.com/#section"),
+            ("t4", "https://b.com/"),
+        ]);
+        tm.set_active_tab("main", Some("t4".to_string()));
+
+        let batch = tm.close_many("main", &CloseSelector::Duplicates).unwrap();
+        assert_eq!(closed_labels(&batch), vec!["t3", "t2"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t4"]);
+        assert!(!batch.active_closed);
+    }
+
+    #[test]
+    fn pinned_tabs_and_other_windows_are_untouched() {
+        let tm = manager(&[("t1", "https://a.com"), ("t2", "https://b.com")]);
+        tm.set_pinned("t1", true).unwrap();
+        tm.add_tab(TabInfo {
+            window_id: "w2".to_string(),
+            ..make_tab("t3", "https://c.com")
+        });
+
+        let batch = tm.close_many("main", &others("t2")).unwrap();
+        assert!(batch.closed.is_empty());
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2"]);
+        assert_eq!(tm.get_tab_labels("w2"), vec!["t3"]);
+    }
+
+    #[test]
+    fn selector_window_comes_from_the_named_tab() {
+        let tm = manager(&[("t1", "https://a.com")]);
+        tm.add_tab(TabInfo {
+            window_id: "w2".to_string(),
+            ..make_tab("t2", "https://b.com")
+        });
+        tm.add_tab(TabInfo {
+            window_id: "w2".to_string(),
+            ..make_tab("t3", "https://c.com")
+        });
+
+        let batch = tm.close_many("main", &others("t2")).unwrap();
+        assert_eq!(batch.window_id, "w2");
+        assert_eq!(closed_labels(&batch), vec!["t3"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1"]);
+
+        assert!(tm.close_many("main", &others("missing")).is_err());
+    }
+
+    #[test]
+    fn selector_deserializes_from_kind_tag() {
+        let selector: CloseSelector =
+            serde_json::from_str(r#"{ "kind": "to_right", "label": "t1" }"#).unwrap();
+        assert_eq!(selector, CloseSelector::ToRight { label: "t1".to_string() });
+        let selector: CloseSelector = serde_json::from_str(r#"{ "kind": "duplicates" }"#).unwrap();
+        assert_eq!(selector, CloseSelector::Duplicates);
+    }
+}
@@ -0,0 +1,1283 @@
+use crate::events::{AppEvent, TabUpdate};
+
+use super::layout::Rect;
+use super::load_error::LoadError;
+use super::split_view::{split_rects, SPLIT_GAP};
+use super::tab_close::{CloseSelector, ClosedBatch};
+use super::tab_state::{next_tab_label, SpaNavigation, TabInfo, TabManager};
+use super::watchdog::PingState;
+
+/// Everything tab behaviour needs from the windowing side: the content webviews
+/// and a line to the browser UI. Implemented over Tauri by `commands::webviews::TauriWebviews`;
+/// tests use a fake that records calls.
+pub trait WebviewHost {
+    /// Build a content webview for a tab in a window, loading `url` (aero:// included).
+    /// A tab in a container gets that container's browsing data.
+    fn create(&self, window_id: &str, label: &str, url: &str, container_id: Option<&str>) -> Result<(), String>;
+    /// Load `url` in a tab's webview
+    fn navigate(&self, label: &str, url: &str) -> Result<(), String>;
+    /// Run a script in a tab's page
+    fn eval(&self, label: &str, script: &str) -> Result<(), String>;
+    /// Place a tab's webview at `rect` and show it
+    fn show(&self, label: &str, rect: Rect) -> Result<(), String>;
+    fn hide(&self, label: &str) -> Result<(), String>;
+    fn close(&self, label: &str) -> Result<(), String>;
+    /// Whether a tab has a live webview (discarded tabs don't)
+    fn has_webview(&self, label: &str) -> bool;
+    /// The area of a window that tab webviews fill
+    fn content_rect(&self, window_id: &str) -> Result<Rect, String>;
+    /// Send an event to the browser UI
+    fn emit(&self, event: AppEvent);
+}
+
+/// A tab removed by `TabController::close`, for the caller to remember
+#[derive(Debug, Clone)]
+pub struct ClosedTab {
+    pub tab: TabInfo,
+    /// Its position in the window's tab strip
+    pub index: usize,
+}
+
+/// Tab and navigation flows — create, close, activate, lay out, navigate, reorder —
+/// over the tab state and a webview host. Persistence (session, history, closed tabs)
+/// stays with the commands calling it.
+pub struct TabController<'a, H: WebviewHost> {
+    pub tabs: &'a TabManager,
+    pub host: H,
+}
+
+impl<'a, H: WebviewHost> TabController<'a, H> {
+    pub fn new(tabs: &'a TabManager, host: H) -> Self {
+        Self { tabs, host }
+    }
+
+    fn tab(&self, label: &str) -> Result<TabInfo, String> {
+        self.tabs
+            .get_tab(label)
+            .ok_or_else(|| format!("Tab {} not found", label))
+    }
+
+    fn require_webview(&self, label: &str) -> Result<(), String> {
+        if self.host.has_webview(label) {
+            Ok(())
+        } else {
+            Err("Tab webview not found".to_string())
+        }
+    }
+
+    // ── Tabs ───────────────────────────────────────────────
+
+    /// Open a tab in a window. Pinned tabs join the end of the pinned block, tabs with
+    /// an `opener` (in the same window) go after it, others at the end of the strip.
+    /// `background` tabs aren't activated unless the window has no active tab.
+    /// Without a `container_id` the tab joins its opener's container, if any.
+    pub fn create_tab(
+        &self,
+        window_id: &str,
+        url: &str,
+        pinned: bool,
+        opener: Option<String>,
+        background: bool,
+        container_id: Option<String>,
+    ) -> Result<TabInfo, String> {
+        let container_id = container_id.or_else(|| {
+            opener
+                .as_deref()
+                .and_then(|o| self.tabs.get_tab(o))
+                .and_then(|t| t.container_id)
+        });
+        let label = next_tab_label();
+        self.host.create(window_id, &label, url, container_id.as_deref())?;
+
+        // Openers only matter within a window
+        let opener = opener.filter(|o| self.tabs.get_tab(o).is_some_and(|t| t.window_id == window_id));
+
+        self.tabs.add_tab(TabInfo {
+            label: label.clone(),
+            window_id: window_id.to_string(),
+            url: url.to_string(),
+            title: "New Tab".to_string(),
+            is_loading: true,
+            favicon: None,
+            can_go_back: false,
+            can_go_forward: false,
+            is_pinned: pinned,
+            group_id: None,
+            is_discarded: false,
+            is_crashed: false,
+            is_unresponsive: false,
+            load_error: None,
+            is_audible: false,
+            is_muted: false,
+            zoom: 100,
+            opener,
+            container_id,
+            last_active: None,
+            ping: PingState::Unarmed,
+            nav_stack: Vec::new(),
+            nav_pos: -1,
+            nav_traversing: false,
+            nav_restored: false,
+            nav_typed: false,
+        });
+        // Placement may have put the tab into its opener's group
+        let tab = self.tab(&label)?;
+
+        let activate = !background || self.tabs.get_active_tab(window_id).is_none();
+        if activate {
+            self.tabs.set_active_tab(window_id, Some(label.clone()));
+        }
+        let _ = self.layout(window_id);
+
+        self.host.emit(AppEvent::TabCreated(tab.clone()));
+        if activate {
+            self.host.emit(AppEvent::TabActivated(tab.clone()));
+        }
+
+        // The UI appends new tabs — tell it when the tab landed elsewhere (pinned block, after its opener)
+        let last = self.tabs.tab_count(window_id) - 1;
+        if let Some(index) = self.tabs.tab_index(&label).filter(|&i| i != last) {
+            self.host.emit(AppEvent::TabReordered {
+                label: label.clone(),
+                old_index: last,
+                new_index: index,
+            });
+        }
+
+        Ok(tab)
+    }
+
+    /// Close a tab and its webview. Focus returns to the tab's opener if it has one,
+    /// else moves to a neighbour; a new tab at `new_tab_url` opens if it was the last one.
+    /// Pinned tabs are only closed when `force` is set.
+    pub fn close(&self, label: &str, force: bool, new_tab_url: &str) -> Result<ClosedTab, String> {
+        let tab = self.tab(label)?;
+        if tab.is_pinned && !force {
+            return Err(format!("Tab {} is pinned", label));
+        }
+
+        let window_id = tab.window_id.clone();
+        let next = tab
+            .opener
+            .clone()
+            .filter(|o| self.tabs.get_tab(o).is_some_and(|t| t.window_id == window_id))
+            .or_else(|| self.tabs.get_adjacent_tab(label));
+        let was_active = self.tabs.get_active_tab(&window_id).as_deref() == Some(label);
+        let index = self.tabs.tab_index(label).ok_or("Tab not found")?;
+
+        let tab = self.tabs.remove_tab(label).ok_or("Tab not found")?;
+        if self.host.has_webview(label) {
+            self.host.close(label)?;
+        }
+
+        self.host.emit(AppEvent::TabClosed { label: label.to_string() });
+        self.prune_groups();
+        self.prune_splits();
+
+        if was_active {
+            match next {
+                // Wakes the next tab if it was discarded
+                Some(next) => self.activate(&next)?,
+                None => {
+                    self.tabs.set_active_tab(&window_id, None);
+                    self.create_tab(&window_id, new_tab_url, false, None, false, None)?;
+                }
+            }
+        } else {
+            // The closed tab may have been a pane of the visible split
+            let _ = self.layout(&window_id);
+        }
+
+        Ok(ClosedTab { tab, index })
+    }
+
+    /// Close the tabs a selector picks, with their webviews, in one go. The UI gets a
+    /// single `tabs_closed` event. If the active tab went, the nearest survivor takes
+    /// over, or a new tab at `new_tab_url` opens when none is left.
+    pub fn close_many(&self, window_id: &str, selector: &CloseSelector, new_tab_url: &str) -> Result<ClosedBatch, String> {
+        let batch = self.tabs.close_many(window_id, selector)?;
+        if batch.closed.is_empty() {
+            return Ok(batch);
+        }
+
+        for (_, tab) in &batch.closed {
+            if self.host.has_webview(&tab.label) {
+                self.host.close(&tab.label)?;
+            }
+        }
+        // Strip order reads better in the event than closed-stack order
+        let labels = batch.closed.iter().rev().map(|(_, t)| t.label.clone()).collect();
+        self.host.emit(AppEvent::TabsClosed {
+            window_id: batch.window_id.clone(),
+            labels,
+        });
+        self.prune_groups();
+        self.prune_splits();
+
+        if batch.active_closed {
+            match &batch.next_active {
+                Some(next) => self.activate(next)?,
+                None => {
+                    self.create_tab(&batch.window_id, new_tab_url, false, None, false, None)?;
+                }
+            }
+        } else {
+            let _ = self.layout(&batch.window_id);
+        }
+
+        Ok(batch)
+    }
+
+    /// Show a tab in the window that owns it. Expands its collapsed group and wakes it —
+    /// and the other panes of its split view — if discarded.
+    pub fn activate(&self, label: &str) -> Result<(), String> {
+        let tab = self.tab(label)?;
+
+        if let Some(group_id) = &tab.group_id {
+            if self.tabs.get_group(group_id).is_some_and(|g| g.collapsed) {
+                if let Some(group) = self.tabs.update_group(group_id, |g| g.collapsed = false) {
+                    self.host.emit(AppEvent::TabGroupUpdated(group));
+                }
+            }
+        }
+
+        let panes = match self.tabs.split_of(label) {
+            Some(split) => split.labels,
+            None => vec![label.to_string()],
+        };
+        for pane in &panes {
+            self.wake(pane)?;
+        }
+
+        self.tabs.set_active_tab(&tab.window_id, Some(label.to_string()));
+        self.layout(&tab.window_id)?;
+
+        if let Some(tab) = self.tabs.get_tab(label) {
+            self.host.emit(AppEvent::TabActivated(tab));
+        }
+        Ok(())
+    }
+
+    /// Close a background tab's webview while keeping its TabInfo, URL, history and
+    /// mute state. Tabs on screen (active, or a pane of the active split) or playing
+    /// audio are never discarded.
+    pub fn discard(&self, label: &str) -> Result<(), String> {
+        if self.tabs.is_visible(label) {
+            return Err("A visible tab can't be discarded".to_string());
+        }
+        let tab = self.tab(label)?;
+        if tab.is_discarded {
+            return Ok(());
+        }
+        if tab.is_audible {
+            return Err("A tab playing audio can't be discarded".to_string());
+        }
+
+        if self.host.has_webview(label) {
+            self.host.close(label)?;
+        }
+
+        // The recreated webview starts with an empty history, so back/forward
+        // have to load stack entries directly from now on
+        self.tabs.update_tab(label, |tab| {
+            tab.is_discarded = true;
+            tab.is_loading = false;
+            tab.restore_nav(tab.nav_stack.clone(), tab.nav_pos);
+        });
+
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            is_discarded: Some(true),
+            loading: Some(false),
+            ..TabUpdate::new(label)
+        }));
+        Ok(())
+    }
+
+    /// Recreate a discarded tab's webview at its last URL
+    pub fn wake(&self, label: &str) -> Result<(), String> {
+        let tab = self.tab(label)?;
+        if !tab.is_discarded {
+            return Ok(());
+        }
+
+        self.host.create(&tab.window_id, label, &tab.url, tab.container_id.as_deref())?;
+        self.tabs.update_tab(label, |tab| {
+            tab.is_discarded = false;
+            tab.is_loading = true;
+            // The new document checks in with the watchdog afresh
+            tab.ping = PingState::Unarmed;
+        });
+
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            is_discarded: Some(false),
+            loading: Some(true),
+            ..TabUpdate::new(label)
+        }));
+        Ok(())
+    }
+
+    /// Replace a crashed tab's webview with a fresh one at its last URL. The tab keeps its
+    /// place in the strip and its back/forward history, loaded entry by entry from now on.
+    pub fn recover(&self, label: &str) -> Result<(), String> {
+        let tab = self.tab(label)?;
+        if !tab.is_crashed {
+            return Err(format!("Tab {} has not crashed", label));
+        }
+
+        if self.host.has_webview(label) {
+            self.host.close(label)?;
+        }
+        self.host.create(&tab.window_id, label, &tab.url, tab.container_id.as_deref())?;
+        self.tabs.update_tab(label, |tab| {
+            tab.is_crashed = false;
+            tab.is_unresponsive = false;
+            tab.is_loading = true;
+            tab.ping = PingState::Unarmed;
+            tab.restore_nav(tab.nav_stack.clone(), tab.nav_pos);
+        });
+        // The new webview covers the content area — hide it again if the tab is in the background
+        let _ = self.layout(&tab.window_id);
+
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            is_crashed: Some(false),
+            is_unresponsive: Some(false),
+            loading: Some(true),
+            ..TabUpdate::new(label)
+        }));
+        Ok(())
+    }
+
+    /// Show, hide and position a window's content webviews. The active tab fills the
+    /// content area — or, when it is part of a split view, every pane of the split is
+    /// shown side by side.
+    pub fn layout(&self, window_id: &str) -> Result<(), String> {
+        let area = self.host.content_rect(window_id)?;
+
+        let visible: Vec<(String, Rect)> = match self.tabs.get_active_tab(window_id) {
+            Some(label) => match self.tabs.split_of(&label) {
+                Some(split) => split
+                    .labels
+                    .into_iter()
+                    .zip(split_rects(area, &split.ratios, SPLIT_GAP))
+                    .collect(),
+                None => vec![(label, area)],
+            },
+            None => Vec::new(),
+        };
+
+        for label in self.tabs.get_tab_labels(window_id) {
+            if !self.host.has_webview(&label) {
+                continue;
+            }
+            match visible.iter().find(|(l, _)| *l == label) {
+                Some((_, rect)) => self.host.show(&label, *rect)?,
+                None => self.host.hide(&label)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Reorder a tab to a new position in its window. With `whole_group`, a grouped tab
+    /// drags its entire group along. Dropping a tab into or out of a group changes membership.
+    pub fn reorder(&self, label: &str, new_index: usize, whole_group: bool) -> Result<(), String> {
+        let group_before = self.tab(label)?.group_id;
+
+        if whole_group {
+            if let Some(group_id) = &group_before {
+                return self.move_group(group_id, new_index);
+            }
+        }
+
+        let (old_index, new_index) = self.tabs.move_tab(label, new_index)?;
+        self.host.emit(AppEvent::TabReordered {
+            label: label.to_string(),
+            old_index,
+            new_index,
+        });
+
+        let group_after = self.tabs.get_tab(label).and_then(|t| t.group_id);
+        if group_after != group_before {
+            self.host.emit(AppEvent::TabUpdated(TabUpdate {
+                group_id: Some(group_after),
+                ..TabUpdate::new(label)
+            }));
+            self.prune_groups();
+        }
+        Ok(())
+    }
+
+    /// Move a whole group so its first tab lands at `new_index`
+    pub fn move_group(&self, group_id: &str, new_index: usize) -> Result<(), String> {
+        let (old_index, new_index) = self.tabs.move_group(group_id, new_index)?;
+        self.host.emit(AppEvent::TabGroupMoved {
+            group_id: group_id.to_string(),
+            labels: self.tabs.group_members(group_id),
+            old_index,
+            new_index,
+        });
+        Ok(())
+    }
+
+    /// Drop groups left without members and tell the UI
+    pub fn prune_groups(&self) {
+        for group_id in self.tabs.prune_groups() {
+            self.host.emit(AppEvent::TabGroupRemoved { group_id });
+        }
+    }
+
+    /// Drop split members that were closed or moved away and tell the UI
+    pub fn prune_splits(&self) {
+        let (changed, dissolved) = self.tabs.prune_splits();
+        for split in changed {
+            self.host.emit(AppEvent::TabSplitUpdated(split));
+        }
+        for split_id in dissolved {
+            self.host.emit(AppEvent::TabSplitRemoved { split_id });
+        }
+    }
+
+    // ── Navigation ─────────────────────────────────────────
+
+    /// Load `url` in a tab as a new history entry, dropping any forward history
+    pub fn navigate(&self, label: &str, url: &str) -> Result<(), String> {
+        self.require_webview(label)?;
+        self.host.navigate(label, url)?;
+
+        self.tabs.update_tab(label, |tab| {
+            tab.url = url.to_string();
+            tab.is_loading = true;
+            tab.push_nav(url);
+        });
+        self.emit_nav_state(label);
+        Ok(())
+    }
+
+    /// Move `delta` entries through a tab's history. Returns whether it moved.
+    /// Restored tabs have no webview history behind their stack, so the target
+    /// entry is loaded directly; otherwise the webview's own history is used.
+    pub fn go(&self, label: &str, delta: i32) -> Result<bool, String> {
+        self.require_webview(label)?;
+        let tab = self.tab(label)?;
+        let Some(target) = tab.nav_target(delta) else {
+            return Ok(false);
+        };
+
+        self.tabs.update_tab(label, |tab| {
+            tab.nav_traversing = true;
+            tab.nav_pos = target;
+        });
+
+        if tab.nav_restored {
+            self.host.navigate(label, &tab.nav_stack[target as usize].url)?;
+        } else {
+            self.host.eval(label, &format!("window.history.go({})", delta))?;
+        }
+
+        self.emit_nav_state(label);
+        Ok(true)
+    }
+
+    /// Jump straight to entry `index` of a tab's history. Returns whether it moved.
+    pub fn go_to_index(&self, label: &str, index: usize) -> Result<bool, String> {
+        let tab = self.tab(label)?;
+        if index >= tab.nav_stack.len() {
+            return Err(format!("No history entry at index {}", index));
+        }
+        self.go(label, index as i32 - tab.nav_pos)
+    }
+
+    /// A tab's page started or finished loading `url`. Finished loads go into the
+    /// tab's history (unless they end a back/forward traversal). Entries loaded directly
+    /// from a restored stack get their scroll position back — the webview's own history
+    /// does that for the rest. The error page stands in for the URL that failed; any
+    /// other page starting to load clears the tab's load error.
+    pub fn page_load(&self, label: &str, url: &str, loading: bool) {
+        let error_page = LoadError::from_page_url(url);
+        let url = error_page.as_ref().map_or(url, |e| e.url.as_str());
+
+        let mut restore_scroll = None;
+        let mut cleared_error = false;
+        let mut cleared_unresponsive = false;
+        self.tabs.update_tab(label, |tab| {
+            tab.is_loading = loading;
+            tab.url = url.to_string();
+            if error_page.is_some() {
+                tab.load_error = error_page.clone();
+            } else if loading {
+                cleared_error = tab.load_error.take().is_some();
+            }
+            if loading {
+                // The new document checks in with the watchdog afresh
+                tab.ping = PingState::Unarmed;
+                cleared_unresponsive = std::mem::take(&mut tab.is_unresponsive);
+            } else {
+                if tab.nav_traversing && tab.nav_restored {
+                    restore_scroll = tab.nav_entry().map(|e| e.scroll_y).filter(|&y| y > 0.0);
+                }
+                tab.record_load(url);
+            }
+        });
+
+        if let Some(y) = restore_scroll {
+            let _ = self.host.eval(label, &format!("window.scrollTo(0, {})", y));
+        }
+
+        let (can_go_back, can_go_forward) = self
+            .tabs
+            .get_tab(label)
+            .map(|t| (t.can_go_back, t.can_go_forward))
+            .unwrap_or((false, false));
+
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            loading: Some(loading),
+            url: Some(url.to_string()),
+            can_go_back: Some(can_go_back),
+            can_go_forward: Some(can_go_forward),
+            load_error: cleared_error.then_some(None),
+            is_unresponsive: cleared_unresponsive.then_some(false),
+            ..TabUpdate::new(label)
+        }));
+    }
+
+    /// A tab's page failed to load. The tab keeps the failed URL, shows the error page
+    /// in its place and remembers the error until another page loads. The webview's own
+    /// history now holds a page that only fails again, so from here on back/forward
+    /// load nav stack entries directly, as for a restored tab.
+    pub fn load_failed(&self, label: &str, error: LoadError) -> Result<(), String> {
+        self.require_webview(label)?;
+        self.host.navigate(label, &error.page_url())?;
+
+        self.tabs.update_tab(label, |tab| {
+            tab.url = error.url.clone();
+            tab.load_error = Some(error.clone());
+            tab.nav_restored = true;
+        });
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            url: Some(error.url.clone()),
+            load_error: Some(Some(error)),
+            ..TabUpdate::new(label)
+        }));
+        Ok(())
+    }
+
+    /// Load a tab's failed URL again in place of the error page. Returns whether
+    /// the tab had a failed load to retry.
+    pub fn retry(&self, label: &str) -> Result<bool, String> {
+        let Some(error) = self.tab(label)?.load_error else {
+            return Ok(false);
+        };
+        self.require_webview(label)?;
+        self.host.navigate(label, &error.url)?;
+        self.tabs.update_tab(label, |tab| tab.is_loading = true);
+        Ok(true)
+    }
+
+    /// A single-page app changed its URL without loading a document (pushState,
+    /// replaceState, popstate, hashchange). Returns whether the tab's URL changed.
+    pub fn spa_navigate(&self, label: &str, url: &str, kind: SpaNavigation) -> Result<bool, String> {
+        let before = self.tab(label)?.url;
+        self.tabs.update_tab(label, |tab| tab.record_spa_navigation(url, kind));
+
+        let tab = self.tab(label)?;
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            url: Some(tab.url.clone()),
+            can_go_back: Some(tab.can_go_back),
+            can_go_forward: Some(tab.can_go_forward),
+            ..TabUpdate::new(label)
+        }));
+        Ok(tab.url != before)
+    }
+
+    /// Refresh can_go_back/can_go_forward from the nav stack and tell the UI
+    fn emit_nav_state(&self, label: &str) {
+        self.tabs.update_tab(label, |tab| tab.sync_nav_flags());
+        let Some(tab) = self.tabs.get_tab(label) else {
+            return;
+        };
+        self.host.emit(AppEvent::TabUpdated(TabUpdate {
+            can_go_back: Some(tab.can_go_back),
+            can_go_forward: Some(tab.can_go_forward),
+            ..TabUpdate::new(label)
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::load_error::LoadErrorKind;
+    use crate::state::tab_groups::TabGroup;
+    use crate::state::tab_state::NavEntry;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    const AREA: Rect = Rect { x: 0.0, y: 76.0, width: 1000.0, height: 700.0 };
+
+    /// A webview as the fake host sees it
+    #[derive(Debug, Clone, PartialEq)]
+    struct FakeWebview {
+        url: String,
+        shown: Option<Rect>,
+        container_id: Option<String>,
+    }
+
+    /// Webview host that keeps webviews in a map and records scripts and events
+    #[derive(Default)]
+    struct FakeHost {
+        webviews: RefCell<HashMap<String, FakeWebview>>,
+        scripts: RefCell<Vec<(String, String)>>,
+        events: RefCell<Vec<AppEvent>>,
+    }
+
+    impl WebviewHost for &FakeHost {
+        fn create(&self, _window_id: &str, label: &str, url: &str, container_id: Option<&str>) -> Result<(), String> {
+            self.webviews.borrow_mut().insert(
+                label.to_string(),
+                FakeWebview {
+                    url: url.to_string(),
+                    shown: Some(AREA),
+                    container_id: container_id.map(str::to_string),
+                },
+            );
+            Ok(())
+        }
+
+        fn navigate(&self, label: &str, url: &str) -> Result<(), String> {
+            let mut webviews = self.webviews.borrow_mut();
+            let webview = webviews.get_mut(label).ok_or("no webview")?;
+            webview.url = url.to_string();
+            Ok(())
+        }
+
+        fn eval(&self, label: &str, script: &str) -> Result<(), String> {
+            self.scripts.borrow_mut().push((label.to_string(), script.to_string()));
+            Ok(())
+        }
+
+        fn show(&self, label: &str, rect: Rect) -> Result<(), String> {
+            let mut webviews = self.webviews.borrow_mut();
+            webviews.get_mut(label).ok_or("no webview")?.shown = Some(rect);
+            Ok(())
+        }
+
+        fn hide(&self, label: &str) -> Result<(), String> {
+            let mut webviews = self.webviews.borrow_mut();
+            webviews.get_mut(label).ok_or("no webview")?.shown = None;
+            Ok(())
+        }
+
+        fn close(&self, label: &str) -> Result<(), String> {
+            self.webviews.borrow_mut().remove(label).map(|_| ()).ok_or("no webview".to_string())
+        }
+
+        fn has_webview(&self, label: &str) -> bool {
+            self.webviews.borrow().contains_key(label)
+        }
+
+        fn content_rect(&self, _window_id: &str) -> Result<Rect, String> {
+            Ok(AREA)
+        }
+
+        fn emit(&self, event: AppEvent) {
+            self.events.borrow_mut().push(event);
+        }
+    }
+
+    impl FakeHost {
+        fn shown(&self) -> Vec<String> {
+            let mut shown: Vec<String> = self
+                .webviews
+                .borrow()
+                .iter()
+                .filter(|(_, w)| w.shown.is_some())
+                .map(|(label, _)| label.clone())
+                .collect();
+            shown.sort();
+            shown
+        }
+
+        fn url(&self, label: &str) -> Option<String> {
+            self.webviews.borrow().get(label).map(|w| w.url.clone())
+        }
+
+        fn event_names(&self) -> Vec<&'static str> {
+            self.events.borrow().iter().map(|e| e.name()).collect()
+        }
+
+        fn clear_events(&self) {
+            self.events.borrow_mut().clear();
+        }
+    }
+
+    /// A window with a tab per URL, the first one active
+    fn setup<'a>(tm: &'a TabManager, host: &'a FakeHost, urls: &[&str]) -> (TabController<'a, &'a FakeHost>, Vec<String>) {
+        let controller = TabController::new(tm, host);
+        let labels = urls
+            .iter()
+            .map(|url| controller.create_tab("main", url, false, None, true, None).unwrap().label)
+            .collect();
+        host.clear_events();
+        (controller, labels)
+    }
+
+    // ── create / activate / close ──────────────────────────
+
+    #[test]
+    fn first_tab_is_shown_background_tabs_hidden() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (_, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+
+        assert_eq!(tm.get_active_tab("main"), Some(labels[0].clone()));
+        assert_eq!(host.shown(), vec![labels[0].clone()]);
+        assert_eq!(host.url(&labels[1]).as_deref(), Some("https://b.com"));
+    }
+
+    #[test]
+    fn foreground_tab_is_activated_and_announced() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, _) = setup(&tm, &host, &["https://a.com"]);
+
+        let tab = controller.create_tab("main", "https://b.com", false, None, false, None).unwrap();
+        assert_eq!(host.shown(), vec![tab.label.clone()]);
+        assert_eq!(host.event_names(), vec!["tab_created", "tab_activated"]);
+    }
+
+    #[test]
+    fn child_tab_placement_is_announced() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+
+        let child = controller
+            .create_tab("main", "https://c.com", false, Some(labels[0].clone()), true, None)
+            .unwrap();
+        assert_eq!(tm.tab_index(&child.label), Some(1));
+        assert_eq!(host.event_names(), vec!["tab_created", "tab_reordered"]);
+    }
+
+    #[test]
+    fn container_follows_opener_and_survives_wake() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, _) = setup(&tm, &host, &["https://a.com"]);
+        let container_of = |label: &str| host.webviews.borrow()[label].container_id.clone();
+
+        let work = controller
+            .create_tab("main", "https://b.com", false, None, false, Some("work".to_string()))
+            .unwrap();
+        assert_eq!(work.container_id.as_deref(), Some("work"));
+        assert_eq!(container_of(&work.label).as_deref(), Some("work"));
+
+        let child = controller
+            .create_tab("main", "https://c.com", false, Some(work.label.clone()), true, None)
+            .unwrap();
+        assert_eq!(child.container_id.as_deref(), Some("work"));
+
+        // A discarded tab's new webview opens in the same container
+        (&host).close(&child.label).unwrap();
+        tm.update_tab(&child.label, |t| t.is_discarded = true);
+        controller.activate(&child.label).unwrap();
+        assert_eq!(container_of(&child.label).as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn activate_swaps_visible_webview() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+
+        controller.activate(&labels[1]).unwrap();
+        assert_eq!(host.shown(), vec![labels[1].clone()]);
+        assert_eq!(host.event_names(), vec!["tab_activated"]);
+    }
+
+    #[test]
+    fn activate_wakes_discarded_tab() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        (&host).close(&labels[1]).unwrap();
+        tm.update_tab(&labels[1], |t| {
+            t.is_discarded = true;
+            t.ping = PingState::Waiting(std::time::Instant::now());
+        });
+
+        controller.activate(&labels[1]).unwrap();
+        let tab = tm.get_tab(&labels[1]).unwrap();
+        assert!(!tab.is_discarded);
+        // A ping sent to the closed webview is forgotten
+        assert_eq!(tab.ping, PingState::Unarmed);
+        assert_eq!(host.url(&labels[1]).as_deref(), Some("https://b.com"));
+        assert_eq!(host.shown(), vec![labels[1].clone()]);
+    }
+
+    #[test]
+    fn discarded_tab_keeps_its_mute_state_when_woken() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        tm.update_tab(&labels[1], |t| t.is_muted = true);
+
+        assert!(controller.discard(&labels[0]).is_err());
+        controller.discard(&labels[1]).unwrap();
+        assert!(host.url(&labels[1]).is_none());
+        assert!(tm.get_tab(&labels[1]).unwrap().is_discarded);
+
+        controller.activate(&labels[1]).unwrap();
+        let tab = tm.get_tab(&labels[1]).unwrap();
+        assert!(!tab.is_discarded);
+        assert!(tab.is_muted);
+    }
+
+    #[test]
+    fn audible_tab_is_not_discarded() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        tm.update_tab(&labels[1], |t| t.is_audible = true);
+
+        assert!(controller.discard(&labels[1]).is_err());
+        assert!(!tm.get_tab(&labels[1]).unwrap().is_discarded);
+        assert!(host.url(&labels[1]).is_some());
+    }
+
+    #[test]
+    fn recover_recreates_crashed_webview_in_place() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        let label = &labels[1];
+        load(&controller, label, "https://b.com");
+        load(&controller, label, "https://c.com");
+        tm.update_tab(label, |t| t.is_crashed = true);
+        host.clear_events();
+
+        assert!(controller.recover(&labels[0]).is_err());
+        controller.recover(label).unwrap();
+
+        let tab = tm.get_tab(label).unwrap();
+        assert!(!tab.is_crashed);
+        assert_eq!(tm.tab_index(label), Some(1));
+        assert_eq!(tab.nav_pos, 1);
+        assert!(tab.nav_restored);
+        assert_eq!(host.url(label).as_deref(), Some("https://c.com"));
+        // Background tab — recreated hidden
+        assert_eq!(host.shown(), vec![labels[0].clone()]);
+        assert_eq!(host.event_names(), vec!["tab_updated"]);
+
+        // Back now loads the entry directly
+        controller.go(label, -1).unwrap();
+        assert_eq!(host.url(label).as_deref(), Some("https://b.com"));
+    }
+
+    #[test]
+    fn unresponsive_tab_is_not_recovered_and_clears_on_next_page() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        tm.update_tab(label, |t| t.is_unresponsive = true);
+
+        // A page blocked on a dialog keeps its webview
+        assert!(controller.recover(label).is_err());
+        assert_eq!(host.url(label).as_deref(), Some("https://a.com"));
+
+        controller.page_load(label, "https://b.com", true);
+        assert!(!tm.get_tab(label).unwrap().is_unresponsive);
+    }
+
+    #[test]
+    fn split_panes_are_shown_side_by_side() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://c.com"]);
+        tm.add_split(&labels[..2], None).unwrap();
+
+        controller.activate(&labels[1]).unwrap();
+        assert_eq!(host.shown(), vec![labels[0].clone(), labels[1].clone()]);
+        let left = host.webviews.borrow()[&labels[0]].shown.unwrap();
+        let right = host.webviews.borrow()[&labels[1]].shown.unwrap();
+        assert!(left.x < right.x);
+        assert_eq!(left.width + right.width + SPLIT_GAP, AREA.width);
+    }
+
+    #[test]
+    fn closing_active_tab_focuses_neighbour() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://c.com"]);
+        controller.activate(&labels[1]).unwrap();
+        host.clear_events();
+
+        let closed = controller.close(&labels[1], false, "about:blank").unwrap();
+        assert_eq!(closed.index, 1);
+        assert_eq!(closed.tab.url, "https://b.com");
+        assert!(host.url(&labels[1]).is_none());
+        assert_eq!(tm.get_active_tab("main"), Some(labels[2].clone()));
+        assert_eq!(host.shown(), vec![labels[2].clone()]);
+        assert_eq!(host.event_names(), vec!["tab_closed", "tab_activated"]);
+    }
+
+    #[test]
+    fn closing_child_returns_to_opener() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        let child = controller
+            .create_tab("main", "https://c.com", false, Some(labels[0].clone()), false, None)
+            .unwrap();
+
+        controller.close(&child.label, false, "about:blank").unwrap();
+        assert_eq!(tm.get_active_tab("main"), Some(labels[0].clone()));
+    }
+
+    #[test]
+    fn closing_last_tab_opens_a_new_one() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+
+        controller.close(&labels[0], false, "about:blank").unwrap();
+        let remaining = tm.get_window_tabs("main");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].url, "about:blank");
+        assert_eq!(tm.get_active_tab("main"), Some(remaining[0].label.clone()));
+    }
+
+    #[test]
+    fn closing_tabs_to_the_right_of_the_active_one_keeps_it() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://c.com"]);
+        controller.activate(&labels[2]).unwrap();
+        host.clear_events();
+
+        let selector = CloseSelector::ToRight { label: labels[0].clone() };
+        let batch = controller.close_many("main", &selector, "about:blank").unwrap();
+        assert!(batch.active_closed);
+        assert_eq!(batch.closed.len(), 2);
+        assert!(host.url(&labels[1]).is_none() && host.url(&labels[2]).is_none());
+        assert_eq!(tm.get_active_tab("main"), Some(labels[0].clone()));
+        assert_eq!(host.shown(), vec![labels[0].clone()]);
+        assert_eq!(host.event_names(), vec!["tabs_closed", "tab_activated"]);
+    }
+
+    #[test]
+    fn closing_duplicates_keeps_the_active_copy() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://a.com#top"]);
+        controller.activate(&labels[2]).unwrap();
+
+        let batch = controller.close_many("main", &CloseSelector::Duplicates, "about:blank").unwrap();
+        assert!(!batch.active_closed);
+        assert!(host.url(&labels[0]).is_none());
+        assert_eq!(tm.get_active_tab("main"), Some(labels[2].clone()));
+        assert_eq!(host.shown(), vec![labels[2].clone()]);
+
+        // Nothing left to close: no event, nothing changes
+        host.clear_events();
+        let batch = controller.close_many("main", &CloseSelector::Duplicates, "about:blank").unwrap();
+        assert!(batch.closed.is_empty());
+        assert!(host.event_names().is_empty());
+    }
+
+    #[test]
+    fn closing_every_tab_by_domain_opens_a_new_one() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, _) = setup(&tm, &host, &["https://a.com", "https://docs.a.com"]);
+
+        let selector = CloseSelector::Domain { domain: "a.com".to_string() };
+        controller.close_many("main", &selector, "about:blank").unwrap();
+        let remaining = tm.get_window_tabs("main");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].url, "about:blank");
+        assert_eq!(tm.get_active_tab("main"), Some(remaining[0].label.clone()));
+    }
+
+    #[test]
+    fn pinned_tab_needs_force_to_close() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        tm.set_pinned(&labels[0], true).unwrap();
+
+        assert!(controller.close(&labels[0], false, "about:blank").is_err());
+        assert!(controller.close(&labels[0], true, "about:blank").is_ok());
+    }
+
+    #[test]
+    fn closing_a_pane_dissolves_two_tab_split() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        tm.add_split(&labels, None).unwrap();
+        controller.layout("main").unwrap();
+        host.clear_events();
+
+        controller.close(&labels[1], false, "about:blank").unwrap();
+        assert!(tm.get_splits().is_empty());
+        assert!(host.event_names().contains(&"tab_split_removed"));
+        assert_eq!(host.webviews.borrow()[&labels[0]].shown, Some(AREA));
+    }
+
+    // ── reorder ────────────────────────────────────────────
+
+    #[test]
+    fn reorder_moves_tab_and_reports_indices() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://c.com"]);
+
+        controller.reorder(&labels[0], 2, false).unwrap();
+        assert_eq!(tm.get_tab_labels("main"), vec![labels[1].clone(), labels[2].clone(), labels[0].clone()]);
+        let events = host.events.borrow();
+        assert!(matches!(
+            &events[..],
+            [AppEvent::TabReordered { old_index: 0, new_index: 2, .. }]
+        ));
+    }
+
+    #[test]
+    fn reorder_out_of_group_updates_membership() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com", "https://c.com"]);
+        tm.add_group(TabGroup {
+            id: "g1".to_string(),
+            name: String::new(),
+            color: "blue".to_string(),
+            collapsed: false,
+        });
+        tm.group_add_tab(&labels[0], "g1").unwrap();
+        tm.group_add_tab(&labels[1], "g1").unwrap();
+
+        controller.reorder(&labels[0], 2, false).unwrap();
+        assert_eq!(tm.get_tab(&labels[0]).unwrap().group_id, None);
+        assert_eq!(host.event_names(), vec!["tab_reordered", "tab_updated"]);
+    }
+
+    // ── navigation ─────────────────────────────────────────
+
+    fn entries(urls: &[&str]) -> Vec<NavEntry> {
+        urls.iter().map(|url| NavEntry::new(url)).collect()
+    }
+
+    /// Simulate the webview finishing a load, as `on_page_load` reports it
+    fn load(controller: &TabController<&FakeHost>, label: &str, url: &str) {
+        controller.page_load(label, url, true);
+        controller.page_load(label, url, false);
+    }
+
+    #[test]
+    fn navigation_builds_history() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        load(&controller, label, "https://a.com");
+
+        controller.navigate(label, "https://b.com").unwrap();
+        assert_eq!(host.url(label).as_deref(), Some("https://b.com"));
+        load(&controller, label, "https://b.com");
+
+        let tab = tm.get_tab(label).unwrap();
+        let urls: Vec<&str> = tab.nav_stack.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://a.com", "https://b.com"]);
+        assert!(tab.can_go_back);
+        assert!(!tab.can_go_forward);
+    }
+
+    #[test]
+    fn back_uses_webview_history_then_load_keeps_stack() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        load(&controller, label, "https://a.com");
+        load(&controller, label, "https://b.com"); // Link click
+
+        assert!(controller.go(label, -1).unwrap());
+        assert_eq!(
+            host.scripts.borrow().last(),
+            Some(&(label.clone(), "window.history.go(-1)".to_string()))
+        );
+        load(&controller, label, "https://a.com");
+
+        let tab = tm.get_tab(label).unwrap();
+        assert_eq!(tab.nav_pos, 0);
+        assert_eq!(tab.nav_stack.len(), 2);
+        assert!(tab.can_go_forward);
+        assert!(!controller.go(label, -1).unwrap());
+    }
+
+    #[test]
+    fn restored_history_loads_entries_directly() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://b.com"]);
+        let label = &labels[0];
+        tm.update_tab(label, |t| t.restore_nav(entries(&["https://a.com", "https://b.com"]), 1));
+
+        controller.go(label, -1).unwrap();
+        assert_eq!(host.url(label).as_deref(), Some("https://a.com"));
+        assert!(host.scripts.borrow().is_empty());
+    }
+
+    #[test]
+    fn restored_entry_gets_its_scroll_back() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://b.com"]);
+        let label = &labels[0];
+        let mut stack = entries(&["https://a.com", "https://b.com"]);
+        stack[0].scroll_y = 640.0;
+        tm.update_tab(label, |t| t.restore_nav(stack, 1));
+
+        controller.go(label, -1).unwrap();
+        load(&controller, label, "https://a.com");
+        assert_eq!(
+            host.scripts.borrow().last(),
+            Some(&(label.clone(), "window.scrollTo(0, 640)".to_string()))
+        );
+    }
+
+    #[test]
+    fn go_to_index_jumps_several_entries() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        for url in ["https://a.com", "https://b.com", "https://c.com", "https://d.com"] {
+            load(&controller, label, url);
+        }
+
+        assert!(controller.go_to_index(label, 0).unwrap());
+        assert_eq!(
+            host.scripts.borrow().last(),
+            Some(&(label.clone(), "window.history.go(-3)".to_string()))
+        );
+        load(&controller, label, "https://a.com");
+
+        let tab = tm.get_tab(label).unwrap();
+        assert_eq!(tab.nav_pos, 0);
+        assert_eq!(tab.nav_stack.len(), 4);
+        assert!(tab.can_go_forward);
+
+        assert!(!controller.go_to_index(label, 0).unwrap());
+        assert!(controller.go_to_index(label, 4).is_err());
+    }
+
+    #[test]
+    fn spa_routes_go_into_history_and_back_stays_in_page() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://app.com/"]);
+        let label = &labels[0];
+        load(&controller, label, "https://app.com/");
+        host.clear_events();
+
+        assert!(controller.spa_navigate(label, "https://app.com/inbox", SpaNavigation::Push).unwrap());
+        let events = host.events.borrow().clone();
+        let [AppEvent::TabUpdated(update)] = &events[..] else {
+            panic!("expected one tab_updated, got {:?}", events);
+        };
+        assert_eq!(update.url.as_deref(), Some("https://app.com/inbox"));
+        assert_eq!(update.can_go_back, Some(true));
+
+        // Back goes through the webview's history; the page answers with popstate
+        assert!(controller.go(label, -1).unwrap());
+        assert!(controller.spa_navigate(label, "https://app.com/", SpaNavigation::Pop).unwrap());
+        assert!(!controller.spa_navigate(label, "https://app.com/", SpaNavigation::Pop).unwrap());
+
+        let tab = tm.get_tab(label).unwrap();
+        assert_eq!(tab.nav_pos, 0);
+        assert!(!tab.nav_traversing);
+        assert!(tab.can_go_forward);
+    }
+
+    #[test]
+    fn navigating_discarded_tab_fails() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com", "https://b.com"]);
+        (&host).close(&labels[1]).unwrap();
+
+        assert!(controller.navigate(&labels[1], "https://c.com").is_err());
+        assert!(controller.go(&labels[1], -1).is_err());
+    }
+
+    #[test]
+    fn page_load_reports_nav_state() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        load(&controller, &labels[0], "https://a.com");
+        host.clear_events();
+
+        controller.page_load(&labels[0], "https://b.com", false);
+        let events = host.events.borrow();
+        let [AppEvent::TabUpdated(update)] = &events[..] else {
+            panic!("expected one tab_updated, got {:?}", events);
+        };
+        assert_eq!(update.url.as_deref(), Some("https://b.com"));
+        assert_eq!(update.loading, Some(false));
+        assert_eq!(update.can_go_back, Some(true));
+    }
+
+    #[test]
+    fn failed_load_shows_error_page_and_keeps_url() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        load(&controller, label, "https://a.com");
+
+        controller.navigate(label, "https://nope.invalid").unwrap();
+        controller.page_load(label, "https://nope.invalid", true);
+        let error = LoadError::new("https://nope.invalid", LoadErrorKind::Dns);
+        controller.load_failed(label, error.clone()).unwrap();
+        assert_eq!(host.url(label), Some(error.page_url()));
+        load(&controller, label, &error.page_url());
+
+        let tab = tm.get_tab(label).unwrap();
+        assert_eq!(tab.url, "https://nope.invalid");
+        assert_eq!(tab.load_error, Some(error));
+        assert_eq!(tab.nav_stack.len(), 2);
+
+        // Back loads the previous entry itself and the error goes with the page
+        controller.go(label, -1).unwrap();
+        assert_eq!(host.url(label).as_deref(), Some("https://a.com"));
+        host.clear_events();
+        controller.page_load(label, "https://a.com", true);
+        assert!(tm.get_tab(label).unwrap().load_error.is_none());
+        let events = host.events.borrow();
+        let [AppEvent::TabUpdated(update)] = &events[..] else {
+            panic!("expected one tab_updated, got {:?}", events);
+        };
+        assert_eq!(update.load_error, Some(None));
+    }
+
+    #[test]
+    fn retry_reloads_failed_url_in_the_same_entry() {
+        let tm = TabManager::new();
+        let host = FakeHost::default();
+        let (controller, labels) = setup(&tm, &host, &["https://a.com"]);
+        let label = &labels[0];
+        assert!(!controller.retry(label).unwrap());
+
+        let error = LoadError::new("https://a.com", LoadErrorKind::Timeout);
+        controller.load_failed(label, error.clone()).unwrap();
+        load(&controller, label, &error.page_url());
+
+        assert!(controller.retry(label).unwrap());
+        assert_eq!(host.url(label).as_deref(), Some("https://a.com"));
+        load(&controller, label, "https://a.com");
+        let tab = tm.get_tab(label).unwrap();
+        assert!(tab.load_error.is_none());
+        assert_eq!(tab.nav_stack.len(), 1);
+    }
+}
@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::tab_state::{window_of, with_window, TabInfo, TabManager};
+
+/// Global group ID counter — ensures unique group IDs across the app lifetime
+static GROUP_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Colours a group can take (Chrome's palette)
+pub const GROUP_COLORS: &[&str] = &[
+    "grey", "blue", "red", "yellow", "green", "pink", "purple", "cyan", "orange",
+];
+
+/// Generate the next unique group ID (e.g. "group-1", "group-2")
+pub fn next_group_id() -> String {
+    let id = GROUP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("group-{}", id)
+}
+
+/// A named, coloured set of adjacent tabs, sent to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabGroup {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub collapsed: bool,
+}
+
+/// Start and end (exclusive) of a group's members. Members are always adjacent.
+fn group_span(tabs: &[TabInfo], group_id: &str) -> Option<(usize, usize)> {
+    let is_member = |t: &TabInfo| t.group_id.as_deref() == Some(group_id);
+    let start = tabs.iter().position(is_member)?;
+    let end = tabs.iter().rposition(is_member)? + 1;
+    Some((start, end))
+}
+
+/// Push an insertion index past any group it would split
+pub(super) fn snap_out_of_groups(tabs: &[TabInfo], index: usize) -> usize {
+    if index == 0 || index >= tabs.len() {
+        return index;
+    }
+    match &tabs[index - 1].group_id {
+        Some(id) if tabs[index].group_id.as_ref() == Some(id) => {
+            group_span(tabs, id).map(|(_, end)| end).unwrap_or(index)
+        }
+        _ => index,
+    }
+}
+
+impl TabManager {
+    pub fn get_groups(&self) -> Vec<TabGroup> {
+        let groups = self.groups.lock().unwrap();
+        groups.clone()
+    }
+
+    pub fn get_group(&self, id: &str) -> Option<TabGroup> {
+        let groups = self.groups.lock().unwrap();
+        groups.iter().find(|g| g.id == id).cloned()
+    }
+
+    pub fn add_group(&self, group: TabGroup) {
+        let mut groups = self.groups.lock().unwrap();
+        groups.push(group);
+    }
+
+    /// Update a group in place and return its new state
+    pub fn update_group<F>(&self, id: &str, updater: F) -> Option<TabGroup>
+    where
+        F: FnOnce(&mut TabGroup),
+    {
+        let mut groups = self.groups.lock().unwrap();
+        let group = groups.iter_mut().find(|g| g.id == id)?;
+        updater(group);
+        Some(group.clone())
+    }
+
+    /// Labels of a group's member tabs, in tab-strip order
+    pub fn group_members(&self, id: &str) -> Vec<String> {
+        let tabs = self.tabs.lock().unwrap();
+        tabs.iter()
+            .filter(|t| t.group_id.as_deref() == Some(id))
+            .map(|t| t.label.clone())
+            .collect()
+    }
+
+    /// Put a tab into a group, moving it after the group's other members.
+    /// Returns the old and new index.
+    pub fn group_add_tab(&self, label: &str, group_id: &str) -> Result<(usize, usize), String> {
+        if self.get_group(group_id).is_none() {
+            return Err("Group not found".to_string());
+        }
+
+        let mut tabs = self.tabs.lock().unwrap();
+        let window_id = window_of(&tabs, label)?;
+        let other_window = tabs
+            .iter()
+            .any(|t| t.group_id.as_deref() == Some(group_id) && t.window_id != window_id);
+        if other_window {
+            return Err("Tab and group are in different windows".to_string());
+        }
+
+        with_window(&mut tabs, &window_id, |tabs| {
+            let old_index = tabs
+                .iter()
+                .position(|t| t.label == label)
+                .ok_or("Tab not found")?;
+            if tabs[old_index].is_pinned {
+                return Err("Pinned tabs can't be grouped".to_string());
+            }
+
+            let mut tab = tabs.remove(old_index);
+            tab.group_id = Some(group_id.to_string());
+            let new_index = match group_span(tabs, group_id) {
+                Some((_, end)) => end,
+                None => snap_out_of_groups(tabs, old_index),
+            };
+            tabs.insert(new_index, tab);
+            Ok((old_index, new_index))
+        })
+    }
+
+    /// Take a tab out of its group, moving it just past the group's remaining members.
+    /// Returns the old and new index.
+    pub fn group_remove_tab(&self, label: &str) -> Result<(usize, usize), String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let window_id = window_of(&tabs, label)?;
+        with_window(&mut tabs, &window_id, |tabs| {
+            let old_index = tabs
+                .iter()
+                .position(|t| t.label == label)
+                .ok_or("Tab not found")?;
+
+            let mut tab = tabs.remove(old_index);
+            let new_index = match tab.group_id.take() {
+                Some(group_id) => group_span(tabs, &group_id)
+                    .map(|(_, end)| end)
+                    .unwrap_or(old_index),
+                None => old_index,
+            };
+            tabs.insert(new_index, tab);
+            Ok((old_index, new_index))
+        })
+    }
+
+    /// Move a whole group so its first tab lands at `new_index`, never inside the
+    /// pinned block or another group. Returns the old and new index of its first tab.
+    pub fn move_group(&self, group_id: &str, new_index: usize) -> Result<(usize, usize), String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let window_id = tabs
+            .iter()
+            .find(|t| t.group_id.as_deref() == Some(group_id))
+            .map(|t| t.window_id.clone())
+            .ok_or("Group not found")?;
+
+        with_window(&mut tabs, &window_id, |tabs| {
+            let (start, end) = group_span(tabs, group_id).ok_or("Group not found")?;
+
+            if new_index >= tabs.len() {
+                return Err("Invalid index".to_string());
+            }
+
+            let block: Vec<TabInfo> = tabs.drain(start..end).collect();
+            let pinned = tabs.iter().take_while(|t| t.is_pinned).count();
+            let target = snap_out_of_groups(tabs, new_index.clamp(pinned, tabs.len()));
+            tabs.splice(target..target, block);
+            Ok((start, target))
+        })
+    }
+
+    /// Nearest tab outside a group that isn't hidden in a collapsed group —
+    /// used to move focus away when the group collapses
+    pub fn get_adjacent_outside_group(&self, group_id: &str) -> Option<String> {
+        let tabs = self.tabs.lock().unwrap();
+        let groups = self.groups.lock().unwrap();
+        let window_id = &tabs.iter().find(|t| t.group_id.as_deref() == Some(group_id))?.window_id;
+        let tabs: Vec<TabInfo> = tabs.iter().filter(|t| &t.window_id == window_id).cloned().collect();
+        let (start, end) = group_span(&tabs, group_id)?;
+
+        let is_visible = |t: &&TabInfo| match &t.group_id {
+            Some(id) => !groups.iter().any(|g| &g.id == id && g.collapsed),
+            None => true,
+        };
+
+        tabs[end..]
+            .iter()
+            .find(is_visible)
+            .or_else(|| tabs[..start].iter().rev().find(is_visible))
+            .map(|t| t.label.clone())
+    }
+
+    /// Drop groups that no longer have any member tabs. Returns the removed group IDs.
+    pub fn prune_groups(&self) -> Vec<String> {
+        let tabs = self.tabs.lock().unwrap();
+        let mut groups = self.groups.lock().unwrap();
+
+        let mut removed = Vec::new();
+        groups.retain(|g| {
+            let used = tabs.iter().any(|t| t.group_id.as_deref() == Some(g.id.as_str()));
+            if !used {
+                removed.push(g.id.clone());
+            }
+            used
+        });
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper: create a TabInfo, optionally in a group
+    fn make_tab(label: &str, group_id: Option<&str>) -> TabInfo {
+        TabInfo {
+            group_id: group_id.map(|g| g.to_string()),
+            ..TabInfo::for_test(label, "https://example.com", "main")
+        }
+    }
+
+    fn make_group(id: &str) -> TabGroup {
+        TabGroup {
+            id: id.to_string(),
+            name: "Work".to_string(),
+            color: "blue".to_string(),
+            collapsed: false,
+        }
+    }
+
+    /// Manager with tabs t1, [g: a1, a2], t2
+    fn grouped_manager() -> TabManager {
+        let tm = TabManager::new();
+        tm.add_group(make_group("g"));
+        tm.add_tab(make_tab("t1", None));
+        tm.add_tab(make_tab("a1", Some("g")));
+        tm.add_tab(make_tab("a2", Some("g")));
+        tm.add_tab(make_tab("t2", None));
+        tm
+    }
+
+    // ── Group registry ─────────────────────────────────────
+
+    #[test]
+    fn update_group_returns_new_state() {
+        let tm = grouped_manager();
+        let group = tm.update_group("g", |g| g.collapsed = true).unwrap();
+        assert!(group.collapsed);
+        assert!(tm.get_group("g").unwrap().collapsed);
+        assert!(tm.update_group("missing", |g| g.collapsed = true).is_none());
+    }
+
+    #[test]
+    fn group_members_in_order() {
+        let tm = grouped_manager();
+        assert_eq!(tm.group_members("g"), vec!["a1", "a2"]);
+    }
+
+    // ── Membership ─────────────────────────────────────────
+
+    #[test]
+    fn add_tab_joins_after_last_member() {
+        let tm = grouped_manager();
+        assert_eq!(tm.group_add_tab("t2", "g"), Ok((3, 3)));
+        assert_eq!(tm.group_add_tab("t1", "g"), Ok((0, 3)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["a1", "a2", "t2", "t1"]);
+        assert_eq!(tm.group_members("g"), vec!["a1", "a2", "t2", "t1"]);
+    }
+
+    #[test]
+    fn add_tab_to_unknown_group_errors() {
+        let tm = grouped_manager();
+        assert!(tm.group_add_tab("t1", "missing").is_err());
+    }
+
+    #[test]
+    fn pinned_tab_cannot_join_group() {
+        let tm = grouped_manager();
+        tm.update_tab("t1", |t| t.is_pinned = true);
+        assert!(tm.group_add_tab("t1", "g").is_err());
+    }
+
+    #[test]
+    fn remove_tab_moves_past_remaining_members() {
+        let tm = grouped_manager();
+        assert_eq!(tm.group_remove_tab("a1"), Ok((1, 2)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "a2", "a1", "t2"]);
+        assert!(tm.get_tab("a1").unwrap().group_id.is_none());
+    }
+
+    #[test]
+    fn remove_ungrouped_tab_is_noop() {
+        let tm = grouped_manager();
+        assert_eq!(tm.group_remove_tab("t1"), Ok((0, 0)));
+    }
+
+    // ── Moving groups ──────────────────────────────────────
+
+    #[test]
+    fn move_group_to_start() {
+        let tm = grouped_manager();
+        assert_eq!(tm.move_group("g", 0), Ok((1, 0)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["a1", "a2", "t1", "t2"]);
+    }
+
+    #[test]
+    fn move_group_to_end() {
+        let tm = grouped_manager();
+        assert_eq!(tm.move_group("g", 3), Ok((1, 2)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2", "a1", "a2"]);
+    }
+
+    #[test]
+    fn move_group_stays_after_pinned_tabs() {
+        let tm = grouped_manager();
+        tm.update_tab("t1", |t| t.is_pinned = true);
+        assert_eq!(tm.move_group("g", 0), Ok((1, 1)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "a1", "a2", "t2"]);
+    }
+
+    #[test]
+    fn move_group_never_splits_another_group() {
+        let tm = grouped_manager();
+        tm.add_group(make_group("h"));
+        tm.add_tab(make_tab("b1", Some("h")));
+        tm.add_tab(make_tab("b2", Some("h")));
+
+        // Dropping h between a1 and a2 lands it after group g instead
+        assert_eq!(tm.move_group("h", 2), Ok((4, 3)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "a1", "a2", "b1", "b2", "t2"]);
+    }
+
+    #[test]
+    fn move_missing_group_errors() {
+        let tm = grouped_manager();
+        assert!(tm.move_group("missing", 0).is_err());
+    }
+
+    // ── Collapse focus / pruning ───────────────────────────
+
+    #[test]
+    fn adjacent_outside_group_prefers_right() {
+        let tm = grouped_manager();
+        assert_eq!(tm.get_adjacent_outside_group("g"), Some("t2".to_string()));
+    }
+
+    #[test]
+    fn adjacent_outside_group_skips_collapsed_groups() {
+        let tm = grouped_manager();
+        tm.add_group(TabGroup {
+            collapsed: true,
+            ..make_group("h")
+        });
+        tm.update_tab("t2", |t| t.group_id = Some("h".to_string()));
+        assert_eq!(tm.get_adjacent_outside_group("g"), Some("t1".to_string()));
+    }
+
+    #[test]
+    fn prune_drops_empty_groups() {
+        let tm = grouped_manager();
+        tm.add_group(make_group("empty"));
+        assert_eq!(tm.prune_groups(), vec!["empty"]);
+        assert!(tm.get_group("empty").is_none());
+        assert!(tm.get_group("g").is_some());
+    }
+
+    #[test]
+    fn group_ids_are_unique() {
+        assert_ne!(next_group_id(), next_group_id());
+    }
+
+    #[test]
+    fn groups_stay_within_one_window() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", None));
+        tm.add_tab(TabInfo { window_id: "w2".to_string(), ..make_tab("u1", None) });
+        tm.add_tab(make_tab("t2", None));
+        tm.add_group(make_group("g1"));
+
+        tm.group_add_tab("t2", "g1").unwrap();
+        assert!(tm.group_add_tab("u1", "g1").is_err());
+        assert_eq!(tm.get_tab("u1").unwrap().group_id, None);
+
+        // Moving the group is bounded by its window's tabs
+        assert_eq!(tm.move_group("g1", 0).unwrap(), (1, 0));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t2", "t1"]);
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u1"]);
+    }
+
+}
@@ -0,0 +1,266 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::tab_state::TabManager;
+
+/// URL matches count for a bit less than title matches
+const URL_WEIGHT: f64 = 0.8;
+
+/// How much a just-used tab is boosted over one never used (score × up to 1.5)
+const RECENCY_WEIGHT: f64 = 0.5;
+
+/// The recency boost halves every 30 minutes since the tab was last shown
+const RECENCY_HALF_LIFE: Duration = Duration::from_secs(30 * 60);
+
+/// A tab matching a search, sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct TabSearchResult {
+    pub label: String,
+    pub window_id: String,
+    pub title: String,
+    pub url: String,
+    pub favicon: Option<String>,
+    pub is_active: bool,
+    pub score: f64,
+}
+
+/// Score how well `query` fuzzily matches `text`, case-insensitively: None when its
+/// characters don't all appear in order. Substrings beat scattered characters, and
+/// matches at the start of the text or of a word beat ones in the middle.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<f64> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    if query.is_empty() {
+        return Some(0.0);
+    }
+
+    if let Some(pos) = text.find(&query) {
+        let score = if pos == 0 {
+            3.0
+        } else if is_word_start(&text, pos) {
+            2.5
+        } else {
+            2.0
+        };
+        return Some(score);
+    }
+
+    // Subsequence match: reward runs of consecutive characters and word starts
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut points = 0.0;
+    let mut next = 0;
+    let mut prev: Option<usize> = None;
+    for q in query.chars() {
+        let found = chars[next..].iter().position(|&(_, c)| c == q)? + next;
+        points += 1.0;
+        if prev.is_some_and(|p| p + 1 == found) {
+            points += 1.0;
+        } else if is_word_start(&text, chars[found].0) {
+            points += 0.75;
+        }
+        prev = Some(found);
+        next = found + 1;
+    }
+
+    // At most 2 points per character — scaled to stay below any substring match
+    Some(points / query.chars().count() as f64 * 0.95)
+}
+
+/// Whether the character at byte offset `pos` starts a word
+fn is_word_start(text: &str, pos: usize) -> bool {
+    text[..pos]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !c.is_alphanumeric())
+}
+
+/// Drop the scheme and "www." so they never match a query
+fn strip_url(url: &str) -> &str {
+    let url = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    url.strip_prefix("www.").unwrap_or(url)
+}
+
+/// Recency multiplier: 1 + RECENCY_WEIGHT for a tab in use now, fading to 1
+fn recency_boost(now: Instant, last_active: Option<Instant>, is_active: bool) -> f64 {
+    if is_active {
+        return 1.0 + RECENCY_WEIGHT;
+    }
+    let Some(last_active) = last_active else {
+        return 1.0;
+    };
+    let age = now.saturating_duration_since(last_active).as_secs_f64();
+    let decay = 0.5_f64.powf(age / RECENCY_HALF_LIFE.as_secs_f64());
+    1.0 + RECENCY_WEIGHT * decay
+}
+
+impl TabManager {
+    /// Search every window's tabs by title and URL. Each word of `query` must match
+    /// one or the other; scores are averaged over the words and weighted by how
+    /// recently the tab was used. An empty query lists tabs by recency.
+    pub fn search_tabs(&self, query: &str, now: Instant, limit: usize) -> Vec<TabSearchResult> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+
+        let mut results: Vec<TabSearchResult> = self
+            .get_all_tabs()
+            .into_iter()
+            .filter_map(|tab| {
+                let mut total = 0.0;
+                for term in &terms {
+                    let title = fuzzy_score(term, &tab.title);
+                    let url = fuzzy_score(term, strip_url(&tab.url)).map(|s| s * URL_WEIGHT);
+                    total += title.into_iter().chain(url).reduce(f64::max)?;
+                }
+                let relevance = if terms.is_empty() { 1.0 } else { total / terms.len() as f64 };
+
+                let is_active = self.is_active(&tab.label);
+                Some(TabSearchResult {
+                    score: relevance * recency_boost(now, tab.last_active, is_active),
+                    is_active,
+                    label: tab.label,
+                    window_id: tab.window_id,
+                    title: tab.title,
+                    url: tab.url,
+                    favicon: tab.favicon,
+                })
+            })
+            .collect();
+
+        // Stable sort keeps strip order between equal scores
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tab_state::TabInfo;
+
+    fn make_tab(label: &str, window_id: &str, title: &str, url: &str) -> TabInfo {
+        TabInfo {
+            title: title.to_string(),
+            ..TabInfo::for_test(label, url, window_id)
+        }
+    }
+
+    fn labels(results: &[TabSearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.label.as_str()).collect()
+    }
+
+    // ── fuzzy_score ────────────────────────────────────────
+
+    #[test]
+    fn substring_beats_scattered_match() {
+        let substring = fuzzy_score("hub", "GitHub").unwrap();
+        let scattered = fuzzy_score("gthb", "GitHub").unwrap();
+        assert!(substring > scattered);
+    }
+
+    #[test]
+    fn prefix_and_word_start_rank_higher() {
+        let prefix = fuzzy_score("rust", "Rust Book").unwrap();
+        let word = fuzzy_score("book", "Rust Book").unwrap();
+        let middle = fuzzy_score("ook", "Rust Book").unwrap();
+        assert!(prefix > word && word > middle);
+    }
+
+    #[test]
+    fn initials_match_across_words() {
+        assert!(fuzzy_score("gh", "GitHub - Pull Requests").is_some());
+        assert!(fuzzy_score("pr", "GitHub - Pull Requests").is_some());
+    }
+
+    #[test]
+    fn missing_or_out_of_order_chars_fail() {
+        assert!(fuzzy_score("xyz", "GitHub").is_none());
+        assert!(fuzzy_score("bg", "GitHub").is_none());
+    }
+
+    #[test]
+    fn matching_is_case_insensitive() {
+        assert_eq!(fuzzy_score("GITHUB", "github"), fuzzy_score("github", "GitHub"));
+    }
+
+    #[test]
+    fn url_scheme_and_www_are_ignored() {
+        assert_eq!(strip_url("https://www.example.com/a"), "example.com/a");
+        assert_eq!(strip_url("aero://settings"), "settings");
+    }
+
+    // ── search_tabs ────────────────────────────────────────
+
+    #[test]
+    fn search_matches_title_or_url_across_windows() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "main", "Inbox", "https://mail.example.com"));
+        tm.add_tab(make_tab("t2", "w2", "Docs", "https://docs.rs/serde"));
+        tm.add_tab(make_tab("t3", "main", "News", "https://news.site"));
+
+        let results = tm.search_tabs("serde", Instant::now(), 10);
+        assert_eq!(labels(&results), vec!["t2"]);
+        assert_eq!(results[0].window_id, "w2");
+
+        let results = tm.search_tabs("inbox", Instant::now(), 10);
+        assert_eq!(labels(&results), vec!["t1"]);
+    }
+
+    #[test]
+    fn every_word_must_match() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "main", "Rust Book", "https://doc.rust-lang.org/book"));
+        tm.add_tab(make_tab("t2", "main", "Rust Blog", "https://blog.rust-lang.org"));
+
+        let results = tm.search_tabs("rust book", Instant::now(), 10);
+        assert_eq!(labels(&results), vec!["t1"]);
+    }
+
+    #[test]
+    fn better_match_ranks_first() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "main", "Getting Things Done", "https://a.com"));
+        tm.add_tab(make_tab("t2", "main", "GitHub", "https://github.com"));
+
+        let results = tm.search_tabs("github", Instant::now(), 10);
+        assert_eq!(labels(&results)[0], "t2");
+    }
+
+    #[test]
+    fn recent_tab_wins_a_tie() {
+        let tm = TabManager::new();
+        let now = Instant::now();
+        tm.add_tab(make_tab("t1", "main", "Issue #1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "main", "Issue #2", "https://b.com"));
+        tm.update_tab("t1", |t| t.last_active = Some(now - Duration::from_secs(3600)));
+        tm.update_tab("t2", |t| t.last_active = Some(now - Duration::from_secs(60)));
+
+        let results = tm.search_tabs("issue", now, 10);
+        assert_eq!(labels(&results), vec!["t2", "t1"]);
+    }
+
+    #[test]
+    fn active_tab_is_flagged_and_boosted() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "main", "Page", "https://a.com"));
+        tm.add_tab(make_tab("t2", "main", "Page", "https://b.com"));
+        tm.set_active_tab("main", Some("t2".to_string()));
+
+        let results = tm.search_tabs("page", Instant::now(), 10);
+        assert_eq!(labels(&results), vec!["t2", "t1"]);
+        assert!(results[0].is_active);
+        assert!(!results[1].is_active);
+    }
+
+    #[test]
+    fn empty_query_lists_by_recency_up_to_limit() {
+        let tm = TabManager::new();
+        let now = Instant::now();
+        for label in ["t1", "t2", "t3"] {
+            tm.add_tab(make_tab(label, "main", "Tab", "https://a.com"));
+        }
+        tm.update_tab("t3", |t| t.last_active = Some(now));
+
+        let results = tm.search_tabs("  ", now, 2);
+        assert_eq!(labels(&results), vec!["t3", "t1"]);
+    }
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::load_error::LoadError;
+use super::split_view::SplitView;
+use super::tab_groups::{snap_out_of_groups, TabGroup};
+use super::watchdog::PingState;
 
 /// Global tab ID counter — ensures unique labels across the app lifetime
 static TAB_COUNTER: AtomicU64 = AtomicU64::new(1);
@@ -11,46 +18,420 @@ pub fn next_tab_label() -> String {
     format!("tab-{}", id)
 }
 
+/// Milliseconds since the Unix epoch
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// One page in a tab's back/forward history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SavedNavEntry")]
+pub struct NavEntry {
+    pub url: String,
+    pub title: String,
+    pub favicon: Option<String>,
+    /// When the page was navigated to, in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// How far the page was scrolled down when last reported, in CSS pixels
+    pub scroll_y: f64,
+}
+
+impl NavEntry {
+    /// A fresh entry for `url`, visited now. Title and favicon follow once the page reports them.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            title: String::new(),
+            favicon: None,
+            timestamp: now_millis(),
+            scroll_y: 0.0,
+        }
+    }
+}
+
+/// A nav entry as stored in the session and closed-tab tables — older rows hold bare URLs
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedNavEntry {
+    Url(String),
+    Entry {
+        url: String,
+        #[serde(default)]
+        title: String,
+        #[serde(default)]
+        favicon: Option<String>,
+        #[serde(default)]
+        timestamp: i64,
+        #[serde(default)]
+        scroll_y: f64,
+    },
+}
+
+impl From<SavedNavEntry> for NavEntry {
+    fn from(saved: SavedNavEntry) -> Self {
+        match saved {
+            SavedNavEntry::Url(url) => Self {
+                url,
+                title: String::new(),
+                favicon: None,
+                timestamp: 0,
+                scroll_y: 0.0,
+            },
+            SavedNavEntry::Entry { url, title, favicon, timestamp, scroll_y } => Self {
+                url,
+                title,
+                favicon,
+                timestamp,
+                scroll_y,
+            },
+        }
+    }
+}
+
+/// How a single-page app moved between its own URLs without loading a new document,
+/// as reported by the injected page helper
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaNavigation {
+    /// `history.pushState` — a new history entry
+    Push,
+    /// `history.replaceState` — the current entry changes URL
+    Replace,
+    /// `popstate` or `hashchange` — back/forward within the page, or a fragment link
+    Pop,
+}
+
+/// A tab's back/forward history, for the long-press menu on the navigation buttons
+#[derive(Debug, Clone, Serialize)]
+pub struct NavHistory {
+    pub entries: Vec<NavEntry>,
+    /// Position of the current page in `entries` (-1 before the first load)
+    pub index: i32,
+}
+
 /// Info about a single tab, sent to the frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TabInfo {
     pub label: String,
+    /// Window that owns this tab
+    pub window_id: String,
     pub url: String,
     pub title: String,
     pub is_loading: bool,
     pub favicon: Option<String>,
     pub can_go_back: bool,
     pub can_go_forward: bool,
+    /// Pinned tabs always sit before unpinned ones and resist closing
+    pub is_pinned: bool,
+    /// Group this tab belongs to — members of a group are always adjacent
+    pub group_id: Option<String>,
+    /// Hibernated: the webview was closed to save memory and is recreated on activation
+    pub is_discarded: bool,
+    /// The webview's content process died — the tab stays blank until recovered
+    pub is_crashed: bool,
+    /// The page let a watchdog ping go unanswered — busy, or blocked on a dialog or the
+    /// debugger. Cleared as soon as it answers again.
+    pub is_unresponsive: bool,
+    /// The last navigation failed — the tab shows the error page but keeps the failed URL
+    pub load_error: Option<LoadError>,
+    /// A media element in the page is playing sound (reported by the injected media observer)
+    pub is_audible: bool,
+    /// Every media element in the page is kept muted, across new elements and navigations
+    pub is_muted: bool,
+    /// Page zoom in percent — follows the site's saved level or `default_zoom`
+    pub zoom: u32,
+    /// Tab this one was opened from (a link with target=_blank, Ctrl+click…)
+    pub opener: Option<String>,
+    /// Container whose cookies and storage the tab uses — None for the default one
+    pub container_id: Option<String>,
+    /// Internal: when the tab was last shown or hidden, for idle discarding
+    #[serde(skip)]
+    pub last_active: Option<Instant>,
+    /// Internal: where the tab is in the watchdog's ping/pong cycle
+    #[serde(skip)]
+    pub ping: PingState,
     /// Internal: navigation history stack
     #[serde(skip)]
-    pub nav_stack: Vec<String>,
+    pub nav_stack: Vec<NavEntry>,
     /// Internal: current position in nav_stack
     #[serde(skip)]
     pub nav_pos: i32,
     /// Internal: true when a back/forward navigation is in progress
     #[serde(skip)]
     pub nav_traversing: bool,
+    /// Internal: true when nav_stack was restored rather than built by this webview
+    /// (or a failed load left it out of step), so back/forward must load entries
+    /// directly instead of going through the webview's own history
+    #[serde(skip)]
+    pub nav_restored: bool,
+    /// Internal: true when the page being loaded was asked for in the address bar,
+    /// so its history visit counts as typed
+    #[serde(skip)]
+    pub nav_typed: bool,
 }
 
-/// Manages the list of open tabs and which one is active
+impl TabInfo {
+    /// Seed navigation state saved from an earlier webview (session restore, reopen).
+    /// Marks the stack as restored so back/forward load entries directly.
+    pub fn restore_nav(&mut self, nav_stack: Vec<NavEntry>, nav_pos: i32) {
+        if !nav_stack.is_empty() {
+            self.nav_pos = nav_pos.clamp(0, nav_stack.len() as i32 - 1);
+            self.nav_stack = nav_stack;
+            self.nav_restored = true;
+        }
+        self.sync_nav_flags();
+    }
+
+    /// Recompute can_go_back/can_go_forward from the nav stack
+    pub fn sync_nav_flags(&mut self) {
+        self.can_go_back = self.nav_pos > 0;
+        self.can_go_forward = self.nav_pos < (self.nav_stack.len() as i32 - 1);
+    }
+
+    /// Record a navigation to `url` after the current entry, dropping any forward history
+    pub fn push_nav(&mut self, url: &str) {
+        let new_pos = self.nav_pos + 1;
+        self.nav_stack.truncate(new_pos as usize);
+        self.nav_stack.push(NavEntry::new(url));
+        self.nav_pos = new_pos;
+        self.sync_nav_flags();
+    }
+
+    /// Record a finished page load. A load ending a back/forward traversal just clears
+    /// the flag (nav_pos was moved already); any other load of a URL other than the
+    /// current entry — link click, redirect, first load — is a new entry.
+    pub fn record_load(&mut self, url: &str) {
+        if self.nav_traversing {
+            self.nav_traversing = false;
+        } else if self.nav_entry().map(|e| e.url.as_str()) != Some(url) {
+            self.push_nav(url);
+        }
+        self.sync_nav_flags();
+    }
+
+    /// The nav stack entry the tab is at, if any
+    pub fn nav_entry(&self) -> Option<&NavEntry> {
+        usize::try_from(self.nav_pos)
+            .ok()
+            .and_then(|pos| self.nav_stack.get(pos))
+    }
+
+    fn nav_entry_mut(&mut self) -> Option<&mut NavEntry> {
+        let pos = usize::try_from(self.nav_pos).ok()?;
+        self.nav_stack.get_mut(pos)
+    }
+
+    /// The current nav entry, if it is still showing `url` — page reports (title, favicon,
+    /// scroll) can arrive after the tab has moved on to another entry
+    fn nav_entry_for(&mut self, url: &str) -> Option<&mut NavEntry> {
+        self.nav_entry_mut().filter(|e| e.url == url)
+    }
+
+    /// Whether the entry `offset` steps from the current one is `url`
+    fn nav_entry_at_offset_is(&self, offset: i32, url: &str) -> bool {
+        usize::try_from(self.nav_pos + offset)
+            .ok()
+            .and_then(|pos| self.nav_stack.get(pos))
+            .is_some_and(|e| e.url == url)
+    }
+
+    /// Record a same-document navigation to `url`. A pop ends a back/forward started by
+    /// the browser (nav_pos was moved already); otherwise it is matched against the
+    /// neighbouring entries, and anything else — a fragment link — is a new entry.
+    /// Pages report popstate and hashchange for the same move, so a pop to the
+    /// current entry is ignored.
+    pub fn record_spa_navigation(&mut self, url: &str, kind: SpaNavigation) {
+        self.url = url.to_string();
+        match kind {
+            SpaNavigation::Push => self.push_nav(url),
+            SpaNavigation::Replace => match self.nav_entry_mut() {
+                Some(entry) => entry.url = url.to_string(),
+                None => self.push_nav(url),
+            },
+            SpaNavigation::Pop => {
+                if self.nav_traversing {
+                    self.nav_traversing = false;
+                } else if self.nav_entry_at_offset_is(0, url) {
+                    // Already there
+                } else if self.nav_entry_at_offset_is(-1, url) {
+                    self.nav_pos -= 1;
+                } else if self.nav_entry_at_offset_is(1, url) {
+                    self.nav_pos += 1;
+                } else {
+                    self.push_nav(url);
+                }
+            }
+        }
+        self.sync_nav_flags();
+    }
+
+    /// Set the page title, on the tab and its current history entry
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+        let url = self.url.clone();
+        if let Some(entry) = self.nav_entry_for(&url) {
+            entry.title = title.to_string();
+        }
+    }
+
+    /// Set the page favicon, on the tab and its current history entry
+    pub fn set_favicon(&mut self, favicon: Option<String>) {
+        let url = self.url.clone();
+        if let Some(entry) = self.nav_entry_for(&url) {
+            entry.favicon = favicon.clone();
+        }
+        self.favicon = favicon;
+    }
+
+    /// Remember how far `url` was scrolled, if it is still the current entry
+    pub fn record_scroll(&mut self, url: &str, scroll_y: f64) {
+        if let Some(entry) = self.nav_entry_for(url) {
+            entry.scroll_y = scroll_y.max(0.0);
+        }
+    }
+
+    /// The whole back/forward history and where the tab is in it
+    pub fn nav_history(&self) -> NavHistory {
+        NavHistory {
+            entries: self.nav_stack.clone(),
+            index: self.nav_pos,
+        }
+    }
+
+    /// Position `delta` entries away in the nav stack, if there is one
+    pub fn nav_target(&self, delta: i32) -> Option<i32> {
+        let target = self.nav_pos + delta;
+        (delta != 0 && target >= 0 && target < self.nav_stack.len() as i32).then_some(target)
+    }
+}
+
+#[cfg(test)]
+impl TabInfo {
+    /// A settled, never-navigated tab for tests to build on with struct-update syntax
+    pub(crate) fn for_test(label: &str, url: &str, window_id: &str) -> Self {
+        TabInfo {
+            label: label.to_string(),
+            window_id: window_id.to_string(),
+            url: url.to_string(),
+            title: "Test".to_string(),
+            is_loading: false,
+            favicon: None,
+            can_go_back: false,
+            can_go_forward: false,
+            is_pinned: false,
+            group_id: None,
+            is_discarded: false,
+            is_crashed: false,
+            is_unresponsive: false,
+            load_error: None,
+            is_audible: false,
+            is_muted: false,
+            zoom: 100,
+            opener: None,
+            container_id: None,
+            last_active: None,
+            ping: PingState::Unarmed,
+            nav_stack: Vec::new(),
+            nav_pos: -1,
+            nav_traversing: false,
+            nav_restored: false,
+            nav_typed: false,
+        }
+    }
+}
+
+/// Run `f` on one window's tabs as if they were the whole tab strip, then put them
+/// back into the slots that window occupied. Tabs `f` adds go after the last slot.
+pub(super) fn with_window<R>(
+    tabs: &mut Vec<TabInfo>,
+    window_id: &str,
+    f: impl FnOnce(&mut Vec<TabInfo>) -> R,
+) -> R {
+    let slots: Vec<bool> = tabs.iter().map(|t| t.window_id == window_id).collect();
+    let (mut own, others): (Vec<TabInfo>, Vec<TabInfo>) =
+        std::mem::take(tabs).into_iter().partition(|t| t.window_id == window_id);
+
+    let result = f(&mut own);
+
+    let mut own = own.into_iter();
+    let mut others = others.into_iter();
+    for mine in slots {
+        let next = if mine { own.next() } else { others.next() };
+        tabs.extend(next);
+    }
+    tabs.extend(own);
+    result
+}
+
+/// Window a tab belongs to
+pub(super) fn window_of(tabs: &[TabInfo], label: &str) -> Result<String, String> {
+    tabs.iter()
+        .find(|t| t.label == label)
+        .map(|t| t.window_id.clone())
+        .ok_or_else(|| "Tab not found".to_string())
+}
+
+/// Manages the open tabs of every window, their groups, split views and each window's active tab.
+/// Tabs of all windows share one list; positions are always relative to a window.
+/// Lock order when several are needed: `tabs` before `active_tabs`, `groups` and `splits`.
 pub struct TabManager {
     pub tabs: Mutex<Vec<TabInfo>>,
-    pub active_tab: Mutex<Option<String>>,
+    /// Active tab label keyed by window ID
+    pub active_tabs: Mutex<HashMap<String, String>>,
+    pub groups: Mutex<Vec<TabGroup>>,
+    pub splits: Mutex<Vec<SplitView>>,
 }
 
 impl TabManager {
     pub fn new() -> Self {
         Self {
             tabs: Mutex::new(Vec::new()),
-            active_tab: Mutex::new(None),
+            active_tabs: Mutex::new(HashMap::new()),
+            groups: Mutex::new(Vec::new()),
+            splits: Mutex::new(Vec::new()),
         }
     }
 
-    pub fn add_tab(&self, info: TabInfo) {
+    /// Add a tab to its window's section. Pinned tabs join the end of the pinned block;
+    /// tabs with an opener go right after it and the children it opened earlier, joining
+    /// its group; anything else goes at the end.
+    pub fn add_tab(&self, mut info: TabInfo) {
         let mut tabs = self.tabs.lock().unwrap();
-        tabs.push(info);
+        let window_id = info.window_id.clone();
+        with_window(&mut tabs, &window_id, |tabs| {
+            let pinned = tabs.iter().take_while(|t| t.is_pinned).count();
+            let opener = info
+                .opener
+                .as_ref()
+                .and_then(|opener| tabs.iter().position(|t| &t.label == opener));
+
+            if info.is_pinned {
+                tabs.insert(pinned, info);
+            } else if let Some(pos) = opener {
+                if info.group_id.is_none() {
+                    info.group_id = tabs[pos].group_id.clone();
+                }
+                // Earlier children only count while they share the new tab's group
+                let mut at = pos + 1;
+                while at < tabs.len()
+                    && tabs[at].opener == info.opener
+                    && tabs[at].group_id == info.group_id
+                {
+                    at += 1;
+                }
+                let at = at.max(pinned);
+                let at = if info.group_id.is_some() { at } else { snap_out_of_groups(tabs, at) };
+                tabs.insert(at, info);
+            } else {
+                tabs.push(info);
+            }
+        });
     }
 
+    #[allow(clippy::manual_map)]
     pub fn remove_tab(&self, label: &str) -> Option<TabInfo> {
         let mut tabs = self.tabs.lock().unwrap();
         if let Some(pos) = tabs.iter().position(|t| t.label == label) {
@@ -60,11 +441,31 @@ impl TabManager {
         }
     }
 
+    /// Forget a window: drop its tabs and active tab, returning the tabs it had
+    pub fn remove_window(&self, window_id: &str) -> Vec<TabInfo> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut active = self.active_tabs.lock().unwrap();
+        active.remove(window_id);
+
+        let (removed, kept) = std::mem::take(&mut *tabs)
+            .into_iter()
+            .partition(|t| t.window_id == window_id);
+        *tabs = kept;
+        removed
+    }
+
+    /// Every tab in every window
     pub fn get_all_tabs(&self) -> Vec<TabInfo> {
         let tabs = self.tabs.lock().unwrap();
         tabs.clone()
     }
 
+    /// A window's tabs in tab-strip order
+    pub fn get_window_tabs(&self, window_id: &str) -> Vec<TabInfo> {
+        let tabs = self.tabs.lock().unwrap();
+        tabs.iter().filter(|t| t.window_id == window_id).cloned().collect()
+    }
+
     pub fn get_tab(&self, label: &str) -> Option<TabInfo> {
         let tabs = self.tabs.lock().unwrap();
         tabs.iter().find(|t| t.label == label).cloned()
@@ -80,29 +481,217 @@ impl TabManager {
         }
     }
 
-    pub fn get_active_tab(&self) -> Option<String> {
-        let active = self.active_tab.lock().unwrap();
-        active.clone()
+    pub fn get_active_tab(&self, window_id: &str) -> Option<String> {
+        let active = self.active_tabs.lock().unwrap();
+        active.get(window_id).cloned()
+    }
+
+    /// Whether the tab is the active one in its window
+    pub fn is_active(&self, label: &str) -> bool {
+        let active = self.active_tabs.lock().unwrap();
+        active.values().any(|l| l == label)
+    }
+
+    /// Set a window's active tab. Both the outgoing and incoming tab are stamped as
+    /// just used, so idle time counts from the moment a tab went into the background.
+    pub fn set_active_tab(&self, window_id: &str, label: Option<String>) {
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut active = self.active_tabs.lock().unwrap();
+        let previous = active.get(window_id).cloned();
+        let now = Instant::now();
+        for tab in tabs.iter_mut() {
+            if Some(&tab.label) == previous.as_ref() || Some(&tab.label) == label.as_ref() {
+                tab.last_active = Some(now);
+            }
+        }
+        match label {
+            Some(label) => active.insert(window_id.to_string(), label),
+            None => active.remove(window_id),
+        };
+    }
+
+    /// Background tabs whose webviews should be closed to save memory:
+    /// those idle for at least `idle_after`, plus the least recently used
+    /// ones while more than `max_live` webviews are alive across all windows.
+    /// Visible tabs (active tabs and the panes of their splits), tabs playing audio,
+    /// crashed tabs (waiting for `tab_recover`) and tabs that are already discarded
+    /// are never returned — the next least recently used tab goes instead.
+    pub fn discard_candidates(
+        &self,
+        now: Instant,
+        idle_after: Option<Duration>,
+        max_live: Option<usize>,
+    ) -> Vec<String> {
+        let tabs = self.tabs.lock().unwrap();
+        let active = self.active_tabs.lock().unwrap();
+        let splits = self.splits.lock().unwrap();
+
+        let visible: Vec<&String> = active
+            .values()
+            .flat_map(|label| match splits.iter().find(|s| s.labels.contains(label)) {
+                Some(split) => split.labels.iter().collect(),
+                None => vec![label],
+            })
+            .collect();
+        let live = tabs.iter().filter(|t| !t.is_discarded).count();
+        let mut background: Vec<&TabInfo> = tabs
+            .iter()
+            .filter(|t| !t.is_discarded && !t.is_crashed && !t.is_audible && !visible.contains(&&t.label))
+            .collect();
+        // Least recently used first; tabs never shown count as oldest
+        background.sort_by_key(|t| t.last_active);
+
+        let excess = max_live.map(|max| live.saturating_sub(max.max(1))).unwrap_or(0);
+        background
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| {
+                let idle = match (idle_after, t.last_active) {
+                    (Some(after), Some(at)) => now.saturating_duration_since(at) >= after,
+                    _ => false,
+                };
+                *i < excess || idle
+            })
+            .map(|(_, t)| t.label.clone())
+            .collect()
     }
 
-    pub fn set_active_tab(&self, label: Option<String>) {
-        let mut active = self.active_tab.lock().unwrap();
-        *active = label;
+    /// Number of tabs in a window
+    pub fn tab_count(&self, window_id: &str) -> usize {
+        let tabs = self.tabs.lock().unwrap();
+        tabs.iter().filter(|t| t.window_id == window_id).count()
     }
 
-    pub fn tab_count(&self) -> usize {
+    /// Position of a tab in its window's tab strip
+    pub fn tab_index(&self, label: &str) -> Option<usize> {
         let tabs = self.tabs.lock().unwrap();
-        tabs.len()
+        let window_id = window_of(&tabs, label).ok()?;
+        tabs.iter()
+            .filter(|t| t.window_id == window_id)
+            .position(|t| t.label == label)
+    }
+
+    /// Move a tab to a new position, clamped so pinned tabs stay ahead of unpinned ones.
+    /// Dropping a tab between two members of a group joins that group; dragging a
+    /// tab away from its group's other members leaves it.
+    /// Returns the old and the actual new index.
+    pub fn move_tab(&self, label: &str, new_index: usize) -> Result<(usize, usize), String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let window_id = window_of(&tabs, label)?;
+        with_window(&mut tabs, &window_id, |tabs| {
+            let old_index = tabs
+                .iter()
+                .position(|t| t.label == label)
+                .ok_or("Tab not found")?;
+
+            if new_index >= tabs.len() {
+                return Err("Invalid index".to_string());
+            }
+
+            let pinned = tabs.iter().take_while(|t| t.is_pinned).count();
+            let tab = tabs.remove(old_index);
+            let new_index = if tab.is_pinned {
+                new_index.min(pinned - 1)
+            } else {
+                new_index.max(pinned)
+            };
+            tabs.insert(new_index, tab);
+
+            let left = new_index
+                .checked_sub(1)
+                .and_then(|i| tabs[i].group_id.clone());
+            let right = tabs.get(new_index + 1).and_then(|t| t.group_id.clone());
+            if left.is_some() && left == right {
+                tabs[new_index].group_id = left;
+            } else if let Some(group_id) = tabs[new_index].group_id.clone() {
+                let has_others = tabs
+                    .iter()
+                    .any(|t| t.label != label && t.group_id.as_ref() == Some(&group_id));
+                let beside_group =
+                    left.as_ref() == Some(&group_id) || right.as_ref() == Some(&group_id);
+                if has_others && !beside_group {
+                    tabs[new_index].group_id = None;
+                }
+            }
+
+            Ok((old_index, new_index))
+        })
+    }
+
+    /// Pin or unpin a tab, moving it to the boundary between pinned and unpinned tabs.
+    /// Pinning takes the tab out of its group. Returns the old and new index.
+    pub fn set_pinned(&self, label: &str, pinned: bool) -> Result<(usize, usize), String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let window_id = window_of(&tabs, label)?;
+        with_window(&mut tabs, &window_id, |tabs| {
+            let old_index = tabs
+                .iter()
+                .position(|t| t.label == label)
+                .ok_or("Tab not found")?;
+
+            let mut tab = tabs.remove(old_index);
+            tab.is_pinned = pinned;
+            if pinned {
+                tab.group_id = None;
+            }
+            let new_index = tabs.iter().take_while(|t| t.is_pinned).count();
+            tabs.insert(new_index, tab);
+            Ok((old_index, new_index))
+        })
+    }
+
+    /// Hand a tab over to another window, inserting it at `index` in that window's
+    /// strip (clamped so pinned tabs stay first and no group is split).
+    /// The tab leaves its group — groups never span windows.
+    /// Returns the old index in its previous window and the new index.
+    pub fn move_to_window(
+        &self,
+        label: &str,
+        window_id: &str,
+        index: usize,
+    ) -> Result<(usize, usize), String> {
+        let mut tabs = self.tabs.lock().unwrap();
+        let old_window = window_of(&tabs, label)?;
+        if old_window == window_id {
+            return Err("Tab is already in that window".to_string());
+        }
+
+        let old_index = tabs
+            .iter()
+            .filter(|t| t.window_id == old_window)
+            .position(|t| t.label == label)
+            .ok_or("Tab not found")?;
+        let pos = tabs.iter().position(|t| t.label == label).ok_or("Tab not found")?;
+        let mut tab = tabs.remove(pos);
+        tab.window_id = window_id.to_string();
+        tab.group_id = None;
+
+        with_window(&mut tabs, window_id, |tabs| {
+            let pinned = tabs.iter().take_while(|t| t.is_pinned).count();
+            let new_index = if tab.is_pinned {
+                index.min(pinned)
+            } else {
+                snap_out_of_groups(tabs, index.clamp(pinned, tabs.len()))
+            };
+            tabs.insert(new_index, tab);
+            Ok((old_index, new_index))
+        })
     }
 
-    pub fn get_tab_labels(&self) -> Vec<String> {
+    /// Labels of a window's tabs in tab-strip order
+    pub fn get_tab_labels(&self, window_id: &str) -> Vec<String> {
         let tabs = self.tabs.lock().unwrap();
-        tabs.iter().map(|t| t.label.clone()).collect()
+        tabs.iter()
+            .filter(|t| t.window_id == window_id)
+            .map(|t| t.label.clone())
+            .collect()
     }
 
-    /// Get the label of the tab adjacent to the given one (for switching after close)
+    /// Get the label of the tab adjacent to the given one in its window (for switching after close)
     pub fn get_adjacent_tab(&self, label: &str) -> Option<String> {
         let tabs = self.tabs.lock().unwrap();
+        let window_id = window_of(&tabs, label).ok()?;
+        let tabs: Vec<&TabInfo> = tabs.iter().filter(|t| t.window_id == window_id).collect();
         if let Some(pos) = tabs.iter().position(|t| t.label == label) {
             // Prefer the tab to the right, fall back to the left
             if pos + 1 < tabs.len() {
@@ -124,18 +713,7 @@ mod tests {
 
     /// Helper: create a TabInfo with sensible defaults
     fn make_tab(label: &str, url: &str) -> TabInfo {
-        TabInfo {
-            label: label.to_string(),
-            url: url.to_string(),
-            title: "Test".to_string(),
-            is_loading: false,
-            favicon: None,
-            can_go_back: false,
-            can_go_forward: false,
-            nav_stack: Vec::new(),
-            nav_pos: -1,
-            nav_traversing: false,
-        }
+        TabInfo::for_test(label, url, "main")
     }
 
     // ── TabManager basics ──────────────────────────────────
@@ -143,8 +721,8 @@ mod tests {
     #[test]
     fn new_manager_is_empty() {
         let tm = TabManager::new();
-        assert_eq!(tm.tab_count(), 0);
-        assert!(tm.get_active_tab().is_none());
+        assert_eq!(tm.tab_count("main"), 0);
+        assert!(tm.get_active_tab("main").is_none());
         assert!(tm.get_all_tabs().is_empty());
     }
 
@@ -152,9 +730,9 @@ mod tests {
     fn add_and_count() {
         let tm = TabManager::new();
         tm.add_tab(make_tab("t1", "https://a.com"));
-        assert_eq!(tm.tab_count(), 1);
+        assert_eq!(tm.tab_count("main"), 1);
         tm.add_tab(make_tab("t2", "https://b.com"));
-        assert_eq!(tm.tab_count(), 2);
+        assert_eq!(tm.tab_count("main"), 2);
     }
 
     #[test]
@@ -174,7 +752,7 @@ mod tests {
 
         let removed = tm.remove_tab("t1").unwrap();
         assert_eq!(removed.label, "t1");
-        assert_eq!(tm.tab_count(), 1);
+        assert_eq!(tm.tab_count("main"), 1);
         assert!(tm.get_tab("t1").is_none());
     }
 
@@ -200,7 +778,7 @@ mod tests {
         let tm = TabManager::new();
         tm.add_tab(make_tab("t1", "https://a.com"));
         tm.add_tab(make_tab("t2", "https://b.com"));
-        assert_eq!(tm.get_tab_labels(), vec!["t1", "t2"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2"]);
     }
 
     // ── Active tab ─────────────────────────────────────────
@@ -208,16 +786,16 @@ mod tests {
     #[test]
     fn set_and_get_active() {
         let tm = TabManager::new();
-        tm.set_active_tab(Some("t1".to_string()));
-        assert_eq!(tm.get_active_tab(), Some("t1".to_string()));
+        tm.set_active_tab("main", Some("t1".to_string()));
+        assert_eq!(tm.get_active_tab("main"), Some("t1".to_string()));
     }
 
     #[test]
     fn clear_active() {
         let tm = TabManager::new();
-        tm.set_active_tab(Some("t1".to_string()));
-        tm.set_active_tab(None);
-        assert!(tm.get_active_tab().is_none());
+        tm.set_active_tab("main", Some("t1".to_string()));
+        tm.set_active_tab("main", None);
+        assert!(tm.get_active_tab("main").is_none());
     }
 
     // ── update_tab ─────────────────────────────────────────
@@ -282,8 +860,178 @@ mod tests {
         assert!(tm.get_adjacent_tab("nope").is_none());
     }
 
+    // ── tab_index / move_tab ───────────────────────────────
+
+    #[test]
+    fn tab_index_hit_and_miss() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        assert_eq!(tm.tab_index("t2"), Some(1));
+        assert!(tm.tab_index("missing").is_none());
+    }
+
+    #[test]
+    fn move_tab_reorders_and_returns_old_index() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_tab("t3", "https://c.com"));
+
+        assert_eq!(tm.move_tab("t3", 0), Ok((2, 0)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t3", "t1", "t2"]);
+    }
+
+    #[test]
+    fn move_tab_rejects_bad_input() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        assert!(tm.move_tab("t1", 1).is_err());
+        assert!(tm.move_tab("missing", 0).is_err());
+    }
+
+    // ── move_tab and groups ────────────────────────────────
+
+    /// Helper: a tab in group "g"
+    fn make_grouped(label: &str) -> TabInfo {
+        TabInfo {
+            group_id: Some("g".to_string()),
+            ..make_tab(label, "https://example.com")
+        }
+    }
+
+    #[test]
+    fn move_between_group_members_joins_group() {
+        let tm = TabManager::new();
+        tm.add_tab(make_grouped("a1"));
+        tm.add_tab(make_grouped("a2"));
+        tm.add_tab(make_tab("t1", "https://a.com"));
+
+        tm.move_tab("t1", 1).unwrap();
+        assert_eq!(tm.get_tab_labels("main"), vec!["a1", "t1", "a2"]);
+        assert_eq!(tm.get_tab("t1").unwrap().group_id.as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn move_away_from_group_leaves_it() {
+        let tm = TabManager::new();
+        tm.add_tab(make_grouped("a1"));
+        tm.add_tab(make_grouped("a2"));
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+
+        tm.move_tab("a1", 3).unwrap();
+        assert!(tm.get_tab("a1").unwrap().group_id.is_none());
+        assert_eq!(tm.get_tab("a2").unwrap().group_id.as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn move_within_group_keeps_membership() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_grouped("a1"));
+        tm.add_tab(make_grouped("a2"));
+
+        tm.move_tab("a1", 2).unwrap();
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "a2", "a1"]);
+        assert_eq!(tm.get_tab("a1").unwrap().group_id.as_deref(), Some("g"));
+    }
+
+    // ── Pinned tabs ────────────────────────────────────────
+
+    /// Helper: a pinned tab
+    fn make_pinned(label: &str, url: &str) -> TabInfo {
+        TabInfo {
+            is_pinned: true,
+            ..make_tab(label, url)
+        }
+    }
+
+    #[test]
+    fn add_pinned_tab_joins_pinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_tab("t1", "https://b.com"));
+        tm.add_tab(make_pinned("p2", "https://c.com"));
+
+        assert_eq!(tm.get_tab_labels("main"), vec!["p1", "p2", "t1"]);
+    }
+
+    #[test]
+    fn move_unpinned_tab_cannot_enter_pinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_tab("t1", "https://b.com"));
+        tm.add_tab(make_tab("t2", "https://c.com"));
+
+        assert_eq!(tm.move_tab("t2", 0), Ok((2, 1)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["p1", "t2", "t1"]);
+    }
+
+    #[test]
+    fn move_pinned_tab_cannot_leave_pinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_pinned("p2", "https://b.com"));
+        tm.add_tab(make_tab("t1", "https://c.com"));
+
+        assert_eq!(tm.move_tab("p1", 2), Ok((0, 1)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["p2", "p1", "t1"]);
+    }
+
+    #[test]
+    fn pin_moves_tab_to_end_of_pinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_tab("t1", "https://b.com"));
+        tm.add_tab(make_tab("t2", "https://c.com"));
+
+        assert_eq!(tm.set_pinned("t2", true), Ok((2, 1)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["p1", "t2", "t1"]);
+        assert!(tm.get_tab("t2").unwrap().is_pinned);
+    }
+
+    #[test]
+    fn unpin_moves_tab_to_start_of_unpinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_pinned("p2", "https://b.com"));
+        tm.add_tab(make_tab("t1", "https://c.com"));
+
+        assert_eq!(tm.set_pinned("p1", false), Ok((0, 1)));
+        assert_eq!(tm.get_tab_labels("main"), vec!["p2", "p1", "t1"]);
+        assert!(!tm.get_tab("p1").unwrap().is_pinned);
+    }
+
+    #[test]
+    fn pin_leaves_group() {
+        let tm = TabManager::new();
+        tm.add_tab(TabInfo {
+            group_id: Some("g".to_string()),
+            ..make_tab("t1", "https://a.com")
+        });
+        tm.set_pinned("t1", true).unwrap();
+        assert!(tm.get_tab("t1").unwrap().group_id.is_none());
+    }
+
+    #[test]
+    fn set_pinned_missing_tab_errors() {
+        let tm = TabManager::new();
+        assert!(tm.set_pinned("missing", true).is_err());
+    }
+
     // ── Navigation state logic ─────────────────────────────
 
+    /// Helper: nav entries for URLs
+    fn entries(urls: &[&str]) -> Vec<NavEntry> {
+        urls.iter().map(|url| NavEntry::new(url)).collect()
+    }
+
+    /// Helper: the URLs in a tab's nav stack
+    fn stack_urls(tab: &TabInfo) -> Vec<&str> {
+        tab.nav_stack.iter().map(|e| e.url.as_str()).collect()
+    }
+
     #[test]
     fn initial_nav_state() {
         let tab = make_tab("t1", "https://google.com");
@@ -297,78 +1045,531 @@ mod tests {
     #[test]
     fn nav_push_builds_stack() {
         let mut tab = make_tab("t1", "https://a.com");
+        tab.push_nav("https://a.com");
+        tab.push_nav("https://b.com");
 
-        // Simulate first page load
-        tab.nav_stack.push("https://a.com".to_string());
-        tab.nav_pos = 0;
-
-        // Navigate to b.com
-        let new_pos = tab.nav_pos + 1;
-        tab.nav_stack.truncate(new_pos as usize);
-        tab.nav_stack.push("https://b.com".to_string());
-        tab.nav_pos = new_pos;
-
-        assert_eq!(tab.nav_stack, vec!["https://a.com", "https://b.com"]);
+        assert_eq!(stack_urls(&tab), vec!["https://a.com", "https://b.com"]);
         assert_eq!(tab.nav_pos, 1);
+        assert!(tab.can_go_back);
+        assert!(!tab.can_go_forward);
     }
 
     #[test]
     fn nav_push_truncates_forward_history() {
         let mut tab = make_tab("t1", "https://a.com");
-        tab.nav_stack = vec![
-            "https://a.com".to_string(),
-            "https://b.com".to_string(),
-            "https://c.com".to_string(),
-        ];
+        tab.nav_stack = entries(&["https://a.com", "https://b.com", "https://c.com"]);
         tab.nav_pos = 0; // Went back to a.com
 
         // Navigate to d.com — should drop b.com and c.com
-        let new_pos = tab.nav_pos + 1;
-        tab.nav_stack.truncate(new_pos as usize);
-        tab.nav_stack.push("https://d.com".to_string());
-        tab.nav_pos = new_pos;
+        tab.push_nav("https://d.com");
 
-        assert_eq!(tab.nav_stack, vec!["https://a.com", "https://d.com"]);
+        assert_eq!(stack_urls(&tab), vec!["https://a.com", "https://d.com"]);
         assert_eq!(tab.nav_pos, 1);
     }
 
     #[test]
     fn nav_back_and_forward_flags() {
-        let tab = TabInfo {
-            nav_stack: vec![
-                "https://a.com".to_string(),
-                "https://b.com".to_string(),
-                "https://c.com".to_string(),
-            ],
+        let mut tab = TabInfo {
+            nav_stack: entries(&["https://a.com", "https://b.com", "https://c.com"]),
             nav_pos: 1, // At b.com (middle)
             ..make_tab("t1", "https://b.com")
         };
+        tab.sync_nav_flags();
 
-        assert!(tab.nav_pos > 0, "should be able to go back");
-        assert!(
-            tab.nav_pos < (tab.nav_stack.len() as i32 - 1),
-            "should be able to go forward"
-        );
+        assert!(tab.can_go_back, "should be able to go back");
+        assert!(tab.can_go_forward, "should be able to go forward");
+        assert_eq!(tab.nav_target(-1), Some(0));
+        assert_eq!(tab.nav_target(1), Some(2));
     }
 
     #[test]
     fn nav_at_start_cannot_go_back() {
-        let tab = TabInfo {
-            nav_stack: vec!["https://a.com".to_string()],
+        let mut tab = TabInfo {
+            nav_stack: entries(&["https://a.com"]),
             nav_pos: 0,
             ..make_tab("t1", "https://a.com")
         };
-        assert!(!(tab.nav_pos > 0));
+        tab.sync_nav_flags();
+        assert!(!tab.can_go_back);
+        assert_eq!(tab.nav_target(-1), None);
     }
 
     #[test]
     fn nav_at_end_cannot_go_forward() {
-        let tab = TabInfo {
-            nav_stack: vec!["https://a.com".to_string(), "https://b.com".to_string()],
+        let mut tab = TabInfo {
+            nav_stack: entries(&["https://a.com", "https://b.com"]),
             nav_pos: 1,
             ..make_tab("t1", "https://b.com")
         };
-        assert!(!(tab.nav_pos < (tab.nav_stack.len() as i32 - 1)));
+        tab.sync_nav_flags();
+        assert!(!tab.can_go_forward);
+        assert_eq!(tab.nav_target(1), None);
+        assert_eq!(tab.nav_target(0), None);
+    }
+
+    #[test]
+    fn record_load_pushes_new_pages_only() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.record_load("https://a.com");
+        tab.record_load("https://a.com"); // Reload
+        tab.record_load("https://b.com"); // Link click
+        assert_eq!(stack_urls(&tab), vec!["https://a.com", "https://b.com"]);
+        assert_eq!(tab.nav_entry().map(|e| e.url.as_str()), Some("https://b.com"));
+    }
+
+    #[test]
+    fn record_load_after_traversal_keeps_stack() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.record_load("https://a.com");
+        tab.record_load("https://b.com");
+
+        // Going back moves nav_pos first, then the page load lands
+        tab.nav_traversing = true;
+        tab.nav_pos = 0;
+        tab.record_load("https://a.com");
+
+        assert!(!tab.nav_traversing);
+        assert_eq!(tab.nav_stack.len(), 2);
+        assert!(tab.can_go_forward);
+    }
+
+    #[test]
+    fn restore_nav_seeds_stack_and_flags() {
+        let mut tab = make_tab("t1", "https://b.com");
+        tab.restore_nav(entries(&["https://a.com", "https://b.com", "https://c.com"]), 1);
+        assert_eq!(tab.nav_pos, 1);
+        assert_eq!(tab.nav_stack.len(), 3);
+        assert!(tab.nav_restored);
+        assert!(tab.can_go_back);
+        assert!(tab.can_go_forward);
+    }
+
+    #[test]
+    fn restore_nav_clamps_position() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.restore_nav(entries(&["https://a.com"]), 7);
+        assert_eq!(tab.nav_pos, 0);
+        assert!(!tab.can_go_back);
+        assert!(!tab.can_go_forward);
+    }
+
+    #[test]
+    fn restore_nav_ignores_empty_stack() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.restore_nav(Vec::new(), 3);
+        assert!(tab.nav_stack.is_empty());
+        assert_eq!(tab.nav_pos, -1);
+        assert!(!tab.nav_restored);
+    }
+
+    #[test]
+    fn page_reports_update_the_current_entry() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.record_load("https://a.com");
+        tab.set_title("A");
+        tab.set_favicon(Some("https://a.com/favicon.ico".to_string()));
+        tab.record_scroll("https://a.com", 420.0);
+
+        let entry = tab.nav_entry().unwrap();
+        assert_eq!(entry.title, "A");
+        assert_eq!(entry.favicon.as_deref(), Some("https://a.com/favicon.ico"));
+        assert_eq!(entry.scroll_y, 420.0);
+        assert!(entry.timestamp > 0);
+    }
+
+    #[test]
+    fn late_page_reports_leave_other_entries_alone() {
+        let mut tab = make_tab("t1", "https://a.com");
+        tab.record_load("https://a.com");
+        tab.push_nav("https://b.com");
+
+        // a.com's final scroll report lands after the navigation started
+        tab.record_scroll("https://a.com", 300.0);
+        tab.set_title("Page A"); // tab.url still a.com, entry is b.com
+        assert_eq!(tab.nav_stack[0].scroll_y, 0.0);
+        assert_eq!(tab.nav_stack[1].scroll_y, 0.0);
+        assert_eq!(tab.nav_stack[1].title, "");
+        assert_eq!(tab.title, "Page A");
+    }
+
+    #[test]
+    fn spa_push_and_replace() {
+        let mut tab = make_tab("t1", "https://app.com/");
+        tab.record_load("https://app.com/");
+        tab.record_spa_navigation("https://app.com/inbox", SpaNavigation::Push);
+        tab.record_spa_navigation("https://app.com/inbox?page=2", SpaNavigation::Replace);
+
+        assert_eq!(stack_urls(&tab), vec!["https://app.com/", "https://app.com/inbox?page=2"]);
+        assert_eq!(tab.url, "https://app.com/inbox?page=2");
+        assert_eq!(tab.nav_pos, 1);
+        assert!(tab.can_go_back);
+    }
+
+    #[test]
+    fn spa_pop_follows_page_back_and_forward() {
+        let mut tab = make_tab("t1", "https://app.com/");
+        tab.record_load("https://app.com/");
+        tab.record_spa_navigation("https://app.com/a", SpaNavigation::Push);
+        tab.record_spa_navigation("https://app.com/b", SpaNavigation::Push);
+
+        // history.back() from the page — popstate and hashchange both report it
+        tab.record_spa_navigation("https://app.com/a", SpaNavigation::Pop);
+        tab.record_spa_navigation("https://app.com/a", SpaNavigation::Pop);
+        assert_eq!(tab.nav_pos, 1);
+        assert!(tab.can_go_forward);
+
+        tab.record_spa_navigation("https://app.com/b", SpaNavigation::Pop);
+        assert_eq!(tab.nav_pos, 2);
+        assert_eq!(tab.nav_stack.len(), 3);
+    }
+
+    #[test]
+    fn spa_pop_ends_browser_traversal() {
+        let mut tab = make_tab("t1", "https://app.com/");
+        tab.record_load("https://app.com/");
+        tab.record_spa_navigation("https://app.com/a", SpaNavigation::Push);
+        tab.record_spa_navigation("https://app.com/b", SpaNavigation::Push);
+
+        // Jumping two entries back: nav_pos moves before the page reports
+        tab.nav_traversing = true;
+        tab.nav_pos = 0;
+        tab.record_spa_navigation("https://app.com/", SpaNavigation::Pop);
+        assert!(!tab.nav_traversing);
+        assert_eq!(tab.nav_pos, 0);
+        assert_eq!(tab.nav_stack.len(), 3);
+    }
+
+    #[test]
+    fn fragment_link_is_a_new_entry() {
+        let mut tab = make_tab("t1", "https://docs.com/");
+        tab.record_load("https://docs.com/");
+        tab.record_spa_navigation("https://docs.com/#install", SpaNavigation::Pop);
+        assert_eq!(stack_urls(&tab), vec!["https://docs.com/", "https://docs.com/#install"]);
+        assert_eq!(tab.url, "https://docs.com/#install");
+    }
+
+    #[test]
+    fn saved_nav_entries_accept_bare_urls() {
+        let stack: Vec<NavEntry> = serde_json::from_str(
+            r#"["https://a.com", { "url": "https://b.com", "title": "B", "timestamp": 5 }]"#,
+        )
+        .unwrap();
+        assert_eq!(stack[0].url, "https://a.com");
+        assert_eq!(stack[0].title, "");
+        assert_eq!(stack[1].title, "B");
+        assert_eq!(stack[1].timestamp, 5);
+        assert_eq!(stack[1].favicon, None);
+
+        let json = serde_json::to_string(&stack).unwrap();
+        let again: Vec<NavEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(again, stack);
+    }
+
+    // ── Opener placement ───────────────────────────────────
+
+    /// Helper: a tab opened from `opener`
+    fn make_child(label: &str, opener: &str) -> TabInfo {
+        TabInfo {
+            opener: Some(opener.to_string()),
+            ..make_tab(label, "https://example.com")
+        }
+    }
+
+    #[test]
+    fn child_goes_right_after_opener() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_child("c1", "t1"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "c1", "t2"]);
+    }
+
+    #[test]
+    fn later_children_follow_earlier_ones() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_child("c1", "t1"));
+        tm.add_tab(make_child("c2", "t1"));
+        tm.add_tab(make_child("c3", "t2"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "c1", "c2", "t2", "c3"]);
+    }
+
+    #[test]
+    fn missing_opener_appends() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_child("c1", "closed"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2", "c1"]);
+    }
+
+    #[test]
+    fn child_of_pinned_tab_lands_after_pinned_block() {
+        let tm = TabManager::new();
+        tm.add_tab(make_pinned("p1", "https://a.com"));
+        tm.add_tab(make_pinned("p2", "https://b.com"));
+        tm.add_tab(make_tab("t1", "https://c.com"));
+        tm.add_tab(make_child("c1", "p1"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["p1", "p2", "c1", "t1"]);
+        assert!(!tm.get_tab("c1").unwrap().is_pinned);
+    }
+
+    #[test]
+    fn child_joins_openers_group() {
+        let tm = TabManager::new();
+        tm.add_tab(make_grouped("a1"));
+        tm.add_tab(make_grouped("a2"));
+        tm.add_tab(make_child("c1", "a1"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["a1", "c1", "a2"]);
+        assert_eq!(tm.get_tab("c1").unwrap().group_id.as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn ungrouped_child_never_splits_a_group() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_child("c1", "t1"));
+        tm.add_tab(make_grouped("a1"));
+        tm.update_tab("c1", |t| t.group_id = Some("g".to_string()));
+        tm.add_tab(make_grouped("a2"));
+
+        // c1 is now a member of g, so c2 goes right after t1, outside the group
+        tm.add_tab(make_child("c2", "t1"));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "c2", "c1", "a1", "a2"]);
+        assert!(tm.get_tab("c2").unwrap().group_id.is_none());
+    }
+
+    // ── Multiple windows ───────────────────────────────────
+
+    /// Helper: a tab owned by another window
+    fn make_in(label: &str, window_id: &str) -> TabInfo {
+        TabInfo {
+            window_id: window_id.to_string(),
+            ..make_tab(label, "https://a.com")
+        }
+    }
+
+    /// Helper: main has t1, t2, t3 and w2 has u1, u2, interleaved in the shared list
+    fn two_windows() -> TabManager {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_in("u1", "w2"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_in("u2", "w2"));
+        tm.add_tab(make_tab("t3", "https://c.com"));
+        tm
+    }
+
+    #[test]
+    fn windows_have_their_own_strips() {
+        let tm = two_windows();
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2", "t3"]);
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u1", "u2"]);
+        assert_eq!(tm.tab_count("w2"), 2);
+        assert_eq!(tm.tab_index("u2"), Some(1));
+        assert_eq!(tm.tab_index("t3"), Some(2));
+    }
+
+    #[test]
+    fn move_tab_only_reorders_its_window() {
+        let tm = two_windows();
+        assert_eq!(tm.move_tab("t3", 0).unwrap(), (2, 0));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t3", "t1", "t2"]);
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u1", "u2"]);
+        // Indexes are bounded by the window, not the shared list
+        assert!(tm.move_tab("u1", 2).is_err());
+    }
+
+    #[test]
+    fn pinned_block_is_per_window() {
+        let tm = two_windows();
+        tm.set_pinned("u2", true).unwrap();
+        tm.add_tab(TabInfo { is_pinned: true, ..make_tab("p1", "https://p.com") });
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u2", "u1"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["p1", "t1", "t2", "t3"]);
+    }
+
+    #[test]
+    fn active_tab_is_per_window() {
+        let tm = two_windows();
+        tm.set_active_tab("main", Some("t2".to_string()));
+        tm.set_active_tab("w2", Some("u1".to_string()));
+        assert_eq!(tm.get_active_tab("main"), Some("t2".to_string()));
+        assert_eq!(tm.get_active_tab("w2"), Some("u1".to_string()));
+        assert!(tm.is_active("u1"));
+        assert!(!tm.is_active("t1"));
+    }
+
+    #[test]
+    fn adjacent_tab_stays_in_window() {
+        let tm = two_windows();
+        assert_eq!(tm.get_adjacent_tab("u1"), Some("u2".to_string()));
+        assert_eq!(tm.get_adjacent_tab("u2"), Some("u1".to_string()));
+        assert_eq!(tm.get_adjacent_tab("t3"), Some("t2".to_string()));
+    }
+
+    #[test]
+    fn remove_window_drops_its_tabs_and_active() {
+        let tm = two_windows();
+        tm.set_active_tab("w2", Some("u1".to_string()));
+
+        let removed: Vec<String> = tm.remove_window("w2").into_iter().map(|t| t.label).collect();
+        assert_eq!(removed, vec!["u1", "u2"]);
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t2", "t3"]);
+        assert!(tm.get_active_tab("w2").is_none());
+        assert_eq!(tm.get_all_tabs().len(), 3);
+    }
+
+    #[test]
+    fn move_to_window_changes_owner_and_position() {
+        let tm = two_windows();
+        assert_eq!(tm.move_to_window("t2", "w2", 1).unwrap(), (1, 1));
+        assert_eq!(tm.get_tab_labels("main"), vec!["t1", "t3"]);
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u1", "t2", "u2"]);
+        assert_eq!(tm.get_tab("t2").unwrap().window_id, "w2");
+    }
+
+    #[test]
+    fn move_to_window_clamps_index() {
+        let tm = two_windows();
+        tm.set_pinned("u1", true).unwrap();
+        // Unpinned tabs can't land in the pinned block, and indexes past the end append
+        tm.move_to_window("t1", "w2", 0).unwrap();
+        tm.move_to_window("t2", "w2", 99).unwrap();
+        assert_eq!(tm.get_tab_labels("w2"), vec!["u1", "t1", "u2", "t2"]);
+
+        // Pinned tabs can't land after unpinned ones
+        tm.set_pinned("t3", true).unwrap();
+        assert_eq!(tm.move_to_window("t3", "w2", 3).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn move_to_window_leaves_group_and_works_for_new_window() {
+        let tm = two_windows();
+        tm.update_tab("t1", |t| t.group_id = Some("g1".to_string()));
+        tm.move_to_window("t1", "w3", 5).unwrap();
+        assert_eq!(tm.get_tab_labels("w3"), vec!["t1"]);
+        assert_eq!(tm.get_tab("t1").unwrap().group_id, None);
+    }
+
+    #[test]
+    fn move_to_same_window_errors() {
+        let tm = two_windows();
+        assert!(tm.move_to_window("t1", "main", 0).is_err());
+        assert!(tm.move_to_window("missing", "w2", 0).is_err());
+    }
+
+    #[test]
+    fn discard_keeps_every_windows_active_tab() {
+        let tm = two_windows();
+        tm.set_active_tab("main", Some("t1".to_string()));
+        tm.set_active_tab("w2", Some("u1".to_string()));
+        let idle = tm.discard_candidates(Instant::now(), None, Some(1));
+        assert_eq!(idle.len(), 3);
+        assert!(!idle.contains(&"t1".to_string()));
+        assert!(!idle.contains(&"u1".to_string()));
+    }
+
+    // ── Discarding ─────────────────────────────────────────
+
+    /// Helper: three tabs used in order t1, t2, t3 (t3 active), `gap` apart
+    fn used_in_order(gap: Duration) -> (TabManager, Instant) {
+        let tm = TabManager::new();
+        let start = Instant::now();
+        for (i, label) in ["t1", "t2", "t3"].iter().enumerate() {
+            let mut tab = make_tab(label, "https://a.com");
+            tab.last_active = Some(start + gap * i as u32);
+            tm.add_tab(tab);
+        }
+        tm.active_tabs.lock().unwrap().insert("main".to_string(), "t3".to_string());
+        (tm, start + gap * 2)
+    }
+
+    #[test]
+    fn set_active_stamps_outgoing_and_incoming_tabs() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1", "https://a.com"));
+        tm.add_tab(make_tab("t2", "https://b.com"));
+        tm.add_tab(make_tab("t3", "https://c.com"));
+        tm.set_active_tab("main", Some("t1".to_string()));
+        tm.set_active_tab("main", Some("t2".to_string()));
+
+        assert!(tm.get_tab("t1").unwrap().last_active.is_some());
+        assert!(tm.get_tab("t2").unwrap().last_active.is_some());
+        assert!(tm.get_tab("t3").unwrap().last_active.is_none());
+    }
+
+    #[test]
+    fn discard_nothing_without_limits() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        assert!(tm.discard_candidates(now, None, None).is_empty());
+    }
+
+    #[test]
+    fn discard_idle_background_tabs() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        let idle = tm.discard_candidates(now, Some(Duration::from_secs(90)), None);
+        assert_eq!(idle, vec!["t1"]);
+    }
+
+    #[test]
+    fn discard_never_returns_active_tab() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        let idle = tm.discard_candidates(now + Duration::from_secs(3600), Some(Duration::ZERO), None);
+        assert_eq!(idle, vec!["t1", "t2"]);
+    }
+
+    #[test]
+    fn discard_never_returns_panes_of_active_split() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        tm.add_split(&["t3".to_string(), "t1".to_string()], None).unwrap();
+        let idle = tm.discard_candidates(now + Duration::from_secs(3600), Some(Duration::ZERO), None);
+        assert_eq!(idle, vec!["t2"]);
+        assert_eq!(tm.discard_candidates(now, None, Some(1)), vec!["t2"]);
+    }
+
+    #[test]
+    fn discard_never_returns_crashed_tabs() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        tm.update_tab("t1", |t| t.is_crashed = true);
+        let idle = tm.discard_candidates(now + Duration::from_secs(3600), Some(Duration::ZERO), None);
+        assert_eq!(idle, vec!["t2"]);
+        assert_eq!(tm.discard_candidates(now, None, Some(1)), vec!["t2"]);
+    }
+
+    #[test]
+    fn discard_passes_over_audible_tabs() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        tm.update_tab("t1", |t| t.is_audible = true);
+        // t1 is the oldest but playing — t2 goes to get under the cap
+        assert_eq!(tm.discard_candidates(now, None, Some(2)), vec!["t2"]);
+        let idle = tm.discard_candidates(now + Duration::from_secs(3600), Some(Duration::ZERO), None);
+        assert_eq!(idle, vec!["t2"]);
+    }
+
+    #[test]
+    fn discard_least_recently_used_over_cap() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        assert_eq!(tm.discard_candidates(now, None, Some(2)), vec!["t1"]);
+        assert_eq!(tm.discard_candidates(now, None, Some(1)), vec!["t1", "t2"]);
+        assert!(tm.discard_candidates(now, None, Some(3)).is_empty());
+    }
+
+    #[test]
+    fn discard_skips_already_discarded_tabs() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        tm.update_tab("t1", |t| t.is_discarded = true);
+        // t1 no longer counts as live, so two live tabs fit under a cap of 2
+        assert!(tm.discard_candidates(now, None, Some(2)).is_empty());
+        assert_eq!(tm.discard_candidates(now, Some(Duration::ZERO), None), vec!["t2"]);
+    }
+
+    #[test]
+    fn discard_treats_never_shown_tabs_as_oldest() {
+        let (tm, now) = used_in_order(Duration::from_secs(60));
+        tm.add_tab(make_tab("t4", "https://d.com"));
+        assert_eq!(tm.discard_candidates(now, None, Some(3)), vec!["t4"]);
     }
 
     // ── next_tab_label ─────────────────────────────────────
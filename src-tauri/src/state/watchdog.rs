@@ -0,0 +1,169 @@
+use std::time::{Duration, Instant};
+
+use super::tab_state::TabManager;
+
+/// Where a tab is in the watchdog's ping/pong cycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PingState {
+    /// The current document hasn't checked in yet — not pinged, so pages without
+    /// the injected helper (browser error pages) are never taken for hung
+    #[default]
+    Unarmed,
+    /// Answered its last ping (or just checked in)
+    Idle,
+    /// Pinged at this time, no answer yet
+    Waiting(Instant),
+}
+
+/// What the watchdog should do after a check of every live tab
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchdogTick {
+    /// Tabs to ping now
+    pub ping: Vec<String>,
+    /// Tabs whose ping just went unanswered for the whole timeout, now marked unresponsive
+    pub unresponsive: Vec<String>,
+}
+
+impl TabManager {
+    /// One watchdog round. Armed tabs get a ping unless one is still outstanding;
+    /// an outstanding ping older than `timeout` marks the tab unresponsive. That isn't
+    /// a crash — a page blocked on a dialog, printing or the debugger answers once it's
+    /// let go. Discarded and crashed tabs are left alone.
+    pub fn watchdog_tick(&self, now: Instant, timeout: Duration) -> WatchdogTick {
+        let mut tabs = self.tabs.lock().unwrap();
+        let mut tick = WatchdogTick::default();
+
+        for tab in tabs.iter_mut().filter(|t| !t.is_discarded && !t.is_crashed) {
+            match tab.ping {
+                PingState::Unarmed => {}
+                PingState::Idle => {
+                    tab.ping = PingState::Waiting(now);
+                    tick.ping.push(tab.label.clone());
+                }
+                PingState::Waiting(sent) => {
+                    if !tab.is_unresponsive && now.saturating_duration_since(sent) >= timeout {
+                        tab.is_unresponsive = true;
+                        tick.unresponsive.push(tab.label.clone());
+                    }
+                }
+            }
+        }
+        tick
+    }
+
+    /// A tab's page answered a ping, or checked in when its document started.
+    /// Returns true if the tab had been marked unresponsive.
+    pub fn watchdog_pong(&self, label: &str) -> bool {
+        let mut was_unresponsive = false;
+        self.update_tab(label, |tab| {
+            was_unresponsive = std::mem::take(&mut tab.is_unresponsive);
+            tab.ping = PingState::Idle;
+        });
+        was_unresponsive
+    }
+
+    /// A tab's content process died. Returns false if the tab is gone, discarded (its
+    /// webview was closed on purpose) or already crashed.
+    pub fn mark_crashed(&self, label: &str) -> bool {
+        let mut newly_crashed = false;
+        self.update_tab(label, |tab| {
+            if tab.is_crashed || tab.is_discarded {
+                return;
+            }
+            newly_crashed = true;
+            tab.is_crashed = true;
+            tab.is_unresponsive = false;
+            tab.is_loading = false;
+            tab.ping = PingState::Unarmed;
+        });
+        newly_crashed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tab_state::TabInfo;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn make_tab(label: &str) -> TabInfo {
+        TabInfo {
+            title: String::new(),
+            ping: PingState::Idle,
+            ..TabInfo::for_test(label, "https://example.com", "main")
+        }
+    }
+
+    #[test]
+    fn armed_live_tabs_are_pinged_once() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1"));
+        tm.add_tab(TabInfo { is_discarded: true, ..make_tab("t2") });
+        tm.add_tab(TabInfo { ping: PingState::Unarmed, ..make_tab("t3") });
+        let start = Instant::now();
+
+        let tick = tm.watchdog_tick(start, TIMEOUT);
+        assert_eq!(tick.ping, vec!["t1"]);
+        assert!(tick.unresponsive.is_empty());
+
+        // Still waiting on the answer — no second ping
+        let tick = tm.watchdog_tick(start + Duration::from_secs(10), TIMEOUT);
+        assert_eq!(tick, WatchdogTick::default());
+    }
+
+    #[test]
+    fn answered_tabs_are_pinged_again() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1"));
+        let start = Instant::now();
+
+        tm.watchdog_tick(start, TIMEOUT);
+        assert!(!tm.watchdog_pong("t1"));
+        let tick = tm.watchdog_tick(start + TIMEOUT * 2, TIMEOUT);
+        assert_eq!(tick.ping, vec!["t1"]);
+        assert!(!tm.get_tab("t1").unwrap().is_unresponsive);
+    }
+
+    #[test]
+    fn unanswered_ping_marks_tab_unresponsive_not_crashed() {
+        let tm = TabManager::new();
+        tm.add_tab(make_tab("t1"));
+        let start = Instant::now();
+
+        tm.watchdog_tick(start, TIMEOUT);
+        let tick = tm.watchdog_tick(start + TIMEOUT, TIMEOUT);
+        assert_eq!(tick.unresponsive, vec!["t1"]);
+        let tab = tm.get_tab("t1").unwrap();
+        assert!(tab.is_unresponsive);
+        assert!(!tab.is_crashed);
+
+        // Reported once, however long the page stays blocked
+        let tick = tm.watchdog_tick(start + TIMEOUT * 3, TIMEOUT);
+        assert_eq!(tick, WatchdogTick::default());
+
+        // The dialog was dismissed: the late answer brings the tab back
+        assert!(tm.watchdog_pong("t1"));
+        assert!(!tm.get_tab("t1").unwrap().is_unresponsive);
+        let tick = tm.watchdog_tick(start + TIMEOUT * 4, TIMEOUT);
+        assert_eq!(tick.ping, vec!["t1"]);
+    }
+
+    #[test]
+    fn crashed_tabs_wait_for_recovery() {
+        let tm = TabManager::new();
+        tm.add_tab(TabInfo { is_unresponsive: true, ..make_tab("t1") });
+
+        assert!(tm.mark_crashed("t1"));
+        assert!(!tm.mark_crashed("t1"));
+        assert!(!tm.mark_crashed("missing"));
+        tm.add_tab(TabInfo { is_discarded: true, ..make_tab("t2") });
+        assert!(!tm.mark_crashed("t2"));
+        let tab = tm.get_tab("t1").unwrap();
+        assert!(tab.is_crashed);
+        assert!(!tab.is_unresponsive);
+
+        let tick = tm.watchdog_tick(Instant::now(), TIMEOUT);
+        assert_eq!(tick, WatchdogTick::default());
+    }
+}
@@ -0,0 +1,103 @@
+use url::{Origin, Url};
+
+/// Zoom steps (percent) for zooming in and out, as in Chrome
+pub const ZOOM_LEVELS: &[u32] = &[
+    25, 33, 50, 67, 75, 80, 90, 100, 110, 125, 150, 175, 200, 250, 300, 400, 500,
+];
+
+pub const MIN_ZOOM: u32 = 25;
+pub const MAX_ZOOM: u32 = 500;
+
+/// Zoom used when `default_zoom` is missing or invalid
+pub const FALLBACK_ZOOM: u32 = 100;
+
+pub fn clamp_zoom(zoom: u32) -> u32 {
+    zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// The next step up from `zoom` (levels between steps go to the step above)
+pub fn zoom_in(zoom: u32) -> u32 {
+    ZOOM_LEVELS
+        .iter()
+        .copied()
+        .find(|&level| level > zoom)
+        .unwrap_or(MAX_ZOOM)
+}
+
+/// The next step down from `zoom`
+pub fn zoom_out(zoom: u32) -> u32 {
+    ZOOM_LEVELS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&level| level < zoom)
+        .unwrap_or(MIN_ZOOM)
+}
+
+/// Parse a zoom percentage setting such as "110"
+pub fn parse_zoom(value: &str) -> Option<u32> {
+    value.trim().parse::<u32>().ok().map(clamp_zoom)
+}
+
+/// The origin zoom levels are saved under: scheme, host and port for web pages,
+/// "aero://page" for internal pages. None for pages without a host (about:blank).
+pub fn origin_of(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    match parsed.origin() {
+        origin @ Origin::Tuple(..) => Some(origin.ascii_serialization()),
+        Origin::Opaque(_) => parsed
+            .host_str()
+            .map(|host| format!("{}://{}", parsed.scheme(), host)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_in_steps_up() {
+        assert_eq!(zoom_in(100), 110);
+        assert_eq!(zoom_in(25), 33);
+        assert_eq!(zoom_in(105), 110);
+        assert_eq!(zoom_in(500), 500);
+    }
+
+    #[test]
+    fn zoom_out_steps_down() {
+        assert_eq!(zoom_out(100), 90);
+        assert_eq!(zoom_out(105), 100);
+        assert_eq!(zoom_out(33), 25);
+        assert_eq!(zoom_out(25), 25);
+    }
+
+    #[test]
+    fn parse_zoom_clamps_and_rejects_garbage() {
+        assert_eq!(parse_zoom("125"), Some(125));
+        assert_eq!(parse_zoom(" 90 "), Some(90));
+        assert_eq!(parse_zoom("5"), Some(MIN_ZOOM));
+        assert_eq!(parse_zoom("9000"), Some(MAX_ZOOM));
+        assert_eq!(parse_zoom("big"), None);
+    }
+
+    #[test]
+    fn origin_keeps_scheme_host_and_port() {
+        assert_eq!(
+            origin_of("https://example.com/a/b?q=1").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            origin_of("http://localhost:5173/").as_deref(),
+            Some("http://localhost:5173")
+        );
+        // Subdomains are separate sites
+        assert_ne!(origin_of("https://a.example.com"), origin_of("https://b.example.com"));
+    }
+
+    #[test]
+    fn internal_pages_have_their_own_origin() {
+        assert_eq!(origin_of("aero://settings").as_deref(), Some("aero://settings"));
+        assert_eq!(origin_of("about:blank"), None);
+        assert_eq!(origin_of("not a url"), None);
+    }
+}
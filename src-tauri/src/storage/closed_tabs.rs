@@ -0,0 +1,262 @@
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use super::database::Database;
+use crate::state::tab_state::NavEntry;
+
+/// Most closed tabs remembered per window — older entries are dropped
+pub const MAX_CLOSED_TABS: i64 = 25;
+
+/// A recently closed tab, with enough state to reopen it in place
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClosedTab {
+	pub id: i64,
+	pub window_id: String,
+	pub url: String,
+	pub title: String,
+	pub favicon: Option<String>,
+	pub container_id: Option<String>,
+	pub is_pinned: bool,
+	#[serde(skip)]
+	pub nav_stack: Vec<NavEntry>,
+	#[serde(skip)]
+	pub nav_pos: i32,
+	/// Position in the tab strip when the tab was closed
+	pub index: usize,
+	pub closed_at: String,
+}
+
+const CLOSED_TAB_COLUMNS: &str =
+	"id, window_id, url, title, favicon, nav_stack, nav_pos, tab_index, closed_at, container_id, is_pinned";
+
+fn closed_tab_from_row(row: &Row) -> rusqlite::Result<ClosedTab> {
+	let nav_stack: String = row.get(5)?;
+	let index: i64 = row.get(7)?;
+	Ok(ClosedTab {
+		id: row.get(0)?,
+		window_id: row.get(1)?,
+		url: row.get(2)?,
+		title: row.get(3)?,
+		favicon: row.get(4)?,
+		container_id: row.get(9)?,
+		is_pinned: row.get(10)?,
+		nav_stack: serde_json::from_str(&nav_stack).unwrap_or_default(),
+		nav_pos: row.get(6)?,
+		index: index.max(0) as usize,
+		closed_at: row.get(8)?,
+	})
+}
+
+impl Database {
+	/// Push a closed tab onto its window's stack, trimming to MAX_CLOSED_TABS.
+	/// `id` and `closed_at` are assigned by the database.
+	pub fn closed_tab_push(&self, tab: &ClosedTab) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		let nav_stack = serde_json::to_string(&tab.nav_stack).map_err(|e| e.to_string())?;
+
+		conn.execute(
+			"INSERT INTO closed_tabs (window_id, url, title, favicon, nav_stack, nav_pos, tab_index, container_id, is_pinned)
+			 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+			params![
+				tab.window_id,
+				tab.url,
+				tab.title,
+				tab.favicon,
+				nav_stack,
+				tab.nav_pos,
+				tab.index as i64,
+				tab.container_id,
+				tab.is_pinned,
+			],
+		)
+		.map_err(|e| e.to_string())?;
+
+		conn.execute(
+			"DELETE FROM closed_tabs WHERE window_id = ?1 AND id NOT IN (
+				SELECT id FROM closed_tabs WHERE window_id = ?1 ORDER BY id DESC LIMIT ?2
+			)",
+			params![tab.window_id, MAX_CLOSED_TABS],
+		)
+		.map_err(|e| e.to_string())?;
+
+		Ok(())
+	}
+
+	/// Remove and return a closed tab — the most recent one, or a specific entry by ID
+	pub fn closed_tab_pop(
+		&self,
+		window_id: &str,
+		id: Option<i64>,
+	) -> Result<Option<ClosedTab>, String> {
+		let conn = self.conn.lock().unwrap();
+
+		let tab = match id {
+			Some(id) => conn
+				.query_row(
+					&format!(
+						"SELECT {} FROM closed_tabs WHERE window_id = ?1 AND id = ?2",
+						CLOSED_TAB_COLUMNS
+					),
+					params![window_id, id],
+					closed_tab_from_row,
+				)
+				.optional(),
+			None => conn
+				.query_row(
+					&format!(
+						"SELECT {} FROM closed_tabs WHERE window_id = ?1 ORDER BY id DESC LIMIT 1",
+						CLOSED_TAB_COLUMNS
+					),
+					params![window_id],
+					closed_tab_from_row,
+				)
+				.optional(),
+		}
+		.map_err(|e| e.to_string())?;
+
+		if let Some(tab) = &tab {
+			conn.execute("DELETE FROM closed_tabs WHERE id = ?1", params![tab.id])
+				.map_err(|e| e.to_string())?;
+		}
+
+		Ok(tab)
+	}
+
+	/// List a window's recently closed tabs, most recent first
+	pub fn closed_tab_list(&self, window_id: &str, limit: i64) -> Result<Vec<ClosedTab>, String> {
+		let conn = self.conn.lock().unwrap();
+		let mut stmt = conn
+			.prepare(&format!(
+				"SELECT {} FROM closed_tabs WHERE window_id = ?1 ORDER BY id DESC LIMIT ?2",
+				CLOSED_TAB_COLUMNS
+			))
+			.map_err(|e| e.to_string())?;
+
+		let tabs = stmt
+			.query_map(params![window_id, limit], closed_tab_from_row)
+			.map_err(|e| e.to_string())?
+			.filter_map(|r| r.ok())
+			.collect();
+
+		Ok(tabs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db() -> Database {
+		Database::open_in_memory().unwrap()
+	}
+
+	fn make_closed(window_id: &str, url: &str, index: usize) -> ClosedTab {
+		ClosedTab {
+			id: 0,
+			window_id: window_id.to_string(),
+			url: url.to_string(),
+			title: "Closed".to_string(),
+			favicon: None,
+			container_id: None,
+			is_pinned: false,
+			nav_stack: vec![NavEntry::new("https://start.com"), NavEntry::new(url)],
+			nav_pos: 1,
+			index,
+			closed_at: String::new(),
+		}
+	}
+
+	#[test]
+	fn pop_empty_returns_none() {
+		let db = test_db();
+		assert!(db.closed_tab_pop("main", None).unwrap().is_none());
+	}
+
+	#[test]
+	fn push_and_pop_round_trip() {
+		let db = test_db();
+		db.closed_tab_push(&ClosedTab {
+			container_id: Some("work".to_string()),
+			is_pinned: true,
+			..make_closed("main", "https://a.com", 3)
+		})
+		.unwrap();
+
+		let tab = db.closed_tab_pop("main", None).unwrap().unwrap();
+		assert_eq!(tab.url, "https://a.com");
+		assert_eq!(tab.index, 3);
+		assert_eq!(tab.container_id.as_deref(), Some("work"));
+		assert!(tab.is_pinned);
+		let urls: Vec<&str> = tab.nav_stack.iter().map(|e| e.url.as_str()).collect();
+		assert_eq!(urls, vec!["https://start.com", "https://a.com"]);
+		assert_eq!(tab.nav_pos, 1);
+
+		// Popping removes it
+		assert!(db.closed_tab_pop("main", None).unwrap().is_none());
+	}
+
+	#[test]
+	fn pop_is_last_in_first_out() {
+		let db = test_db();
+		db.closed_tab_push(&make_closed("main", "https://a.com", 0)).unwrap();
+		db.closed_tab_push(&make_closed("main", "https://b.com", 1)).unwrap();
+
+		assert_eq!(db.closed_tab_pop("main", None).unwrap().unwrap().url, "https://b.com");
+		assert_eq!(db.closed_tab_pop("main", None).unwrap().unwrap().url, "https://a.com");
+	}
+
+	#[test]
+	fn pop_by_id() {
+		let db = test_db();
+		db.closed_tab_push(&make_closed("main", "https://a.com", 0)).unwrap();
+		db.closed_tab_push(&make_closed("main", "https://b.com", 1)).unwrap();
+
+		let oldest = db.closed_tab_list("main", 10).unwrap().pop().unwrap();
+		let tab = db.closed_tab_pop("main", Some(oldest.id)).unwrap().unwrap();
+		assert_eq!(tab.url, "https://a.com");
+		assert_eq!(db.closed_tab_list("main", 10).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn stacks_are_per_window() {
+		let db = test_db();
+		db.closed_tab_push(&make_closed("main", "https://a.com", 0)).unwrap();
+		db.closed_tab_push(&make_closed("other", "https://b.com", 0)).unwrap();
+
+		assert_eq!(db.closed_tab_pop("main", None).unwrap().unwrap().url, "https://a.com");
+		assert!(db.closed_tab_pop("main", None).unwrap().is_none());
+		assert_eq!(db.closed_tab_list("other", 10).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn list_is_most_recent_first() {
+		let db = test_db();
+		db.closed_tab_push(&make_closed("main", "https://a.com", 0)).unwrap();
+		db.closed_tab_push(&make_closed("main", "https://b.com", 0)).unwrap();
+
+		let urls: Vec<String> = db
+			.closed_tab_list("main", 10)
+			.unwrap()
+			.into_iter()
+			.map(|t| t.url)
+			.collect();
+		assert_eq!(urls, vec!["https://b.com", "https://a.com"]);
+	}
+
+	#[test]
+	fn push_trims_to_bound() {
+		let db = test_db();
+		for i in 0..(MAX_CLOSED_TABS + 5) {
+			db.closed_tab_push(&make_closed("main", &format!("https://site{}.com", i), 0))
+				.unwrap();
+		}
+
+		let tabs = db.closed_tab_list("main", 100).unwrap();
+		assert_eq!(tabs.len() as i64, MAX_CLOSED_TABS);
+		// The oldest entries were dropped
+		assert_eq!(
+			tabs.last().unwrap().url,
+			format!("https://site{}.com", 5)
+		);
+	}
+}
@@ -0,0 +1,204 @@
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::database::Database;
+
+/// A named container. Tabs opened in it share cookies and site storage with each
+/// other, and with no tab outside it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+	pub id: String,
+	pub name: String,
+	/// One of the tab group colours
+	pub color: String,
+	pub position: i64,
+}
+
+const CONTAINER_COLUMNS: &str = "id, name, color, position";
+
+fn container_from_row(row: &Row) -> rusqlite::Result<Container> {
+	Ok(Container {
+		id: row.get(0)?,
+		name: row.get(1)?,
+		color: row.get(2)?,
+		position: row.get(3)?,
+	})
+}
+
+fn validate_name(name: &str) -> Result<&str, String> {
+	let name = name.trim();
+	if name.is_empty() {
+		return Err("A container needs a name".to_string());
+	}
+	Ok(name)
+}
+
+impl Database {
+	/// Add a container at the end of the list
+	pub fn container_create(&self, name: &str, color: &str) -> Result<Container, String> {
+		let name = validate_name(name)?;
+		let conn = self.conn.lock().unwrap();
+		let id = Uuid::new_v4().to_string();
+
+		let position: i64 = conn
+			.query_row(
+				"SELECT COALESCE(MAX(position), -1) + 1 FROM containers",
+				[],
+				|row| row.get(0),
+			)
+			.map_err(|e| e.to_string())?;
+
+		conn.execute(
+			"INSERT INTO containers (id, name, color, position) VALUES (?1, ?2, ?3, ?4)",
+			params![id, name, color, position],
+		)
+		.map_err(|e| e.to_string())?;
+
+		Ok(Container {
+			id,
+			name: name.to_string(),
+			color: color.to_string(),
+			position,
+		})
+	}
+
+	pub fn container_get(&self, id: &str) -> Result<Option<Container>, String> {
+		let conn = self.conn.lock().unwrap();
+		conn.query_row(
+			&format!("SELECT {} FROM containers WHERE id = ?1", CONTAINER_COLUMNS),
+			params![id],
+			container_from_row,
+		)
+		.optional()
+		.map_err(|e| e.to_string())
+	}
+
+	/// All containers in list order
+	pub fn container_get_all(&self) -> Result<Vec<Container>, String> {
+		let conn = self.conn.lock().unwrap();
+		let mut stmt = conn
+			.prepare(&format!(
+				"SELECT {} FROM containers ORDER BY position",
+				CONTAINER_COLUMNS
+			))
+			.map_err(|e| e.to_string())?;
+
+		let containers = stmt
+			.query_map([], container_from_row)
+			.map_err(|e| e.to_string())?
+			.filter_map(|r| r.ok())
+			.collect();
+
+		Ok(containers)
+	}
+
+	/// Rename and/or recolour a container, returning its new state
+	pub fn container_update(
+		&self,
+		id: &str,
+		name: Option<&str>,
+		color: Option<&str>,
+	) -> Result<Container, String> {
+		let name = name.map(validate_name).transpose()?;
+		{
+			let conn = self.conn.lock().unwrap();
+			let changed = conn
+				.execute(
+					"UPDATE containers SET name = COALESCE(?2, name), color = COALESCE(?3, color) WHERE id = ?1",
+					params![id, name, color],
+				)
+				.map_err(|e| e.to_string())?;
+			if changed == 0 {
+				return Err(format!("Container {} not found", id));
+			}
+		}
+		self.container_get(id)?
+			.ok_or_else(|| format!("Container {} not found", id))
+	}
+
+	/// Remove a container. Closed tabs and session entries that were in it
+	/// fall back to the default container.
+	pub fn container_delete(&self, id: &str) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute("DELETE FROM containers WHERE id = ?1", params![id])
+			.map_err(|e| e.to_string())?;
+		conn.execute(
+			"UPDATE closed_tabs SET container_id = NULL WHERE container_id = ?1",
+			params![id],
+		)
+		.map_err(|e| e.to_string())?;
+		conn.execute(
+			"UPDATE session_tabs SET container_id = NULL WHERE container_id = ?1",
+			params![id],
+		)
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db() -> Database {
+		Database::open_in_memory().unwrap()
+	}
+
+	#[test]
+	fn create_appends_in_order() {
+		let db = test_db();
+		let work = db.container_create("Work", "blue").unwrap();
+		let personal = db.container_create("  Personal ", "green").unwrap();
+		assert_eq!(personal.name, "Personal");
+
+		let all = db.container_get_all().unwrap();
+		assert_eq!(all, vec![work.clone(), personal]);
+		assert_eq!(db.container_get(&work.id).unwrap(), Some(work));
+		assert_eq!(db.container_get("missing").unwrap(), None);
+	}
+
+	#[test]
+	fn create_requires_name() {
+		let db = test_db();
+		assert!(db.container_create("   ", "blue").is_err());
+		assert!(db.container_get_all().unwrap().is_empty());
+	}
+
+	#[test]
+	fn update_changes_only_given_fields() {
+		let db = test_db();
+		let work = db.container_create("Work", "blue").unwrap();
+
+		let renamed = db.container_update(&work.id, Some("Office"), None).unwrap();
+		assert_eq!(renamed.name, "Office");
+		assert_eq!(renamed.color, "blue");
+
+		let recoloured = db.container_update(&work.id, None, Some("red")).unwrap();
+		assert_eq!(recoloured.name, "Office");
+		assert_eq!(recoloured.color, "red");
+
+		assert!(db.container_update(&work.id, Some(""), None).is_err());
+		assert!(db.container_update("missing", Some("X"), None).is_err());
+	}
+
+	#[test]
+	fn delete_releases_closed_tabs() {
+		let db = test_db();
+		let work = db.container_create("Work", "blue").unwrap();
+		{
+			let conn = db.conn.lock().unwrap();
+			conn.execute(
+				"INSERT INTO closed_tabs (window_id, url, title, tab_index, container_id)
+				 VALUES ('main', 'https://a.com', 'A', 0, ?1)",
+				params![work.id],
+			)
+			.unwrap();
+		}
+
+		db.container_delete(&work.id).unwrap();
+		assert_eq!(db.container_get(&work.id).unwrap(), None);
+		let closed = db.closed_tab_pop("main", None).unwrap().unwrap();
+		assert_eq!(closed.container_id, None);
+	}
+}
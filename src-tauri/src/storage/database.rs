@@ -2,7 +2,7 @@ use rusqlite::{Connection, Result as SqlResult};
 use std::sync::Mutex;
 
 /// Current schema version — bump this when adding migrations
-const SCHEMA_VERSION: u32 = 1;
+const SCHEMA_VERSION: u32 = 11;
 
 /// Thread-safe wrapper around a SQLite connection
 pub struct Database {
@@ -48,8 +48,39 @@ impl Database {
 			self.apply_v1(&conn)?;
 		}
 
+		if current_version < 2 {
+			self.apply_v2(&conn)?;
+		}
+		if current_version < 3 {
+			self.apply_v3(&conn)?;
+		}
+		if current_version < 4 {
+			self.apply_v4(&conn)?;
+		}
+		if current_version < 5 {
+			self.apply_v5(&conn)?;
+		}
+		if current_version < 6 {
+			self.apply_v6(&conn)?;
+		}
+		if current_version < 7 {
+			self.apply_v7(&conn)?;
+		}
+		if current_version < 8 {
+			self.apply_v8(&conn)?;
+		}
+		if current_version < 9 {
+			self.apply_v9(&conn)?;
+		}
+		if current_version < 10 {
+			self.apply_v10(&conn)?;
+		}
+		if current_version < 11 {
+			self.apply_v11(&conn)?;
+		}
+
 		// Future migrations go here:
-		// if current_version < 2 { self.apply_v2(&conn)?; }
+		// if current_version < 12 { self.apply_v12(&conn)?; }
 
 		conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
 		Ok(())
@@ -111,6 +142,148 @@ impl Database {
 		)?;
 		Ok(())
 	}
+
+	/// V2: Session snapshot — one row per open tab, ordered by position
+	fn apply_v2(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			CREATE TABLE IF NOT EXISTS session_tabs (
+				position INTEGER PRIMARY KEY,
+				url TEXT NOT NULL,
+				title TEXT NOT NULL,
+				favicon TEXT,
+				nav_stack TEXT NOT NULL DEFAULT '[]',
+				nav_pos INTEGER NOT NULL DEFAULT -1,
+				is_active BOOLEAN DEFAULT FALSE
+			);
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V3: Recently closed tabs — a bounded per-window stack for "reopen closed tab"
+	fn apply_v3(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			CREATE TABLE IF NOT EXISTS closed_tabs (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				window_id TEXT NOT NULL,
+				url TEXT NOT NULL,
+				title TEXT NOT NULL,
+				favicon TEXT,
+				nav_stack TEXT NOT NULL DEFAULT '[]',
+				nav_pos INTEGER NOT NULL DEFAULT -1,
+				tab_index INTEGER NOT NULL,
+				closed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			);
+			CREATE INDEX IF NOT EXISTS idx_closed_tabs_window ON closed_tabs(window_id, id DESC);
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V4: Pinned tabs are part of the saved session
+	fn apply_v4(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			ALTER TABLE session_tabs ADD COLUMN is_pinned BOOLEAN DEFAULT FALSE;
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V5: The saved session remembers which window each tab was in
+	fn apply_v5(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			ALTER TABLE session_tabs ADD COLUMN window_id TEXT NOT NULL DEFAULT 'main';
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V6: Per-site zoom levels, keyed by origin
+	fn apply_v6(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			CREATE TABLE IF NOT EXISTS site_zoom (
+				origin TEXT PRIMARY KEY,
+				zoom INTEGER NOT NULL,
+				updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			);
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V7: Containers — named sets of tabs with their own cookies and storage.
+	/// Open and recently closed tabs remember theirs.
+	fn apply_v7(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			CREATE TABLE IF NOT EXISTS containers (
+				id TEXT PRIMARY KEY,
+				name TEXT NOT NULL,
+				color TEXT NOT NULL,
+				position INTEGER NOT NULL,
+				created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			);
+			ALTER TABLE session_tabs ADD COLUMN container_id TEXT;
+			ALTER TABLE closed_tabs ADD COLUMN container_id TEXT;
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V8: Search engines, replacing the free-text `search_engine` setting
+	fn apply_v8(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			CREATE TABLE IF NOT EXISTS search_engines (
+				id TEXT PRIMARY KEY,
+				name TEXT NOT NULL,
+				keyword TEXT UNIQUE,
+				url TEXT NOT NULL,
+				suggest_url TEXT,
+				favicon TEXT,
+				is_default BOOLEAN NOT NULL DEFAULT FALSE,
+				position INTEGER NOT NULL,
+				created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			);
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V9: Count visits typed in the address bar, for suggestion ranking
+	fn apply_v9(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			ALTER TABLE history ADD COLUMN typed_count INTEGER NOT NULL DEFAULT 0;
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V10: Remember pages whose last visit failed to load
+	fn apply_v10(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			ALTER TABLE history ADD COLUMN last_error TEXT;
+			",
+		)?;
+		Ok(())
+	}
+
+	/// V11: Reopen closed pinned tabs pinned
+	fn apply_v11(&self, conn: &Connection) -> SqlResult<()> {
+		conn.execute_batch(
+			"
+			ALTER TABLE closed_tabs ADD COLUMN is_pinned BOOLEAN DEFAULT FALSE;
+			",
+		)?;
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -145,6 +318,11 @@ mod tests {
 		assert!(tables.contains(&"bookmarks".to_string()));
 		assert!(tables.contains(&"permissions".to_string()));
 		assert!(tables.contains(&"autofill_profiles".to_string()));
+		assert!(tables.contains(&"session_tabs".to_string()));
+		assert!(tables.contains(&"closed_tabs".to_string()));
+		assert!(tables.contains(&"site_zoom".to_string()));
+		assert!(tables.contains(&"containers".to_string()));
+		assert!(tables.contains(&"search_engines".to_string()));
 	}
 
 	#[test]
@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, Row};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -10,19 +10,47 @@ pub struct HistoryEntry {
 	pub url: String,
 	pub title: Option<String>,
 	pub visit_count: i64,
+	/// Visits that started in the address bar
+	pub typed_count: i64,
+	/// Why the last visit failed to load (`dns`, `tls`…) — None once the page loads
+	pub last_error: Option<String>,
 	pub last_visited: String,
 	pub first_visited: String,
 }
 
+/// A history entry matching address bar input, with how long ago it was last visited
+#[derive(Debug, Clone)]
+pub struct HistoryMatch {
+	pub entry: HistoryEntry,
+	pub days_ago: f64,
+}
+
+const HISTORY_COLUMNS: &str =
+	"id, url, title, visit_count, typed_count, last_error, last_visited, first_visited";
+
+fn history_entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+	Ok(HistoryEntry {
+		id: row.get(0)?,
+		url: row.get(1)?,
+		title: row.get(2)?,
+		visit_count: row.get(3)?,
+		typed_count: row.get(4)?,
+		last_error: row.get(5)?,
+		last_visited: row.get(6)?,
+		first_visited: row.get(7)?,
+	})
+}
+
 impl Database {
-	/// Record a page visit — upserts by URL (increments visit_count if exists)
+	/// Record a page visit — upserts by URL (increments visit_count if exists).
+	/// A page that loads is no longer marked as failed.
 	pub fn history_add_visit(&self, url: &str, title: Option<&str>) -> Result<(), String> {
 		let conn = self.conn.lock().unwrap();
 
 		// Try to update existing entry first
 		let updated = conn
 			.execute(
-				"UPDATE history SET visit_count = visit_count + 1, last_visited = CURRENT_TIMESTAMP, title = COALESCE(?2, title) WHERE url = ?1",
+				"UPDATE history SET visit_count = visit_count + 1, last_visited = CURRENT_TIMESTAMP, title = COALESCE(?2, title), last_error = NULL WHERE url = ?1",
 				params![url, title],
 			)
 			.map_err(|e| e.to_string())?;
@@ -40,6 +68,18 @@ impl Database {
 		Ok(())
 	}
 
+	/// Set the title of a page already in history. Pages often settle on their title
+	/// after the visit is recorded (single-page apps always do).
+	pub fn history_set_title(&self, url: &str, title: &str) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute(
+			"UPDATE history SET title = ?2 WHERE url = ?1",
+			params![url, title],
+		)
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
 	/// Search history by URL or title substring
 	pub fn history_search(&self, query: &str, limit: i64) -> Result<Vec<HistoryEntry>, String> {
 		let conn = self.conn.lock().unwrap();
@@ -47,30 +87,77 @@ impl Database {
 
 		let mut stmt = conn
 			.prepare(
-				"SELECT id, url, title, visit_count, last_visited, first_visited
-				 FROM history
-				 WHERE url LIKE ?1 OR title LIKE ?1
-				 ORDER BY last_visited DESC
-				 LIMIT ?2",
+				&format!(
+					"SELECT {} FROM history
+					 WHERE url LIKE ?1 OR title LIKE ?1
+					 ORDER BY last_visited DESC
+					 LIMIT ?2",
+					HISTORY_COLUMNS
+				),
 			)
 			.map_err(|e| e.to_string())?;
 
 		let entries = stmt
+			.query_map(params![pattern, limit], history_entry_from_row)
+			.map_err(|e| e.to_string())?
+			.filter_map(|r| r.ok())
+			.collect();
+
+		Ok(entries)
+	}
+
+	/// Mark the latest visit to a page as failed, with the kind of load error
+	pub fn history_set_error(&self, url: &str, error: &str) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute(
+			"UPDATE history SET last_error = ?2 WHERE url = ?1",
+			params![url, error],
+		)
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// Count the latest visit to a page as typed — it was opened from the address bar
+	pub fn history_mark_typed(&self, url: &str) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute(
+			"UPDATE history SET typed_count = typed_count + 1 WHERE url = ?1",
+			params![url],
+		)
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// Candidates for address bar suggestions: pages whose URL or title contains
+	/// `query`, roughly most visited and recent first (`frecency` ranks them properly).
+	/// Pages that failed to load last time aren't suggested.
+	pub fn history_suggest(&self, query: &str, limit: i64) -> Result<Vec<HistoryMatch>, String> {
+		let conn = self.conn.lock().unwrap();
+		let pattern = format!("%{}%", query);
+
+		let mut stmt = conn
+			.prepare(&format!(
+				"SELECT {}, julianday('now') - julianday(last_visited) AS days_ago
+				 FROM history
+				 WHERE (url LIKE ?1 OR title LIKE ?1) AND last_error IS NULL
+				 ORDER BY (visit_count + typed_count) / (1.0 + MAX(days_ago, 0.0) / 7.0) DESC
+				 LIMIT ?2",
+				HISTORY_COLUMNS
+			))
+			.map_err(|e| e.to_string())?;
+
+		let matches = stmt
 			.query_map(params![pattern, limit], |row| {
-				Ok(HistoryEntry {
-					id: row.get(0)?,
-					url: row.get(1)?,
-					title: row.get(2)?,
-					visit_count: row.get(3)?,
-					last_visited: row.get(4)?,
-					first_visited: row.get(5)?,
+				Ok(HistoryMatch {
+					entry: history_entry_from_row(row)?,
+					days_ago: row.get::<_, f64>(8)?.max(0.0),
 				})
 			})
 			.map_err(|e| e.to_string())?
 			.filter_map(|r| r.ok())
 			.collect();
 
-		Ok(entries)
+		Ok(matches)
 	}
 
 	/// Get recent history entries
@@ -79,24 +166,15 @@ impl Database {
 
 		let mut stmt = conn
 			.prepare(
-				"SELECT id, url, title, visit_count, last_visited, first_visited
-				 FROM history
-				 ORDER BY last_visited DESC
-				 LIMIT ?1",
+				&format!(
+					"SELECT {} FROM history ORDER BY last_visited DESC LIMIT ?1",
+					HISTORY_COLUMNS
+				),
 			)
 			.map_err(|e| e.to_string())?;
 
 		let entries = stmt
-			.query_map(params![limit], |row| {
-				Ok(HistoryEntry {
-					id: row.get(0)?,
-					url: row.get(1)?,
-					title: row.get(2)?,
-					visit_count: row.get(3)?,
-					last_visited: row.get(4)?,
-					first_visited: row.get(5)?,
-				})
-			})
+			.query_map(params![limit], history_entry_from_row)
 			.map_err(|e| e.to_string())?
 			.filter_map(|r| r.ok())
 			.collect();
@@ -165,6 +243,19 @@ mod tests {
 		assert_eq!(entries[0].title.as_deref(), Some("Example - Updated"));
 	}
 
+	#[test]
+	fn set_title_updates_known_pages_only() {
+		let db = test_db();
+		db.history_add_visit("https://app.com/inbox", None).unwrap();
+		db.history_set_title("https://app.com/inbox", "Inbox (3)").unwrap();
+		db.history_set_title("https://app.com/unvisited", "Nope").unwrap();
+
+		let entries = db.history_get_recent(10).unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].title.as_deref(), Some("Inbox (3)"));
+		assert_eq!(entries[0].visit_count, 1);
+	}
+
 	#[test]
 	fn search_finds_by_url() {
 		let db = test_db();
@@ -178,6 +269,43 @@ mod tests {
 		assert_eq!(results[0].url, "https://example.com");
 	}
 
+	#[test]
+	fn suggest_prefers_frequent_and_typed_pages() {
+		let db = test_db();
+		db.history_add_visit("https://docs.rs/serde", Some("serde - Docs.rs")).unwrap();
+		for _ in 0..3 {
+			db.history_add_visit("https://docs.rs/tokio", Some("tokio - Docs.rs")).unwrap();
+		}
+		db.history_add_visit("https://docs.rs/rand", Some("rand - Docs.rs")).unwrap();
+		db.history_mark_typed("https://docs.rs/rand").unwrap();
+		db.history_mark_typed("https://docs.rs/rand").unwrap();
+		db.history_mark_typed("https://docs.rs/rand").unwrap();
+		db.history_mark_typed("https://unvisited.com").unwrap();
+
+		let matches = db.history_suggest("docs", 10).unwrap();
+		let urls: Vec<&str> = matches.iter().map(|m| m.entry.url.as_str()).collect();
+		assert_eq!(urls, vec!["https://docs.rs/rand", "https://docs.rs/tokio", "https://docs.rs/serde"]);
+		assert_eq!(matches[0].entry.typed_count, 3);
+		assert!(matches[0].days_ago < 1.0);
+		assert!(db.history_suggest("unvisited", 10).unwrap().is_empty());
+	}
+
+	#[test]
+	fn failed_visit_is_marked_until_page_loads() {
+		let db = test_db();
+		db.history_add_visit("https://exmaple.com", None).unwrap();
+		db.history_set_error("https://exmaple.com", "dns").unwrap();
+
+		let entries = db.history_get_recent(10).unwrap();
+		assert_eq!(entries[0].last_error.as_deref(), Some("dns"));
+		assert!(db.history_suggest("exmaple", 10).unwrap().is_empty());
+
+		db.history_add_visit("https://exmaple.com", None).unwrap();
+		let entries = db.history_get_recent(10).unwrap();
+		assert_eq!(entries[0].last_error, None);
+		assert_eq!(db.history_suggest("exmaple", 10).unwrap().len(), 1);
+	}
+
 	#[test]
 	fn search_finds_by_title() {
 		let db = test_db();
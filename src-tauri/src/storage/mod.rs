@@ -1,4 +1,9 @@
 pub mod bookmarks;
+pub mod closed_tabs;
+pub mod containers;
 pub mod database;
 pub mod history;
+pub mod search_engines;
+pub mod session;
 pub mod settings;
+pub mod site_zoom;
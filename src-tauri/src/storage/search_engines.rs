@@ -0,0 +1,489 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::database::Database;
+
+/// A search engine the address bar can search with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchEngine {
+	pub id: String,
+	pub name: String,
+	/// Typed before a query to search with this engine (`w rust`)
+	pub keyword: Option<String>,
+	/// Results page URL with `%s` where the query goes
+	pub url: String,
+	/// Suggestions endpoint (OpenSearch JSON) with `%s` where the query goes
+	pub suggest_url: Option<String>,
+	pub favicon: Option<String>,
+	/// The engine used for searches without a keyword — exactly one is
+	pub is_default: bool,
+	pub position: i64,
+}
+
+/// The editable part of a search engine, as sent by the settings page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchEngineFields {
+	pub name: String,
+	pub keyword: Option<String>,
+	pub url: String,
+	pub suggest_url: Option<String>,
+	pub favicon: Option<String>,
+}
+
+/// Engines seeded on first run: (name, keyword, url, suggest_url, favicon)
+const DEFAULT_ENGINES: &[(&str, &str, &str, &str, &str)] = &[
+	(
+		"Google",
+		"g",
+		"https://www.google.com/search?q=%s",
+		"https://suggestqueries.google.com/complete/search?client=firefox&q=%s",
+		"https://www.google.com/favicon.ico",
+	),
+	(
+		"DuckDuckGo",
+		"d",
+		"https://duckduckgo.com/?q=%s",
+		"https://duckduckgo.com/ac/?q=%s&type=list",
+		"https://duckduckgo.com/favicon.ico",
+	),
+	(
+		"Bing",
+		"b",
+		"https://www.bing.com/search?q=%s",
+		"https://api.bing.com/osjson.aspx?query=%s",
+		"https://www.bing.com/favicon.ico",
+	),
+	(
+		"Brave Search",
+		"br",
+		"https://search.brave.com/search?q=%s",
+		"https://search.brave.com/api/suggest?q=%s",
+		"https://search.brave.com/favicon.ico",
+	),
+	(
+		"Wikipedia",
+		"w",
+		"https://en.wikipedia.org/wiki/Special:Search?search=%s",
+		"https://en.wikipedia.org/w/api.php?action=opensearch&search=%s",
+		"https://en.wikipedia.org/favicon.ico",
+	),
+];
+
+const SEARCH_ENGINE_COLUMNS: &str =
+	"id, name, keyword, url, suggest_url, favicon, is_default, position";
+
+fn search_engine_from_row(row: &Row) -> rusqlite::Result<SearchEngine> {
+	Ok(SearchEngine {
+		id: row.get(0)?,
+		name: row.get(1)?,
+		keyword: row.get(2)?,
+		url: row.get(3)?,
+		suggest_url: row.get(4)?,
+		favicon: row.get(5)?,
+		is_default: row.get(6)?,
+		position: row.get(7)?,
+	})
+}
+
+/// Check a query URL template and bring it to the `%s` form
+fn normalize_template(template: &str) -> Result<String, String> {
+	let template = template.trim().replace("{searchTerms}", "%s");
+	if !template.contains("%s") {
+		return Err("The search URL needs %s where the query goes".to_string());
+	}
+	match url::Url::parse(&template.replace("%s", "test")) {
+		Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(template),
+		_ => Err(format!("Invalid search URL: {}", template)),
+	}
+}
+
+/// Trim, validate and normalise an engine's fields. The keyword must not be in use by
+/// another engine than `id`.
+fn validate(
+	conn: &Connection,
+	fields: &SearchEngineFields,
+	id: Option<&str>,
+) -> Result<SearchEngineFields, String> {
+	let name = fields.name.trim();
+	if name.is_empty() {
+		return Err("A search engine needs a name".to_string());
+	}
+
+	let keyword = fields
+		.keyword
+		.as_deref()
+		.map(|k| k.trim().to_lowercase())
+		.filter(|k| !k.is_empty());
+	if let Some(keyword) = &keyword {
+		if keyword.contains(char::is_whitespace) {
+			return Err("A keyword can't contain spaces".to_string());
+		}
+		let taken_by: Option<String> = conn
+			.query_row(
+				"SELECT name FROM search_engines WHERE keyword = ?1 AND id != ?2",
+				params![keyword, id.unwrap_or("")],
+				|row| row.get(0),
+			)
+			.optional()
+			.map_err(|e| e.to_string())?;
+		if let Some(other) = taken_by {
+			return Err(format!("The keyword {} is already used by {}", keyword, other));
+		}
+	}
+
+	let suggest_url = match fields.suggest_url.as_deref().map(str::trim) {
+		Some(url) if !url.is_empty() => Some(normalize_template(url)?),
+		_ => None,
+	};
+
+	Ok(SearchEngineFields {
+		name: name.to_string(),
+		keyword,
+		url: normalize_template(&fields.url)?,
+		suggest_url,
+		favicon: fields.favicon.clone().filter(|f| !f.trim().is_empty()),
+	})
+}
+
+impl Database {
+	/// Seed the built-in engines on first run (idempotent). The default follows the
+	/// old free-text `search_engine` setting, which the table replaces.
+	pub fn seed_search_engines(&self) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		let count: i64 = conn
+			.query_row("SELECT COUNT(*) FROM search_engines", [], |row| row.get(0))
+			.map_err(|e| e.to_string())?;
+		if count > 0 {
+			return Ok(());
+		}
+
+		let legacy: Option<String> = conn
+			.query_row(
+				"SELECT value FROM settings WHERE key = 'search_engine'",
+				[],
+				|row| row.get(0),
+			)
+			.optional()
+			.map_err(|e| e.to_string())?;
+		// Old values were a prefix the query was appended to
+		let legacy = legacy
+			.map(|url| if url.contains("%s") { url } else { format!("{}%s", url) })
+			.filter(|url| normalize_template(url).is_ok());
+		let legacy_is_builtin = legacy
+			.as_deref()
+			.is_some_and(|url| DEFAULT_ENGINES.iter().any(|e| e.2 == url));
+
+		for (position, (name, keyword, url, suggest_url, favicon)) in
+			DEFAULT_ENGINES.iter().enumerate()
+		{
+			let is_default = match &legacy {
+				Some(legacy) if legacy_is_builtin => legacy == url,
+				Some(_) => false,
+				None => position == 0,
+			};
+			conn.execute(
+				"INSERT INTO search_engines (id, name, keyword, url, suggest_url, favicon, is_default, position)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+				params![
+					Uuid::new_v4().to_string(),
+					name,
+					keyword,
+					url,
+					suggest_url,
+					favicon,
+					is_default,
+					position as i64,
+				],
+			)
+			.map_err(|e| e.to_string())?;
+		}
+
+		if let Some(url) = legacy.filter(|_| !legacy_is_builtin) {
+			conn.execute(
+				"INSERT INTO search_engines (id, name, url, is_default, position) VALUES (?1, 'Custom', ?2, TRUE, ?3)",
+				params![Uuid::new_v4().to_string(), url, DEFAULT_ENGINES.len() as i64],
+			)
+			.map_err(|e| e.to_string())?;
+		}
+		conn.execute("DELETE FROM settings WHERE key = 'search_engine'", [])
+			.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// All engines in list order
+	pub fn search_engine_get_all(&self) -> Result<Vec<SearchEngine>, String> {
+		let conn = self.conn.lock().unwrap();
+		let mut stmt = conn
+			.prepare(&format!(
+				"SELECT {} FROM search_engines ORDER BY position",
+				SEARCH_ENGINE_COLUMNS
+			))
+			.map_err(|e| e.to_string())?;
+
+		let engines = stmt
+			.query_map([], search_engine_from_row)
+			.map_err(|e| e.to_string())?
+			.filter_map(|r| r.ok())
+			.collect();
+
+		Ok(engines)
+	}
+
+	pub fn search_engine_get(&self, id: &str) -> Result<Option<SearchEngine>, String> {
+		let conn = self.conn.lock().unwrap();
+		conn.query_row(
+			&format!("SELECT {} FROM search_engines WHERE id = ?1", SEARCH_ENGINE_COLUMNS),
+			params![id],
+			search_engine_from_row,
+		)
+		.optional()
+		.map_err(|e| e.to_string())
+	}
+
+	/// Add an engine at the end of the list. The first engine added becomes the default.
+	pub fn search_engine_add(&self, fields: &SearchEngineFields) -> Result<SearchEngine, String> {
+		let id = Uuid::new_v4().to_string();
+		{
+			let conn = self.conn.lock().unwrap();
+			let fields = validate(&conn, fields, None)?;
+			let (position, count): (i64, i64) = conn
+				.query_row(
+					"SELECT COALESCE(MAX(position), -1) + 1, COUNT(*) FROM search_engines",
+					[],
+					|row| Ok((row.get(0)?, row.get(1)?)),
+				)
+				.map_err(|e| e.to_string())?;
+
+			conn.execute(
+				"INSERT INTO search_engines (id, name, keyword, url, suggest_url, favicon, is_default, position)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+				params![
+					id,
+					fields.name,
+					fields.keyword,
+					fields.url,
+					fields.suggest_url,
+					fields.favicon,
+					count == 0,
+					position,
+				],
+			)
+			.map_err(|e| e.to_string())?;
+		}
+		self.search_engine_get(&id)?
+			.ok_or_else(|| "Search engine not saved".to_string())
+	}
+
+	/// Replace an engine's editable fields, returning its new state
+	pub fn search_engine_update(
+		&self,
+		id: &str,
+		fields: &SearchEngineFields,
+	) -> Result<SearchEngine, String> {
+		{
+			let conn = self.conn.lock().unwrap();
+			let fields = validate(&conn, fields, Some(id))?;
+			let changed = conn
+				.execute(
+					"UPDATE search_engines SET name = ?2, keyword = ?3, url = ?4, suggest_url = ?5, favicon = ?6
+					 WHERE id = ?1",
+					params![
+						id,
+						fields.name,
+						fields.keyword,
+						fields.url,
+						fields.suggest_url,
+						fields.favicon,
+					],
+				)
+				.map_err(|e| e.to_string())?;
+			if changed == 0 {
+				return Err(format!("Search engine {} not found", id));
+			}
+		}
+		self.search_engine_get(id)?
+			.ok_or_else(|| format!("Search engine {} not found", id))
+	}
+
+	/// Make an engine the one used for searches without a keyword
+	pub fn search_engine_set_default(&self, id: &str) -> Result<(), String> {
+		let mut conn = self.conn.lock().unwrap();
+		let tx = conn.transaction().map_err(|e| e.to_string())?;
+		let changed = tx
+			.execute(
+				"UPDATE search_engines SET is_default = (id = ?1)",
+				params![id],
+			)
+			.map_err(|e| e.to_string())?;
+		let found: bool = tx
+			.query_row(
+				"SELECT EXISTS(SELECT 1 FROM search_engines WHERE id = ?1)",
+				params![id],
+				|row| row.get(0),
+			)
+			.map_err(|e| e.to_string())?;
+		if changed == 0 || !found {
+			return Err(format!("Search engine {} not found", id));
+		}
+		tx.commit().map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// Remove an engine. The last one can't go; removing the default makes the
+	/// first remaining engine the default.
+	pub fn search_engine_delete(&self, id: &str) -> Result<(), String> {
+		let mut conn = self.conn.lock().unwrap();
+		let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+		let count: i64 = tx
+			.query_row("SELECT COUNT(*) FROM search_engines", [], |row| row.get(0))
+			.map_err(|e| e.to_string())?;
+		if count <= 1 {
+			return Err("At least one search engine is needed".to_string());
+		}
+
+		let deleted = tx
+			.execute("DELETE FROM search_engines WHERE id = ?1", params![id])
+			.map_err(|e| e.to_string())?;
+		if deleted == 0 {
+			return Err(format!("Search engine {} not found", id));
+		}
+		tx.execute(
+			"UPDATE search_engines SET is_default = TRUE
+			 WHERE NOT EXISTS (SELECT 1 FROM search_engines WHERE is_default)
+			 AND id = (SELECT id FROM search_engines ORDER BY position LIMIT 1)",
+			[],
+		)
+		.map_err(|e| e.to_string())?;
+
+		tx.commit().map_err(|e| e.to_string())?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db() -> Database {
+		let db = Database::open_in_memory().unwrap();
+		db.seed_search_engines().unwrap();
+		db
+	}
+
+	fn fields(name: &str, keyword: Option<&str>, url: &str) -> SearchEngineFields {
+		SearchEngineFields {
+			name: name.to_string(),
+			keyword: keyword.map(str::to_string),
+			url: url.to_string(),
+			..Default::default()
+		}
+	}
+
+	fn default_name(db: &Database) -> String {
+		let all = db.search_engine_get_all().unwrap();
+		let defaults: Vec<&SearchEngine> = all.iter().filter(|e| e.is_default).collect();
+		assert_eq!(defaults.len(), 1);
+		defaults[0].name.clone()
+	}
+
+	#[test]
+	fn seed_adds_builtins_with_google_default() {
+		let db = test_db();
+		let names: Vec<String> =
+			db.search_engine_get_all().unwrap().into_iter().map(|e| e.name).collect();
+		assert_eq!(names, vec!["Google", "DuckDuckGo", "Bing", "Brave Search", "Wikipedia"]);
+		assert_eq!(default_name(&db), "Google");
+
+		// Seeding again changes nothing
+		db.seed_search_engines().unwrap();
+		assert_eq!(db.search_engine_get_all().unwrap().len(), DEFAULT_ENGINES.len());
+	}
+
+	#[test]
+	fn seed_keeps_old_search_engine_setting() {
+		let db = Database::open_in_memory().unwrap();
+		db.settings_set("search_engine", "https://duckduckgo.com/?q=").unwrap();
+		db.seed_search_engines().unwrap();
+		assert_eq!(default_name(&db), "DuckDuckGo");
+		assert_eq!(db.settings_get("search_engine").unwrap(), None);
+
+		let db = Database::open_in_memory().unwrap();
+		db.settings_set("search_engine", "https://search.example/find?q=").unwrap();
+		db.seed_search_engines().unwrap();
+		assert_eq!(default_name(&db), "Custom");
+		let custom = db.search_engine_get_all().unwrap().pop().unwrap();
+		assert_eq!(custom.url, "https://search.example/find?q=%s");
+	}
+
+	#[test]
+	fn add_normalises_fields() {
+		let db = test_db();
+		let engine = db
+			.search_engine_add(&SearchEngineFields {
+				suggest_url: Some("  ".to_string()),
+				..fields(" MDN ", Some(" MDN "), "https://developer.mozilla.org/search?q={searchTerms}")
+			})
+			.unwrap();
+		assert_eq!(engine.name, "MDN");
+		assert_eq!(engine.keyword.as_deref(), Some("mdn"));
+		assert_eq!(engine.url, "https://developer.mozilla.org/search?q=%s");
+		assert_eq!(engine.suggest_url, None);
+		assert!(!engine.is_default);
+		assert_eq!(engine.position, DEFAULT_ENGINES.len() as i64);
+	}
+
+	#[test]
+	fn add_rejects_bad_fields() {
+		let db = test_db();
+		assert!(db.search_engine_add(&fields("", None, "https://a.com/?q=%s")).is_err());
+		assert!(db.search_engine_add(&fields("A", None, "https://a.com/")).is_err());
+		assert!(db.search_engine_add(&fields("A", None, "javascript:%s")).is_err());
+		assert!(db.search_engine_add(&fields("A", Some("a b"), "https://a.com/?q=%s")).is_err());
+
+		let err = db.search_engine_add(&fields("Wiki", Some("W"), "https://a.com/?q=%s")).unwrap_err();
+		assert!(err.contains("Wikipedia"), "{}", err);
+	}
+
+	#[test]
+	fn update_keeps_own_keyword() {
+		let db = test_db();
+		let wiki = db.search_engine_get_all().unwrap().pop().unwrap();
+		let updated = db
+			.search_engine_update(
+				&wiki.id,
+				&fields("Wikipedia (de)", Some("w"), "https://de.wikipedia.org/w/index.php?search=%s"),
+			)
+			.unwrap();
+		assert_eq!(updated.name, "Wikipedia (de)");
+		assert_eq!(updated.keyword.as_deref(), Some("w"));
+		assert!(db.search_engine_update("missing", &fields("X", None, "https://x.com/?q=%s")).is_err());
+	}
+
+	#[test]
+	fn set_default_moves_the_flag() {
+		let db = test_db();
+		let bing = db.search_engine_get_all().unwrap()[2].clone();
+		db.search_engine_set_default(&bing.id).unwrap();
+		assert_eq!(default_name(&db), "Bing");
+		assert!(db.search_engine_set_default("missing").is_err());
+		assert_eq!(default_name(&db), "Bing");
+	}
+
+	#[test]
+	fn deleting_default_promotes_first_engine() {
+		let db = test_db();
+		let google = db.search_engine_get_all().unwrap()[0].clone();
+		assert!(db.search_engine_delete("missing").is_err());
+		db.search_engine_delete(&google.id).unwrap();
+		assert_eq!(default_name(&db), "DuckDuckGo");
+
+		// The last engine stays
+		for engine in db.search_engine_get_all().unwrap().iter().skip(1) {
+			db.search_engine_delete(&engine.id).unwrap();
+		}
+		let last = db.search_engine_get_all().unwrap().pop().unwrap();
+		assert!(db.search_engine_delete(&last.id).is_err());
+	}
+}
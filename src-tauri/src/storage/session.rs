@@ -0,0 +1,179 @@
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::database::Database;
+use crate::state::tab_state::NavEntry;
+
+/// Snapshot of a single open tab, enough to rebuild it on the next launch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTab {
+	pub window_id: String,
+	pub url: String,
+	pub title: String,
+	pub favicon: Option<String>,
+	pub nav_stack: Vec<NavEntry>,
+	pub nav_pos: i32,
+	pub is_active: bool,
+	pub is_pinned: bool,
+	pub container_id: Option<String>,
+}
+
+impl Database {
+	/// Replace the stored session with the given tabs (in tab-strip order within each window)
+	pub fn session_save(&self, tabs: &[SessionTab]) -> Result<(), String> {
+		let mut conn = self.conn.lock().unwrap();
+		let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+		tx.execute("DELETE FROM session_tabs", [])
+			.map_err(|e| e.to_string())?;
+
+		{
+			let mut stmt = tx
+				.prepare(
+					"INSERT INTO session_tabs (position, window_id, url, title, favicon, nav_stack, nav_pos, is_active, is_pinned, container_id)
+					 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+				)
+				.map_err(|e| e.to_string())?;
+
+			for (position, tab) in tabs.iter().enumerate() {
+				let nav_stack =
+					serde_json::to_string(&tab.nav_stack).map_err(|e| e.to_string())?;
+				stmt.execute(params![
+					position as i64,
+					tab.window_id,
+					tab.url,
+					tab.title,
+					tab.favicon,
+					nav_stack,
+					tab.nav_pos,
+					tab.is_active,
+					tab.is_pinned,
+					tab.container_id,
+				])
+				.map_err(|e| e.to_string())?;
+			}
+		}
+
+		tx.commit().map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// Load the stored session in tab-strip order
+	pub fn session_load(&self) -> Result<Vec<SessionTab>, String> {
+		let conn = self.conn.lock().unwrap();
+		let mut stmt = conn
+			.prepare(
+				"SELECT window_id, url, title, favicon, nav_stack, nav_pos, is_active, is_pinned, container_id
+				 FROM session_tabs
+				 ORDER BY position",
+			)
+			.map_err(|e| e.to_string())?;
+
+		let tabs = stmt
+			.query_map([], |row| {
+				let nav_stack: String = row.get(4)?;
+				Ok(SessionTab {
+					window_id: row.get(0)?,
+					url: row.get(1)?,
+					title: row.get(2)?,
+					favicon: row.get(3)?,
+					// A corrupt stack only costs back/forward, not the tab itself
+					nav_stack: serde_json::from_str(&nav_stack).unwrap_or_default(),
+					nav_pos: row.get(5)?,
+					is_active: row.get(6)?,
+					is_pinned: row.get(7)?,
+					container_id: row.get(8)?,
+				})
+			})
+			.map_err(|e| e.to_string())?
+			.filter_map(|r| r.ok())
+			.collect();
+
+		Ok(tabs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db() -> Database {
+		Database::open_in_memory().unwrap()
+	}
+
+	fn make_tab(url: &str, is_active: bool) -> SessionTab {
+		SessionTab {
+			window_id: "main".to_string(),
+			url: url.to_string(),
+			title: "Test".to_string(),
+			favicon: None,
+			nav_stack: vec![NavEntry::new(url)],
+			nav_pos: 0,
+			is_active,
+			is_pinned: false,
+			container_id: None,
+		}
+	}
+
+	#[test]
+	fn load_empty_session() {
+		let db = test_db();
+		assert!(db.session_load().unwrap().is_empty());
+	}
+
+	#[test]
+	fn save_and_load_round_trip() {
+		let db = test_db();
+		let tab = SessionTab {
+			favicon: Some("https://b.com/favicon.ico".to_string()),
+			nav_stack: vec![NavEntry::new("https://a.com"), NavEntry::new("https://b.com")],
+			nav_pos: 1,
+			is_pinned: true,
+			container_id: Some("work".to_string()),
+			..make_tab("https://b.com", true)
+		};
+		db.session_save(std::slice::from_ref(&tab)).unwrap();
+
+		assert_eq!(db.session_load().unwrap(), vec![tab]);
+	}
+
+	#[test]
+	fn load_preserves_order() {
+		let db = test_db();
+		db.session_save(&[
+			make_tab("https://a.com", false),
+			make_tab("https://b.com", true),
+			make_tab("https://c.com", false),
+		])
+		.unwrap();
+
+		let urls: Vec<String> = db.session_load().unwrap().into_iter().map(|t| t.url).collect();
+		assert_eq!(urls, vec!["https://a.com", "https://b.com", "https://c.com"]);
+	}
+
+	#[test]
+	fn load_keeps_window_of_each_tab() {
+		let db = test_db();
+		db.session_save(&[
+			make_tab("https://a.com", true),
+			SessionTab { window_id: "window-2".to_string(), ..make_tab("https://b.com", true) },
+		])
+		.unwrap();
+
+		let windows: Vec<String> =
+			db.session_load().unwrap().into_iter().map(|t| t.window_id).collect();
+		assert_eq!(windows, vec!["main", "window-2"]);
+	}
+
+	#[test]
+	fn save_replaces_previous_snapshot() {
+		let db = test_db();
+		db.session_save(&[make_tab("https://a.com", true), make_tab("https://b.com", false)])
+			.unwrap();
+		db.session_save(&[make_tab("https://c.com", true)]).unwrap();
+
+		let tabs = db.session_load().unwrap();
+		assert_eq!(tabs.len(), 1);
+		assert_eq!(tabs[0].url, "https://c.com");
+	}
+}
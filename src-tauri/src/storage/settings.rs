@@ -5,7 +5,6 @@ use super::database::Database;
 
 /// Default settings seeded on first run
 const DEFAULTS: &[(&str, &str)] = &[
-	("search_engine", "https://www.google.com/search?q="),
 	("homepage", "https://www.google.com"),
 	("new_tab_page", "https://www.google.com"),
 	("restore_on_startup", "new_tab"),
@@ -13,6 +12,8 @@ const DEFAULTS: &[(&str, &str)] = &[
 	("show_bookmarks_bar", "true"),
 	("show_status_bar", "true"),
 	("default_zoom", "100"),
+	("tab_discard_after_minutes", "30"),
+	("max_live_tabs", "20"),
 	("download_path", "~/Downloads"),
 	("ask_download_location", "false"),
 ];
@@ -94,10 +95,7 @@ mod tests {
 		let all = db.settings_get_all().unwrap();
 		assert!(all.len() >= DEFAULTS.len());
 		assert_eq!(all.get("theme").unwrap(), "dark");
-		assert_eq!(
-			all.get("search_engine").unwrap(),
-			"https://www.google.com/search?q="
-		);
+		assert_eq!(all.get("homepage").unwrap(), "https://www.google.com");
 	}
 
 	#[test]
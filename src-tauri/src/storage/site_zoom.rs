@@ -0,0 +1,78 @@
+use rusqlite::{params, OptionalExtension};
+
+use super::database::Database;
+
+impl Database {
+	/// Saved zoom level (percent) for an origin, if the user changed it there
+	pub fn site_zoom_get(&self, origin: &str) -> Result<Option<u32>, String> {
+		let conn = self.conn.lock().unwrap();
+		conn.query_row(
+			"SELECT zoom FROM site_zoom WHERE origin = ?1",
+			params![origin],
+			|row| row.get(0),
+		)
+		.optional()
+		.map_err(|e| e.to_string())
+	}
+
+	/// Remember an origin's zoom level (percent), replacing any earlier one
+	pub fn site_zoom_set(&self, origin: &str, zoom: u32) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute(
+			"INSERT INTO site_zoom (origin, zoom) VALUES (?1, ?2)
+			 ON CONFLICT(origin) DO UPDATE SET zoom = excluded.zoom, updated_at = CURRENT_TIMESTAMP",
+			params![origin, zoom],
+		)
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+
+	/// Forget an origin's zoom level so it follows `default_zoom` again
+	pub fn site_zoom_clear(&self, origin: &str) -> Result<(), String> {
+		let conn = self.conn.lock().unwrap();
+		conn.execute("DELETE FROM site_zoom WHERE origin = ?1", params![origin])
+			.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_db() -> Database {
+		Database::open_in_memory().unwrap()
+	}
+
+	#[test]
+	fn unknown_origin_has_no_zoom() {
+		let db = test_db();
+		assert_eq!(db.site_zoom_get("https://example.com").unwrap(), None);
+	}
+
+	#[test]
+	fn set_then_get() {
+		let db = test_db();
+		db.site_zoom_set("https://example.com", 125).unwrap();
+		assert_eq!(db.site_zoom_get("https://example.com").unwrap(), Some(125));
+		assert_eq!(db.site_zoom_get("https://other.com").unwrap(), None);
+	}
+
+	#[test]
+	fn set_replaces_earlier_level() {
+		let db = test_db();
+		db.site_zoom_set("https://example.com", 125).unwrap();
+		db.site_zoom_set("https://example.com", 80).unwrap();
+		assert_eq!(db.site_zoom_get("https://example.com").unwrap(), Some(80));
+	}
+
+	#[test]
+	fn clear_forgets_level() {
+		let db = test_db();
+		db.site_zoom_set("https://example.com", 150).unwrap();
+		db.site_zoom_clear("https://example.com").unwrap();
+		assert_eq!(db.site_zoom_get("https://example.com").unwrap(), None);
+		// Clearing an unknown origin is fine
+		db.site_zoom_clear("https://other.com").unwrap();
+	}
+}
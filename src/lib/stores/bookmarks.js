@@ -16,8 +16,8 @@ function createBookmarksStore() {
 		if (listenersSetUp) return
 		listenersSetUp = true
 
-		await listen('chrome_height_changed', () => {
-			// Chrome height changed — UI will re-render
+		await listen('layout_changed', () => {
+			// Layout insets changed (e.g. bar toggled) — UI will re-render
 		})
 	}
 
@@ -0,0 +1,58 @@
+import { writable } from 'svelte/store'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Colours a container can take — the same palette as tab groups
+export const CONTAINER_COLORS = ['grey', 'blue', 'red', 'yellow', 'green', 'pink', 'purple', 'cyan', 'orange']
+
+function createContainersStore() {
+	const { subscribe, set } = writable([])
+
+	let listenersSetUp = false
+
+	async function setupListeners() {
+		if (listenersSetUp) return
+		listenersSetUp = true
+
+		// The backend sends the whole list after every change, from any window
+		await listen('containers_updated', (event) => {
+			set(event.payload)
+		})
+	}
+
+	return {
+		subscribe,
+
+		async init() {
+			await setupListeners()
+			try {
+				set(await invoke('container_get_all'))
+			} catch (e) {
+				console.error('Failed to load containers:', e)
+			}
+		},
+
+		async create(name, color = null) {
+			try {
+				return await invoke('container_create', { name, color })
+			} catch (e) {
+				console.error('Failed to create container:', e)
+			}
+		},
+
+		async update(id, { name = null, color = null }) {
+			try {
+				return await invoke('container_update', { id, name, color })
+			} catch (e) {
+				console.error('Failed to update container:', e)
+			}
+		},
+
+		// Fails while tabs are open in the container — the error is passed on for the UI to show
+		async remove(id) {
+			await invoke('container_delete', { id })
+		},
+	}
+}
+
+export const containers = createContainersStore()
@@ -52,14 +52,6 @@ function createHistoryStore() {
 				console.error('Failed to clear history:', e)
 			}
 		},
-
-		async suggest(query, limit = 8) {
-			try {
-				return await invoke('history_search', { query, limit })
-			} catch (e) {
-				return []
-			}
-		},
 	}
 }
 
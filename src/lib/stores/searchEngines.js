@@ -0,0 +1,99 @@
+import { writable, get } from 'svelte/store'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+function createSearchEnginesStore() {
+	const { subscribe, set } = writable([])
+
+	// Engines offered by the page of each tab (label → engine), until it navigates away
+	const found = writable({})
+
+	function forget(label) {
+		found.update((all) => {
+			if (!(label in all)) return all
+			const { [label]: _, ...rest } = all
+			return rest
+		})
+	}
+
+	let listenersSetUp = false
+
+	async function setupListeners() {
+		if (listenersSetUp) return
+		listenersSetUp = true
+
+		// The backend sends the whole list after every change, from any window
+		await listen('search_engines_updated', (event) => {
+			set(event.payload)
+		})
+
+		await listen('search_engine_found', (event) => {
+			const { label, engine } = event.payload
+			found.update((all) => ({ ...all, [label]: engine }))
+		})
+
+		await listen('tab_updated', (event) => {
+			if (event.payload.loading === true) forget(event.payload.label)
+		})
+
+		await listen('tab_closed', (event) => {
+			forget(event.payload.label)
+		})
+	}
+
+	return {
+		subscribe,
+		found: { subscribe: found.subscribe },
+
+		async init() {
+			await setupListeners()
+			try {
+				set(await invoke('search_engine_get_all'))
+			} catch (e) {
+				console.error('Failed to load search engines:', e)
+			}
+		},
+
+		// Fails on a bad URL or a keyword that's taken — the error is passed on for the UI to show
+		async add({ name, keyword = null, url, suggest_url = null, favicon = null }) {
+			return await invoke('search_engine_add', {
+				engine: { name, keyword, url, suggest_url, favicon },
+			})
+		},
+
+		// Add the engine a tab's page offered
+		async addFound(label) {
+			const engine = get(found)[label]
+			if (!engine) return
+			try {
+				await this.add(engine)
+				forget(label)
+			} catch (e) {
+				console.error('Failed to add search engine:', e)
+			}
+		},
+
+		async update(id, { name, keyword = null, url, suggest_url = null, favicon = null }) {
+			return await invoke('search_engine_update', {
+				id,
+				engine: { name, keyword, url, suggest_url, favicon },
+			})
+		},
+
+		async setDefault(id) {
+			try {
+				await invoke('search_engine_set_default', { id })
+			} catch (e) {
+				console.error('Failed to set default search engine:', e)
+			}
+		},
+
+		async remove(id) {
+			await invoke('search_engine_delete', { id })
+		},
+
+		dismiss: forget,
+	}
+}
+
+export const searchEngines = createSearchEnginesStore()
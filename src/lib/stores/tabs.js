@@ -1,10 +1,16 @@
 import { writable, derived } from 'svelte/store'
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
+import { getCurrentWindow, Window } from '@tauri-apps/api/window'
+
+// Tab events are broadcast to every window — each UI only tracks its own window's tabs
+const windowId = getCurrentWindow().label
 
 function createTabStore() {
 	const { subscribe, set, update } = writable({
 		tabs: [],
+		groups: [],
+		splits: [],
 		activeTabLabel: null,
 	})
 
@@ -25,15 +31,13 @@ function createTabStore() {
 
 		await listen('tab_created', (event) => {
 			const tab = event.payload
+			if (tab.window_id !== windowId) return
 			update((state) => {
-				// Avoid duplicates — backend may emit before invoke returns
+				// Avoid duplicates — backend may emit before invoke returns.
+				// Activation follows as tab_activated unless the tab opened in the background
 				const exists = state.tabs.some((t) => t.label === tab.label)
 				if (exists) return state
-				return {
-					...state,
-					tabs: [...state.tabs, tab],
-					activeTabLabel: tab.label,
-				}
+				return { ...state, tabs: [...state.tabs, tab] }
 			})
 		})
 
@@ -45,8 +49,17 @@ function createTabStore() {
 			}))
 		})
 
+		await listen('tabs_closed', (event) => {
+			const { window_id, labels } = event.payload
+			if (window_id !== windowId) return
+			update((state) => ({
+				...state,
+				tabs: state.tabs.filter((t) => !labels.includes(t.label)),
+			}))
+		})
+
 		await listen('tab_updated', (event) => {
-			const { label, loading, url, title, favicon, can_go_back, can_go_forward } = event.payload
+			const { label, loading, url, title, favicon, can_go_back, can_go_forward, is_pinned, group_id, is_discarded, is_crashed, is_unresponsive, load_error, is_audible, is_muted, zoom } = event.payload
 			update((state) => ({
 				...state,
 				tabs: state.tabs.map((tab) =>
@@ -59,14 +72,35 @@ function createTabStore() {
 								...(favicon !== undefined && { favicon }),
 								...(can_go_back !== undefined && { can_go_back }),
 								...(can_go_forward !== undefined && { can_go_forward }),
+								...(is_pinned !== undefined && { is_pinned }),
+								...(group_id !== undefined && { group_id }),
+								...(is_discarded !== undefined && { is_discarded }),
+								...(is_crashed !== undefined && { is_crashed }),
+								...(is_unresponsive !== undefined && { is_unresponsive }),
+								...(load_error !== undefined && { load_error }),
+								...(is_audible !== undefined && { is_audible }),
+								...(is_muted !== undefined && { is_muted }),
+								...(zoom !== undefined && { zoom }),
 							}
 						: tab
 				),
 			}))
 		})
 
+		// The tab's content process died — it stays crashed until recovered
+		await listen('tab_crashed', (event) => {
+			const { label } = event.payload
+			update((state) => ({
+				...state,
+				tabs: state.tabs.map((t) =>
+					t.label === label ? { ...t, is_crashed: true, is_unresponsive: false, is_loading: false } : t
+				),
+			}))
+		})
+
 		await listen('tab_activated', (event) => {
 			const tab = event.payload
+			if (tab.window_id !== windowId) return
 			update((state) => ({
 				...state,
 				activeTabLabel: tab.label,
@@ -92,9 +126,79 @@ function createTabStore() {
 			})
 		})
 
+		await listen('tab_moved_to_window', (event) => {
+			const { tab, old_window_id, new_index } = event.payload
+			if (old_window_id === windowId) {
+				update((state) => ({
+					...state,
+					tabs: state.tabs.filter((t) => t.label !== tab.label),
+				}))
+			} else if (tab.window_id === windowId) {
+				update((state) => {
+					const tabs = state.tabs.filter((t) => t.label !== tab.label)
+					tabs.splice(new_index, 0, tab)
+					return { ...state, tabs }
+				})
+			}
+		})
+
+		await listen('tab_group_updated', (event) => {
+			const group = event.payload
+			update((state) => {
+				const exists = state.groups.some((g) => g.id === group.id)
+				return {
+					...state,
+					groups: exists
+						? state.groups.map((g) => (g.id === group.id ? group : g))
+						: [...state.groups, group],
+				}
+			})
+		})
+
+		await listen('tab_group_removed', (event) => {
+			const { group_id } = event.payload
+			update((state) => ({
+				...state,
+				groups: state.groups.filter((g) => g.id !== group_id),
+			}))
+		})
+
+		await listen('tab_group_moved', (event) => {
+			const { labels, new_index } = event.payload
+			update((state) => {
+				const members = state.tabs.filter((t) => labels.includes(t.label))
+				const tabs = state.tabs.filter((t) => !labels.includes(t.label))
+				tabs.splice(new_index, 0, ...members)
+				return { ...state, tabs }
+			})
+		})
+
+		await listen('tab_split_updated', (event) => {
+			const split = event.payload
+			if (split.window_id !== windowId) return
+			update((state) => {
+				const exists = state.splits.some((s) => s.id === split.id)
+				return {
+					...state,
+					splits: exists
+						? state.splits.map((s) => (s.id === split.id ? split : s))
+						: [...state.splits, split],
+				}
+			})
+		})
+
+		await listen('tab_split_removed', (event) => {
+			const { split_id } = event.payload
+			update((state) => ({
+				...state,
+				splits: state.splits.filter((s) => s.id !== split_id),
+			}))
+		})
+
 		await listen('open_in_new_tab', (event) => {
-			const url = event.payload
-			invoke('tab_create', { url })
+			const { url, window_id, opener, background } = event.payload
+			if (window_id !== windowId) return
+			invoke('tab_create', { url, opener, background })
 		})
 	}
 
@@ -106,8 +210,10 @@ function createTabStore() {
 			// Load existing tabs from backend
 			try {
 				const tabs = await invoke('tab_get_all')
+				const groups = await invoke('tab_group_get_all')
+				const splits = await invoke('tab_split_get_all')
 				const activeLabel = await invoke('tab_get_active')
-				set({ tabs, activeTabLabel: activeLabel })
+				set({ tabs, groups, splits, activeTabLabel: activeLabel })
 			} catch (e) {
 				console.error('Failed to load tabs:', e)
 			}
@@ -126,15 +232,17 @@ function createTabStore() {
 			}
 		},
 
-		async create(url) {
+		// Open a tab, in a container if `containerId` is given
+		async create(url, containerId = null) {
 			try {
-				const tab = await invoke('tab_create', { url: url || null })
+				const tab = await invoke('tab_create', { url: url || null, containerId })
 				// State will be updated by the tab_created event listener,
 				// but we also update optimistically here for responsiveness
 				update((state) => {
 					const exists = state.tabs.some((t) => t.label === tab.label)
 					if (exists) return { ...state, activeTabLabel: tab.label }
 					return {
+						...state,
 						tabs: [...state.tabs, tab],
 						activeTabLabel: tab.label,
 					}
@@ -153,6 +261,86 @@ function createTabStore() {
 			}
 		},
 
+		// Close several tabs in one go — `selector` is { kind: 'others' | 'to_right', label },
+		// { kind: 'domain', domain } or { kind: 'duplicates' }. Pinned tabs are kept.
+		async closeMany(selector) {
+			try {
+				return await invoke('tab_close_many', { selector })
+			} catch (e) {
+				console.error('Failed to close tabs:', e)
+			}
+		},
+
+		async reopenClosed() {
+			try {
+				// Backend emits tab_created/tab_reordered, so the store follows along
+				return await invoke('tab_reopen_closed')
+			} catch (e) {
+				console.error('Failed to reopen closed tab:', e)
+			}
+		},
+
+		async moveToWindow(label, windowId = null, index = null) {
+			try {
+				// Backend emits tab_moved_to_window, so both windows follow along
+				return await invoke('tab_move_to_window', { label, windowId, index })
+			} catch (e) {
+				console.error('Failed to move tab to window:', e)
+			}
+		},
+
+		async split(labels, ratios = null) {
+			try {
+				// Backend emits tab_split_updated and activates a pane
+				return await invoke('tab_split', { labels, ratios })
+			} catch (e) {
+				console.error('Failed to split tabs:', e)
+			}
+		},
+
+		async unsplit(label) {
+			try {
+				await invoke('tab_unsplit', { label })
+			} catch (e) {
+				console.error('Failed to unsplit tab:', e)
+			}
+		},
+
+		async setSplitRatios(splitId, ratios) {
+			try {
+				return await invoke('tab_split_set_ratios', { splitId, ratios })
+			} catch (e) {
+				console.error('Failed to resize split:', e)
+			}
+		},
+
+		// Recreate a crashed tab's webview at its last URL
+		async recover(label) {
+			try {
+				await invoke('tab_recover', { label })
+			} catch (e) {
+				console.error('Failed to recover tab:', e)
+			}
+		},
+
+		async setMuted(label, muted) {
+			try {
+				await invoke('tab_set_muted', { label, muted })
+			} catch (e) {
+				console.error('Failed to mute tab:', e)
+			}
+		},
+
+		// Zoom the active tab (or `label`); the level is remembered for the tab's site
+		async zoom(direction, label = null) {
+			const command = { in: 'tab_zoom_in', out: 'tab_zoom_out', reset: 'tab_zoom_reset' }[direction]
+			try {
+				return await invoke(command, { label })
+			} catch (e) {
+				console.error('Failed to zoom tab:', e)
+			}
+		},
+
 		async setActive(label) {
 			try {
 				// Optimistic update
@@ -163,6 +351,28 @@ function createTabStore() {
 			}
 		},
 
+		async search(query, limit = 20) {
+			try {
+				return await invoke('tab_search', { query, limit })
+			} catch (e) {
+				console.error('Failed to search tabs:', e)
+				return []
+			}
+		},
+
+		// Jump to a search result — focusing its window if it lives in another one
+		async switchTo(result) {
+			try {
+				await invoke('tab_set_active', { label: result.label })
+				if (result.window_id !== windowId) {
+					const target = await Window.getByLabel(result.window_id)
+					await target?.setFocus()
+				}
+			} catch (e) {
+				console.error('Failed to switch to tab:', e)
+			}
+		},
+
 		activateNext() {
 			const state = getState()
 			const idx = state.tabs.findIndex((t) => t.label === state.activeTabLabel)
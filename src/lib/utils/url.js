@@ -24,32 +24,6 @@ export const isAeroUrl = (url) => {
 	return /^aero:\/\//i.test(url)
 }
 
-/**
- * Normalise user input into a navigable URL
- * - If it looks like a URL, prepend https:// if needed
- * - Otherwise, turn it into a Google search
- */
-export const resolveInput = (input) => {
-	const trimmed = input.trim()
-	if (!trimmed) return null
-
-	// Preserve aero:// URLs as-is
-	if (/^aero:\/\//i.test(trimmed)) {
-		return trimmed
-	}
-
-	if (/^https?:\/\//i.test(trimmed)) {
-		return trimmed
-	}
-
-	if (isValidUrl(trimmed)) {
-		return `https://${trimmed}`
-	}
-
-	// It's a search query
-	return `https://www.google.com/search?q=${encodeURIComponent(trimmed)}`
-}
-
 /**
  * Extract a display-friendly version of a URL
  */
@@ -67,3 +41,17 @@ export const displayUrl = (url) => {
 		return url
 	}
 }
+
+/**
+ * The site a web page belongs to, for "close tabs from site": its host without "www."
+ * Null for internal and other non-web pages.
+ */
+export const siteHost = (url) => {
+	try {
+		const parsed = new URL(url)
+		if (parsed.protocol !== 'http:' && parsed.protocol !== 'https:') return null
+		return parsed.hostname.replace(/^www\./, '') || null
+	} catch {
+		return null
+	}
+}
@@ -1,5 +1,5 @@
 import { describe, test, expect } from 'vitest'
-import { isValidUrl, isAeroUrl, resolveInput, displayUrl } from './url.js'
+import { isValidUrl, isAeroUrl, displayUrl, siteHost } from './url.js'
 
 // ── isValidUrl ─────────────────────────────────────────
 
@@ -56,53 +56,6 @@ describe('isAeroUrl', () => {
 	})
 })
 
-// ── resolveInput ───────────────────────────────────────
-
-describe('resolveInput', () => {
-	test('preserves full http:// URLs', () => {
-		expect(resolveInput('http://example.com')).toBe('http://example.com')
-	})
-
-	test('preserves full https:// URLs', () => {
-		expect(resolveInput('https://github.com/foo')).toBe('https://github.com/foo')
-	})
-
-	test('preserves aero:// URLs', () => {
-		expect(resolveInput('aero://settings')).toBe('aero://settings')
-		expect(resolveInput('aero://history')).toBe('aero://history')
-	})
-
-	test('prepends https:// to bare domains', () => {
-		expect(resolveInput('google.com')).toBe('https://google.com')
-		expect(resolveInput('example.co.uk')).toBe('https://example.co.uk')
-	})
-
-	test('prepends https:// to localhost', () => {
-		expect(resolveInput('localhost:3000')).toBe('https://localhost:3000')
-	})
-
-	test('converts search queries to Google search', () => {
-		const result = resolveInput('how to cook pasta')
-		expect(result).toContain('google.com/search?q=')
-		expect(result).toContain('how%20to%20cook%20pasta')
-	})
-
-	test('trims whitespace', () => {
-		expect(resolveInput('  google.com  ')).toBe('https://google.com')
-	})
-
-	test('returns null for empty input', () => {
-		expect(resolveInput('')).toBeNull()
-		expect(resolveInput('   ')).toBeNull()
-	})
-
-	test('encodes special characters in search', () => {
-		const result = resolveInput('c++ tutorial')
-		expect(result).toContain('google.com/search?q=')
-		expect(result).toContain('c%2B%2B')
-	})
-})
-
 // ── displayUrl ─────────────────────────────────────────
 
 describe('displayUrl', () => {
@@ -131,3 +84,18 @@ describe('displayUrl', () => {
 		expect(displayUrl('aero://history')).toBe('aero://history')
 	})
 })
+
+// ── siteHost ───────────────────────────────────────────
+
+describe('siteHost', () => {
+	test('returns the host without www', () => {
+		expect(siteHost('https://www.example.com/a?b=1')).toBe('example.com')
+		expect(siteHost('http://docs.example.com')).toBe('docs.example.com')
+	})
+
+	test('returns null for non-web pages', () => {
+		expect(siteHost('aero://settings')).toBe(null)
+		expect(siteHost('about:blank')).toBe(null)
+		expect(siteHost('not a url')).toBe(null)
+	})
+})